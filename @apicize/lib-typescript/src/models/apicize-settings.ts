@@ -18,4 +18,11 @@ export interface ApicizeSettings {
     editorIndentSize: number
     editorDetectExistingIndent: boolean
     editorCheckJsSyntax: boolean
+    traceRetention: number
+    traceSpool: boolean
+    traceSpoolDays?: number
+    secretStorage?: 'file' | 'keyring' | 'encryptedFile'
+    encryptPrivateParameters?: boolean
+    canonicalWorkbookFormat?: boolean
+    splitMultilineStrings?: boolean
 }
@@ -140,7 +140,7 @@ export function useFileDragDrop() {
     return context;
 }
 
-export type DroppedFile = DroppedTextFile | DroppedBinaryFile
+export type DroppedFile = DroppedTextFile | DroppedBinaryFile | DroppedHarFile
 
 export interface DroppedTextFile {
     type: 'text'
@@ -153,3 +153,9 @@ export interface DroppedBinaryFile {
     data: Uint8Array
     extension: string
 }
+
+export interface DroppedHarFile {
+    type: 'har'
+    data: string
+    extension: string
+}
@@ -151,6 +151,66 @@ export const SettingsEditor = observer((props: { sx?: SxProps }) => {
                                 onChange={(e) => settings.setPkceListenerPort(parseInt(e.target.value))} />
                             <InputLabel>("0" to disable)</InputLabel>
                         </Stack>
+                        <Stack direction={'row'} spacing={'1em'} display='flex' alignItems='center' justifyContent='left'>
+                            <InputLabel id='trace-retention-label-id' sx={{ width: '12em' }}>Trace Events Retained:</InputLabel>
+                            <TextField type='number' slotProps={{ htmlInput: { min: 0, max: 1000000 } }}
+                                value={settings.traceRetention}
+                                size='small'
+                                title='Number of network trace events kept in memory'
+                                onChange={(e) => settings.setTraceRetention(parseInt(e.target.value))} />
+                        </Stack>
+                        <Stack direction={'row'} spacing={'1em'} display='flex' alignItems='center' justifyContent='left'>
+                            <InputLabel id='trace-spool-label-id' sx={{ width: '12em' }}>Save Trace to Disk:</InputLabel>
+                            <RadioGroup row value={settings.traceSpool} onChange={(e) => settings.setTraceSpool(e.target.value === 'true')}>
+                                <FormControlLabel value={true} control={<Radio />} label='Yes' title='Append network trace events to files in the settings directory' />
+                                <FormControlLabel value={false} control={<Radio />} label='No' title='Only keep network trace events in memory' />
+                            </RadioGroup>
+                        </Stack>
+                        {(
+                            settings.traceSpool
+                                ? <Stack direction={'row'} spacing={'1em'} display='flex' alignItems='center' justifyContent='left'>
+                                    <InputLabel id='trace-spool-days-label-id' sx={{ width: '12em' }}>Days of Trace Kept:</InputLabel>
+                                    <TextField type='number' slotProps={{ htmlInput: { min: 1, max: 3650 } }}
+                                        value={settings.traceSpoolDays}
+                                        size='small'
+                                        title='Number of days network trace files are kept on disk'
+                                        onChange={(e) => settings.setTraceSpoolDays(parseInt(e.target.value))} />
+                                </Stack>
+                                : null
+                        )}
+                        <Stack direction={'row'} spacing={'1em'} display='flex' alignItems='center' justifyContent='left'>
+                            <InputLabel id='secret-storage-label-id' sx={{ width: '12em' }}>Store Vault Secrets:</InputLabel>
+                            <RadioGroup row value={settings.secretStorage} onChange={(e) => settings.setSecretStorage(e.target.value as 'file' | 'keyring' | 'encryptedFile')}>
+                                <FormControlLabel value='file' control={<Radio />} label='Globals File' title='Save vault parameters to the global parameters file' />
+                                <FormControlLabel value='keyring' control={<Radio />} label='Keyring' title='Encrypt vault parameters and tokens using a key in the system keyring' />
+                                <FormControlLabel value='encryptedFile' control={<Radio />} label='Passphrase' title='Encrypt vault parameters and tokens using a passphrase' />
+                            </RadioGroup>
+                        </Stack>
+                        <Stack direction={'row'} spacing={'1em'} display='flex' alignItems='center' justifyContent='left'>
+                            <InputLabel id='encrypt-private-label-id' sx={{ width: '12em' }}>Encrypt Private Parameters:</InputLabel>
+                            <RadioGroup row value={settings.encryptPrivateParameters} onChange={(e) => settings.setEncryptPrivateParameters(e.target.value === 'true')}>
+                                <FormControlLabel value={true} control={<Radio />} label='Yes' title='Encrypt private parameter files with a passphrase' />
+                                <FormControlLabel value={false} control={<Radio />} label='No' title='Save private parameter files as plain text' />
+                            </RadioGroup>
+                        </Stack>
+                        <Stack direction={'row'} spacing={'1em'} display='flex' alignItems='center' justifyContent='left'>
+                            <InputLabel id='canonical-format-label-id' sx={{ width: '12em' }}>Canonical Workbook Format:</InputLabel>
+                            <RadioGroup row value={settings.canonicalWorkbookFormat} onChange={(e) => settings.setCanonicalWorkbookFormat(e.target.value === 'true')}>
+                                <FormControlLabel value={true} control={<Radio />} label='Yes' title='Save workbooks with sorted properties and fixed formatting to reduce differences between versions' />
+                                <FormControlLabel value={false} control={<Radio />} label='No' title='Save workbooks in the default format' />
+                            </RadioGroup>
+                        </Stack>
+                        {(
+                            settings.canonicalWorkbookFormat
+                                ? <Stack direction={'row'} spacing={'1em'} display='flex' alignItems='center' justifyContent='left'>
+                                    <InputLabel id='split-multiline-label-id' sx={{ width: '12em' }}>Split Multi-Line Text:</InputLabel>
+                                    <RadioGroup row value={settings.splitMultilineStrings} onChange={(e) => settings.setSplitMultilineStrings(e.target.value === 'true')}>
+                                        <FormControlLabel value={true} control={<Radio />} label='Yes' title='Save multi-line bodies and test scripts as lists of lines (earlier versions of Apicize and the standalone Apicize CLI will see them as empty)' />
+                                        <FormControlLabel value={false} control={<Radio />} label='No' title='Save multi-line bodies and test scripts as single strings' />
+                                    </RadioGroup>
+                                </Stack>
+                                : null
+                        )}
                         <Stack direction={'row'} spacing={'1em'} display='flex' alignItems='center' justifyContent='left'>
                             <InputLabel id='show_diag-info-label-id' sx={{ width: '12em' }}>Show Diagnostic Info:</InputLabel>
                             <RadioGroup row value={settings.showDiagnosticInfo} onChange={(e) => settings.setShowDiagnosticInfo(e.target.value === 'true')}>
@@ -27,6 +27,13 @@ export class EditableSettings {
     @observable accessor editorIndentSize = 3
     @observable accessor editorDetectExistingIndent = true
     @observable accessor editorCheckJsSyntax = true
+    @observable accessor traceRetention = 1000
+    @observable accessor traceSpool = false
+    @observable accessor traceSpoolDays = 7
+    @observable accessor secretStorage: 'file' | 'keyring' | 'encryptedFile' = 'file'
+    @observable accessor encryptPrivateParameters = false
+    @observable accessor canonicalWorkbookFormat = false
+    @observable accessor splitMultilineStrings = false
     @observable accessor readyToSave = true
 
     constructor(settings?: ApicizeSettings) {
@@ -52,6 +59,13 @@ export class EditableSettings {
         this.editorIndentSize = settings.editorIndentSize
         this.editorDetectExistingIndent = settings.editorDetectExistingIndent
         this.editorCheckJsSyntax = settings.editorCheckJsSyntax
+        this.traceRetention = settings.traceRetention ?? 1000
+        this.traceSpool = settings.traceSpool ?? false
+        this.traceSpoolDays = settings.traceSpoolDays ?? 7
+        this.secretStorage = settings.secretStorage ?? 'file'
+        this.encryptPrivateParameters = settings.encryptPrivateParameters ?? false
+        this.canonicalWorkbookFormat = settings.canonicalWorkbookFormat ?? false
+        this.splitMultilineStrings = settings.splitMultilineStrings ?? false
         this.readyToSave = false
         this.lastChangeCtrCheck = 0
         this.pendingChangeCtr = 0
@@ -217,6 +231,45 @@ export class EditableSettings {
         this.editorCheckJsSyntax = value
         this.incrementChangeCtr()
     }
+
+    @action setTraceRetention(value: number) {
+        if (this.traceRetention !== value) {
+            this.traceRetention = value
+            this.incrementChangeCtr(2000)
+        }
+    }
+
+    @action setTraceSpool(value: boolean) {
+        this.traceSpool = value
+        this.incrementChangeCtr()
+    }
+
+    @action setTraceSpoolDays(value: number) {
+        if (this.traceSpoolDays !== value) {
+            this.traceSpoolDays = value
+            this.incrementChangeCtr(2000)
+        }
+    }
+
+    @action setSecretStorage(value: 'file' | 'keyring' | 'encryptedFile') {
+        this.secretStorage = value
+        this.incrementChangeCtr()
+    }
+
+    @action setEncryptPrivateParameters(value: boolean) {
+        this.encryptPrivateParameters = value
+        this.incrementChangeCtr()
+    }
+
+    @action setCanonicalWorkbookFormat(value: boolean) {
+        this.canonicalWorkbookFormat = value
+        this.incrementChangeCtr()
+    }
+
+    @action setSplitMultilineStrings(value: boolean) {
+        this.splitMultilineStrings = value
+        this.incrementChangeCtr()
+    }
 }
 
 export interface StorageInformation {
@@ -1,25 +1,39 @@
 export interface ReqwestEventConnect {
     event: 'Connect'
+    sequence: number
     timestamp: string
+    timestampMs: number
     host: string
+    runId?: string
+    requestOrGroupId?: string
 }
 
 export interface ReqwestEventRead {
     event: 'Read'
+    sequence: number
     timestamp: string
+    timestampMs: number
     id: string
     data: string
+    host?: string
+    runId?: string
+    requestOrGroupId?: string
 }
 
 export interface ReqwestEventWrite {
     event: 'Write'
+    sequence: number
     timestamp: string
+    timestampMs: number
     id: string
     data: string
+    host?: string
+    runId?: string
+    requestOrGroupId?: string
 }
 
 export interface ReqwestClear {
     event: 'Clear'
 }
 
-export type ReqwestEvent = ReqwestEventConnect | ReqwestEventRead | ReqwestEventWrite | ReqwestClear
\ No newline at end of file
+export type ReqwestEvent = ReqwestEventConnect | ReqwestEventRead | ReqwestEventWrite | ReqwestClear
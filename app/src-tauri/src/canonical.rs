@@ -0,0 +1,251 @@
+//! Canonical workbook format submodule
+//!
+//! This submodule rewrites saved workbook files in a deterministic, diff-friendly format:
+//! object keys are sorted, entities are written in navigation order, indentation is fixed
+//! and, optionally, multi-line request bodies and test scripts are split into arrays of lines.
+//! Split strings are joined back when workbooks are opened, regardless of the save format
+
+use apicize_lib::{IndexedEntities, RequestEntry};
+use serde::Serialize;
+use serde_json::{ser::PrettyFormatter, Map, Value};
+
+use crate::error::ApicizeAppError;
+
+/// Request properties (as JSON pointers) split into arrays of lines when multi-line
+const SPLIT_PROPERTIES: [&str; 3] = ["/test", "/body/data", "/body/formatted"];
+
+/// Suffix of the property holding a split property's lines
+const LINES_SUFFIX: &str = "Lines";
+
+/// How workbook files are formatted when saved
+#[derive(Clone, Copy, Default)]
+pub struct SaveFormat {
+    /// Write workbook files in canonical format
+    pub canonical: bool,
+    /// Split multi-line bodies and test scripts into arrays of lines (canonical format only)
+    pub split_multiline_strings: bool,
+}
+
+impl SaveFormat {
+    /// Return true if multi-line strings should be split
+    pub fn split(&self) -> bool {
+        self.canonical && self.split_multiline_strings
+    }
+
+    /// Serialize a workbook file's contents in this format
+    pub fn format_contents(&self, value: &Value) -> Result<String, ApicizeAppError> {
+        if !self.canonical {
+            return Ok(serde_json::to_string_pretty(value)?);
+        }
+
+        let formatter = PrettyFormatter::with_indent(b"    ");
+        let mut contents = Vec::new();
+        let mut serializer = serde_json::Serializer::with_formatter(&mut contents, formatter);
+        sort_keys(value).serialize(&mut serializer)?;
+        contents.push(b'\n');
+        String::from_utf8(contents).map_err(|err| {
+            ApicizeAppError::InvalidOperation(format!("unable to format workbook: {err}"))
+        })
+    }
+}
+
+/// Split multi-line strings of the requests and groups in a workbook's "requests" tree
+pub fn split_request_lines(requests: &mut Value) {
+    let mut pending = vec![requests];
+    while let Some(value) = pending.pop() {
+        match value {
+            Value::Array(entries) => pending.extend(entries.iter_mut()),
+            Value::Object(_) => {
+                split_entity_lines(value);
+                if let Some(children) = value.get_mut("children") {
+                    pending.push(children);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Split a request's multi-line strings into arrays of lines
+pub fn split_entity_lines(entity: &mut Value) {
+    for pointer in SPLIT_PROPERTIES {
+        let (parent, key) = pointer.rsplit_once('/').unwrap_or_default();
+        let Some(Value::Object(properties)) = entity.pointer_mut(parent) else {
+            continue;
+        };
+        let lines = match properties.get(key) {
+            Some(Value::String(text)) if text.contains('\n') => text
+                .split('\n')
+                .map(|line| Value::String(line.to_string()))
+                .collect::<Vec<Value>>(),
+            _ => continue,
+        };
+        properties.insert(key.to_string(), Value::String(String::new()));
+        properties.insert(format!("{key}{LINES_SUFFIX}"), Value::Array(lines));
+    }
+}
+
+/// Join a request's split strings, returning the pointers of the joined properties
+pub fn join_entity_lines(entity: &mut Value) -> Vec<&'static str> {
+    let mut joined = Vec::new();
+    for pointer in SPLIT_PROPERTIES {
+        let (parent, key) = pointer.rsplit_once('/').unwrap_or_default();
+        let Some(Value::Object(properties)) = entity.pointer_mut(parent) else {
+            continue;
+        };
+        let Some(Value::Array(lines)) = properties.remove(&format!("{key}{LINES_SUFFIX}")) else {
+            continue;
+        };
+        let text = lines
+            .iter()
+            .map(|line| line.as_str().unwrap_or_default())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        properties.insert(key.to_string(), Value::String(text));
+        joined.push(pointer);
+    }
+    joined
+}
+
+/// Update opened requests with strings split in the workbook's "requests" tree, which
+/// were loaded with empty values
+pub fn join_request_lines(
+    requests: &Value,
+    entities: &mut IndexedEntities<RequestEntry>,
+) -> Result<(), ApicizeAppError> {
+    let mut pending = vec![requests];
+    while let Some(value) = pending.pop() {
+        match value {
+            Value::Array(entries) => pending.extend(entries.iter()),
+            Value::Object(properties) => {
+                if let Some(children) = properties.get("children") {
+                    pending.push(children);
+                }
+
+                let Some(entity) = properties
+                    .get("id")
+                    .and_then(|id| id.as_str())
+                    .and_then(|id| entities.entities.get_mut(id))
+                else {
+                    continue;
+                };
+                let mut saved = Value::Object(
+                    properties
+                        .iter()
+                        .filter(|(key, _)| *key != "children")
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect(),
+                );
+                let joined = join_entity_lines(&mut saved);
+                if joined.is_empty() {
+                    continue;
+                }
+
+                let mut updated = serde_json::to_value(&*entity)?;
+                for pointer in joined {
+                    let (parent, key) = pointer.rsplit_once('/').unwrap_or_default();
+                    if let (Some(Value::Object(updated_properties)), Some(text)) =
+                        (updated.pointer_mut(parent), saved.pointer(pointer))
+                    {
+                        updated_properties.insert(key.to_string(), text.clone());
+                    }
+                }
+                *entity = serde_json::from_value(updated)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Return a copy of the value with object keys sorted; array order is kept since it
+/// reflects navigation order
+fn sort_keys(value: &Value) -> Value {
+    match value {
+        Value::Object(properties) => {
+            let mut keys = properties.keys().collect::<Vec<&String>>();
+            keys.sort();
+            Value::Object(
+                keys.into_iter()
+                    .map(|key| (key.clone(), sort_keys(&properties[key])))
+                    .collect::<Map<String, Value>>(),
+            )
+        }
+        Value::Array(entries) => Value::Array(entries.iter().map(sort_keys).collect()),
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apicize_lib::{Request, RequestEntry, Workspace};
+    use serde_json::{json, Value};
+
+    use super::{join_entity_lines, join_request_lines, split_entity_lines, split_request_lines};
+
+    const TEST_TEXT: &str = "describe('status', () => {\r\n   it('equals 200', () => {})\r\n})\r\n";
+    const BODY_TEXT: &str = "line 1\nline 2\n";
+
+    fn request_value(test: &str, body: &str) -> Value {
+        json!({
+            "id": "r1",
+            "name": "Request",
+            "url": "http://localhost",
+            "test": test,
+            "body": { "type": "Text", "data": body },
+        })
+    }
+
+    #[test]
+    fn split_entity_round_trips() {
+        let original = request_value(TEST_TEXT, BODY_TEXT);
+        let mut entity = original.clone();
+        split_entity_lines(&mut entity);
+        assert_eq!(entity["test"], json!(""));
+        assert_eq!(entity["body"]["data"], json!(""));
+        assert_eq!(
+            entity["testLines"],
+            json!([
+                "describe('status', () => {\r",
+                "   it('equals 200', () => {})\r",
+                "})\r",
+                ""
+            ])
+        );
+        assert_eq!(entity["body"]["dataLines"], json!(["line 1", "line 2", ""]));
+
+        assert_eq!(join_entity_lines(&mut entity), vec!["/test", "/body/data"]);
+        assert_eq!(entity, original);
+    }
+
+    #[test]
+    fn single_line_text_is_not_split() {
+        let original = request_value("no newline", "");
+        let mut entity = original.clone();
+        split_entity_lines(&mut entity);
+        assert_eq!(entity, original);
+        assert!(join_entity_lines(&mut entity).is_empty());
+    }
+
+    #[test]
+    fn opened_requests_are_joined() {
+        let original =
+            serde_json::from_value::<Request>(request_value(TEST_TEXT, BODY_TEXT)).unwrap();
+
+        // Saved split, and opened with the split properties empty
+        let mut saved = json!([serde_json::to_value(&original).unwrap()]);
+        split_request_lines(&mut saved);
+        let opened = serde_json::from_value::<Request>(request_value("", "")).unwrap();
+        let mut workspace = Workspace::new().unwrap();
+        workspace
+            .requests
+            .add_entity(RequestEntry::Request(opened), None, None)
+            .unwrap();
+
+        join_request_lines(&saved, &mut workspace.requests).unwrap();
+        assert_eq!(
+            serde_json::to_value(&workspace.requests.entities["r1"]).unwrap(),
+            serde_json::to_value(RequestEntry::Request(original)).unwrap()
+        );
+    }
+}
@@ -0,0 +1,519 @@
+//! Command line runner submodule
+//!
+//! This submodule runs workbook requests headlessly (without starting the GUI),
+//! i.e. `apicize run <workbook> --request <id|name> --scenario <name> --report junit`
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use apicize_lib::{
+    ApicizeRunner, ExecutionReportFormat, ExecutionResultSummary, Identifiable, RequestEntry,
+    Selection, TestRunnerContext, Workspace,
+};
+use serde_json::Value;
+
+use crate::{
+    includes,
+    private_params::{self, PRIVATE_SECTION},
+    secrets::{self, EncryptedFileSecretStore, SecretStorage},
+    settings::ApicizeSettings,
+};
+
+/// Exit code when all requests and tests succeed
+pub const EXIT_SUCCESS: i32 = 0;
+/// Exit code when any request fails or any test does not pass
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code when a request errors, arguments are invalid or the workbook cannot be opened
+pub const EXIT_ERROR: i32 = 2;
+
+/// Environment variable holding the passphrase for a passphrase-encrypted secret store
+const VAULT_PASSPHRASE_VARIABLE: &str = "APICIZE_VAULT_PASSPHRASE";
+/// Environment variable holding the passphrase for encrypted private parameters
+const PRIVATE_PASSPHRASE_VARIABLE: &str = "APICIZE_PRIVATE_PASSPHRASE";
+
+const USAGE: &str = "Usage: apicize run <workbook> [options]
+       apicize --run <workbook> [options]
+
+Options:
+  -r, --request <id|name>    Request or group to run (default: all top-level requests)
+  -s, --scenario <name>      Scenario to use as the default scenario
+  -f, --report <format>      Report format: json, csv or junit
+  -o, --output <file>        Write the report to a file instead of standard output
+  -h, --help                 Display this message
+
+Environment:
+  APICIZE_VAULT_PASSPHRASE   Passphrase for vault parameters stored with a passphrase
+  APICIZE_PRIVATE_PASSPHRASE Passphrase for the workbook's encrypted private parameters";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CliReportFormat {
+    Json,
+    Csv,
+    JUnit,
+}
+
+pub struct CliRunOptions {
+    pub workbook: String,
+    pub request: Option<String>,
+    pub scenario: Option<String>,
+    pub report: Option<CliReportFormat>,
+    pub output: Option<String>,
+}
+
+/// Returns true if the process arguments request headless CLI mode, via the "run" subcommand
+/// or its "--run" alias.  A lone "run" argument naming an existing file is a workbook to open
+/// in the GUI rather than the subcommand
+pub fn is_cli_mode(args: &[String]) -> bool {
+    match args.get(1).map(|a| a.as_str()) {
+        Some("--run") => true,
+        Some("run") => args.len() > 2 || !Path::new("run").is_file(),
+        _ => false,
+    }
+}
+
+/// Attach to the parent process's console, since release builds on Windows use the GUI
+/// subsystem and would otherwise discard all output
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // SAFETY: AttachConsole has no preconditions, failure (e.g. no parent console) is harmless
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+/// Parse the arguments following the "run" subcommand (or "--run" flag)
+pub fn parse_args(args: &[String]) -> Result<CliRunOptions, String> {
+    let mut workbook: Option<String> = None;
+    let mut request: Option<String> = None;
+    let mut scenario: Option<String> = None;
+    let mut report: Option<CliReportFormat> = None;
+    let mut output: Option<String> = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value_for = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value for {name}"))
+        };
+        match arg.as_str() {
+            "-r" | "--request" => request = Some(value_for(arg)?),
+            "-s" | "--scenario" => scenario = Some(value_for(arg)?),
+            "-o" | "--output" => output = Some(value_for(arg)?),
+            "-f" | "--report" => {
+                report = Some(match value_for(arg)?.to_ascii_lowercase().as_str() {
+                    "json" => CliReportFormat::Json,
+                    "csv" => CliReportFormat::Csv,
+                    "junit" => CliReportFormat::JUnit,
+                    other => return Err(format!("Unsupported report format \"{other}\"")),
+                })
+            }
+            "-h" | "--help" => return Err(String::default()),
+            other if other.starts_with('-') => return Err(format!("Unknown option {other}")),
+            other => {
+                if workbook.is_some() {
+                    return Err(format!("Unexpected argument {other}"));
+                }
+                workbook = Some(other.to_string());
+            }
+        }
+    }
+
+    match workbook {
+        Some(workbook) => Ok(CliRunOptions {
+            workbook,
+            request,
+            scenario,
+            report,
+            output,
+        }),
+        None => Err("Workbook file name is required".to_string()),
+    }
+}
+
+/// Run the CLI with the process arguments, returning the exit code
+pub async fn run_from_args(args: &[String]) -> i32 {
+    attach_console();
+    match parse_args(&args[2..]) {
+        Ok(options) => match run(options).await {
+            Ok(exit_code) => exit_code,
+            Err(err) => {
+                eprintln!("{err}");
+                EXIT_ERROR
+            }
+        },
+        Err(err) => {
+            if !err.is_empty() {
+                eprintln!("{err}\n");
+            }
+            eprintln!("{USAGE}");
+            EXIT_ERROR
+        }
+    }
+}
+
+/// Open the workbook, execute the selected requests and output a report
+pub async fn run(options: CliRunOptions) -> Result<i32, String> {
+    let workbook_path = PathBuf::from(&options.workbook);
+    let (mut workspace, _) =
+        includes::open_workbook(&workbook_path).map_err(|err| format!("{err}"))?;
+    add_secret_parameters(&mut workspace, &workbook_path)?;
+
+    let request_ids = match &options.request {
+        Some(id_or_name) => vec![find_request_id(&workspace, id_or_name)?],
+        None => workspace.requests.top_level_ids.clone(),
+    };
+
+    if request_ids.is_empty() {
+        return Err(format!(
+            "{} does not contain any requests",
+            options.workbook
+        ));
+    }
+
+    if let Some(scenario_name) = &options.scenario {
+        select_scenario(&mut workspace, scenario_name, &request_ids)?;
+    }
+
+    let allowed_data_path = std::path::absolute(&workbook_path)
+        .ok()
+        .and_then(|p| p.parent().map(|p| p.to_path_buf()));
+
+    let runner = Arc::new(TestRunnerContext::new(
+        workspace,
+        None,
+        false,
+        &allowed_data_path,
+        false,
+    ));
+
+    let mut all_summaries: Vec<Vec<ExecutionResultSummary>> = vec![];
+    let mut exit_code = EXIT_SUCCESS;
+
+    for response in runner.run(request_ids).await {
+        match response {
+            Ok(result) => {
+                let (summaries, _) = result.assemble_results(&runner);
+                if let Some(code) = summarize(&summaries) {
+                    exit_code = exit_code.max(code);
+                }
+                all_summaries.push(summaries);
+            }
+            Err(err) => {
+                eprintln!("{err}");
+                exit_code = EXIT_ERROR;
+            }
+        }
+    }
+
+    if let Some(format) = options.report {
+        let report = generate_report(&all_summaries, format)?;
+        match &options.output {
+            Some(file_name) => fs::write(file_name, report)
+                .map_err(|err| format!("Unable to write {file_name}: {err}"))?,
+            None => println!("{report}"),
+        }
+    }
+
+    Ok(exit_code)
+}
+
+/// Add vault parameters held in the secret store and encrypted private parameters, failing
+/// if they cannot be loaded rather than running with missing values
+fn add_secret_parameters(workspace: &mut Workspace, workbook_path: &Path) -> Result<(), String> {
+    let storage = ApicizeSettings::open()
+        .map(|settings| settings.data.secret_storage)
+        .unwrap_or_default();
+    let store = match storage {
+        SecretStorage::File => None,
+        SecretStorage::Keyring => Some(
+            EncryptedFileSecretStore::open_with_keyring()
+                .map_err(|err| format!("Unable to open vault parameters in keyring: {err}"))?,
+        ),
+        SecretStorage::EncryptedFile => match env::var(VAULT_PASSPHRASE_VARIABLE) {
+            // A missing store is only read as empty here, so no confirmation is needed
+            Ok(passphrase) => Some(
+                EncryptedFileSecretStore::open_with_passphrase(&passphrase, Some(&passphrase))
+                    .map_err(|err| format!("Unable to open vault parameters: {err}"))?,
+            ),
+            Err(_) if EncryptedFileSecretStore::get_passphrase_filename().is_file() => {
+                return Err(format!(
+                    "Vault parameters are encrypted, set {VAULT_PASSPHRASE_VARIABLE} to use them"
+                ));
+            }
+            Err(_) => None,
+        },
+    };
+    if let Some(store) = store {
+        secrets::restore_vault(&store, workspace)
+            .map_err(|err| format!("Unable to load vault parameters: {err}"))?;
+    }
+
+    if private_params::is_encrypted(workbook_path) {
+        let passphrase = env::var(PRIVATE_PASSPHRASE_VARIABLE).map_err(|_| {
+            format!(
+                "Private parameters are encrypted, set {PRIVATE_PASSPHRASE_VARIABLE} to use them"
+            )
+        })?;
+        if let Some(parameters) = private_params::open(workbook_path, &passphrase)
+            .map_err(|err| format!("Unable to open private parameters: {err}"))?
+        {
+            parameters
+                .add_to(workspace, PRIVATE_SECTION)
+                .map_err(|err| format!("Unable to load private parameters: {err}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Locate a request or group by ID, key or name
+fn find_request_id(workspace: &Workspace, id_or_name: &str) -> Result<String, String> {
+    if workspace.requests.entities.contains_key(id_or_name) {
+        return Ok(id_or_name.to_string());
+    }
+
+    let matches = workspace
+        .requests
+        .entities
+        .values()
+        .filter(|entry| {
+            let key = match entry {
+                RequestEntry::Request(request) => request.key.as_deref(),
+                RequestEntry::Group(group) => group.key.as_deref(),
+            };
+            entry.get_name() == id_or_name || key == Some(id_or_name)
+        })
+        .map(|entry| entry.get_id().to_string())
+        .collect::<Vec<String>>();
+
+    match matches.len() {
+        1 => Ok(matches[0].clone()),
+        0 => Err(format!("Request \"{id_or_name}\" not found")),
+        _ => Err(format!(
+            "Request name \"{id_or_name}\" is ambiguous, specify the ID instead"
+        )),
+    }
+}
+
+/// Use the named scenario as the workbook default and for the requests being run
+fn select_scenario(
+    workspace: &mut Workspace,
+    scenario_name: &str,
+    request_ids: &[String],
+) -> Result<(), String> {
+    let selection = match workspace
+        .scenarios
+        .entities
+        .values()
+        .find(|s| s.name == scenario_name || s.id == scenario_name)
+    {
+        Some(scenario) => Selection {
+            id: scenario.id.clone(),
+            name: scenario.name.clone(),
+        },
+        None => return Err(format!("Scenario \"{scenario_name}\" not found")),
+    };
+
+    for id in request_ids {
+        if let Some(entry) = workspace.requests.entities.get_mut(id) {
+            if let Some(selected) = entry.selected_scenario_as_mut() {
+                *selected = selection.clone();
+            }
+        }
+    }
+    workspace.defaults.selected_scenario = Some(selection);
+    Ok(())
+}
+
+/// Write a summary of results to standard error, returning the resulting exit code
+fn summarize(summaries: &[ExecutionResultSummary]) -> Option<i32> {
+    let values = summaries
+        .iter()
+        .filter_map(|s| serde_json::to_value(s).ok())
+        .collect::<Vec<Value>>();
+    let root = values.first()?;
+
+    let (passed, failed) = values
+        .iter()
+        .filter_map(|v| v.get("testResults").and_then(|t| t.as_array()))
+        .flatten()
+        .fold((0, 0), |(passed, failed), test| {
+            if test.get("success").and_then(|s| s.as_bool()) == Some(true) {
+                (passed + 1, failed)
+            } else {
+                (passed, failed + 1)
+            }
+        });
+
+    let success = root
+        .get("success")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let name = root
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+
+    eprintln!("{name}: {success} ({passed} test(s) passed, {failed} test(s) failed)");
+
+    Some(match success {
+        "SUCCESS" => EXIT_SUCCESS,
+        "FAILURE" => EXIT_FAILURE,
+        _ => EXIT_ERROR,
+    })
+}
+
+fn generate_report(
+    all_summaries: &[Vec<ExecutionResultSummary>],
+    format: CliReportFormat,
+) -> Result<String, String> {
+    let lib_format = match format {
+        CliReportFormat::Json => ExecutionReportFormat::JSON,
+        CliReportFormat::Csv => ExecutionReportFormat::CSV,
+        CliReportFormat::JUnit => return generate_junit_report(all_summaries),
+    };
+
+    let reports = all_summaries
+        .iter()
+        .map(|summaries| Workspace::geneate_report(0, summaries, lib_format.clone()))
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|err| format!("{err}"))?;
+
+    if format == CliReportFormat::Json && reports.len() > 1 {
+        // Combine individual JSON reports into a single array
+        let values = reports
+            .iter()
+            .map(|r| serde_json::from_str::<Value>(r))
+            .collect::<Result<Vec<Value>, _>>()
+            .map_err(|err| format!("{err}"))?;
+        serde_json::to_string_pretty(&values).map_err(|err| format!("{err}"))
+    } else {
+        Ok(reports.join("\n"))
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Generate a JUnit XML report, with a test suite for each executed request
+fn generate_junit_report(all_summaries: &[Vec<ExecutionResultSummary>]) -> Result<String, String> {
+    let mut suites = String::new();
+    let mut total_tests = 0;
+    let mut total_failures = 0;
+    let mut total_errors = 0;
+
+    for summaries in all_summaries {
+        for summary in summaries {
+            let summary = serde_json::to_value(summary).map_err(|err| format!("{err}"))?;
+            let error = summary.get("error").filter(|e| !e.is_null());
+            let tests = summary
+                .get("testResults")
+                .and_then(|t| t.as_array())
+                .cloned()
+                .unwrap_or_default();
+
+            // Groups without errors do not have anything to report on their own
+            if tests.is_empty() && error.is_none() {
+                continue;
+            }
+
+            let name = summary
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or_default();
+            let seconds = summary
+                .get("duration")
+                .and_then(|d| d.as_f64())
+                .unwrap_or(0.0)
+                / 1000.0;
+
+            let mut cases = String::new();
+            let mut failures = 0;
+            let mut errors = 0;
+
+            if let Some(error) = error {
+                errors += 1;
+                let description = error
+                    .get("description")
+                    .and_then(|d| d.as_str())
+                    .unwrap_or_default();
+                cases.push_str(&format!(
+                    "    <testcase classname=\"{0}\" name=\"{0}\">\n      <error message=\"{1}\"/>\n    </testcase>\n",
+                    escape_xml(name),
+                    escape_xml(description)
+                ));
+            }
+
+            for test in &tests {
+                let test_name = test
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .unwrap_or_default();
+                let success = test
+                    .get("success")
+                    .and_then(|s| s.as_bool())
+                    .unwrap_or(false);
+                if success {
+                    cases.push_str(&format!(
+                        "    <testcase classname=\"{}\" name=\"{}\"/>\n",
+                        escape_xml(name),
+                        escape_xml(test_name)
+                    ));
+                } else {
+                    failures += 1;
+                    let message = test
+                        .get("error")
+                        .and_then(|e| e.as_str())
+                        .unwrap_or("failed");
+                    cases.push_str(&format!(
+                        "    <testcase classname=\"{}\" name=\"{}\">\n      <failure message=\"{}\"/>\n    </testcase>\n",
+                        escape_xml(name),
+                        escape_xml(test_name),
+                        escape_xml(message)
+                    ));
+                }
+            }
+
+            let count = tests.len() + usize::from(error.is_some());
+            total_tests += count;
+            total_failures += failures;
+            total_errors += errors;
+
+            suites.push_str(&format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n{}  </testsuite>\n",
+                escape_xml(name),
+                count,
+                failures,
+                errors,
+                seconds,
+                cases
+            ));
+        }
+    }
+
+    Ok(format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites tests=\"{total_tests}\" failures=\"{total_failures}\" errors=\"{total_errors}\">\n{suites}</testsuites>"
+    ))
+}
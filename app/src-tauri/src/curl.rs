@@ -0,0 +1,436 @@
+//! curl submodule
+//!
+//! This submodule parses curl command lines (i.e. from browser devtools "copy as cURL")
+//! into requests
+
+use apicize_lib::{Request, RequestBody, RequestMethod};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    error::ApicizeAppError,
+    import::{self, encode_query_component},
+};
+
+/// Request parsed from a curl command, along with settings that are stored as
+/// separate workspace entities
+pub struct ParsedCurl {
+    pub request: Request,
+    /// Username and password for basic authentication
+    pub basic_auth: Option<(String, String)>,
+    pub proxy: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Request created from a curl command, along with any options that were not imported
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CurlRequestResult {
+    pub id: String,
+    pub warnings: Vec<String>,
+}
+
+/// Options that do not affect the request and take no argument
+const IGNORED_FLAGS: [&str; 22] = [
+    "-s",
+    "--silent",
+    "-S",
+    "--show-error",
+    "-v",
+    "--verbose",
+    "-i",
+    "--include",
+    "-L",
+    "--location",
+    "--compressed",
+    "-f",
+    "--fail",
+    "-g",
+    "--globoff",
+    "-#",
+    "--progress-bar",
+    "--http1.1",
+    "--http2",
+    "--http2-prior-knowledge",
+    "-N",
+    "--no-buffer",
+];
+
+/// Options that do not affect the request and take an argument
+const IGNORED_OPTIONS: [&str; 6] = [
+    "-o",
+    "--output",
+    "--connect-timeout",
+    "-w",
+    "--write-out",
+    "--retry",
+];
+
+/// Parse a curl command line
+pub fn parse(command: &str) -> Result<ParsedCurl, ApicizeAppError> {
+    let args = split_arguments(command)?;
+    let mut args = args.into_iter().peekable();
+    if args.peek().map(|a| a.as_str()) != Some("curl") {
+        return Err(ApicizeAppError::ImportError(
+            "text is not a curl command".to_string(),
+        ));
+    }
+    args.next();
+
+    let mut warnings: Vec<String> = vec![];
+    let mut url: Option<String> = None;
+    let mut method: Option<String> = None;
+    let mut headers: Vec<(String, String)> = vec![];
+    let mut data: Vec<String> = vec![];
+    let mut form: Vec<(String, String)> = vec![];
+    let mut is_json = false;
+    let mut use_get = false;
+    let mut basic_auth: Option<(String, String)> = None;
+    let mut proxy: Option<String> = None;
+    let mut accept_invalid_certs = false;
+    let mut timeout: Option<u32> = None;
+
+    while let Some(arg) = args.next() {
+        // Support both "--option value" and "--option=value"
+        let (option, inline_value) = match arg.split_once('=') {
+            Some((option, value)) if arg.starts_with("--") => {
+                (option.to_string(), Some(value.to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        let mut value = |option: &str| match inline_value.clone().or_else(|| args.next()) {
+            Some(value) => Ok(value),
+            None => Err(ApicizeAppError::ImportError(format!(
+                "curl option {option} requires a value"
+            ))),
+        };
+
+        match option.as_str() {
+            "-X" | "--request" => method = Some(value(&option)?.to_uppercase()),
+            "-H" | "--header" => {
+                let header = value(&option)?;
+                match header.split_once(':') {
+                    Some((name, value)) => {
+                        headers.push((name.trim().to_string(), value.trim().to_string()))
+                    }
+                    None => warnings.push(format!("Header \"{header}\" is not valid")),
+                }
+            }
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii" => {
+                let text = value(&option)?;
+                match text.strip_prefix('@') {
+                    Some(file_name) if option != "--data-raw" => {
+                        warnings.push(format!("Data from file \"{file_name}\" was not imported"))
+                    }
+                    _ => data.push(text),
+                }
+            }
+            "--data-urlencode" => {
+                let text = value(&option)?;
+                data.push(match text.split_once('=') {
+                    Some((name, value)) => format!("{name}={}", encode_query_component(value)),
+                    None => encode_query_component(&text),
+                });
+            }
+            "--json" => {
+                data.push(value(&option)?);
+                is_json = true;
+            }
+            "-F" | "--form" | "--form-string" => {
+                let field = value(&option)?;
+                match field.split_once('=') {
+                    Some((name, value)) if option == "--form-string" || !value.starts_with('@') => {
+                        form.push((name.to_string(), value.to_string()))
+                    }
+                    _ => warnings.push(format!("Form field \"{field}\" was not imported")),
+                }
+            }
+            "-u" | "--user" => {
+                let credentials = value(&option)?;
+                let (username, password) = credentials
+                    .split_once(':')
+                    .unwrap_or((credentials.as_str(), ""));
+                basic_auth = Some((username.to_string(), password.to_string()));
+            }
+            "-b" | "--cookie" => headers.push(("Cookie".to_string(), value(&option)?)),
+            "-A" | "--user-agent" => headers.push(("User-Agent".to_string(), value(&option)?)),
+            "-e" | "--referer" => headers.push(("Referer".to_string(), value(&option)?)),
+            "-x" | "--proxy" => proxy = Some(value(&option)?),
+            "-k" | "--insecure" => accept_invalid_certs = true,
+            "-G" | "--get" => use_get = true,
+            "-I" | "--head" => method = Some("HEAD".to_string()),
+            "-m" | "--max-time" => {
+                let seconds = value(&option)?;
+                match seconds.parse::<f64>() {
+                    Ok(seconds) => timeout = Some((seconds * 1000.0) as u32),
+                    Err(_) => warnings.push(format!("Timeout \"{seconds}\" is not valid")),
+                }
+            }
+            "--url" => url = Some(value(&option)?),
+            option if IGNORED_FLAGS.contains(&option) => {}
+            option if IGNORED_OPTIONS.contains(&option) => {
+                value(option)?;
+            }
+            option if option.starts_with('-') => {
+                warnings.push(format!("curl option {option} was not imported"))
+            }
+            _ if url.is_none() => url = Some(arg),
+            _ => warnings.push(format!("Additional URL \"{arg}\" was not imported")),
+        }
+    }
+
+    let Some(url) = url else {
+        return Err(ApicizeAppError::ImportError(
+            "curl command does not include a URL".to_string(),
+        ));
+    };
+
+    let (url, query_string) = url.split_once('?').unwrap_or((url.as_str(), ""));
+    let mut query_string_params = parse_query(query_string);
+
+    // With -G, data is sent as query string parameters
+    if use_get && !data.is_empty() {
+        query_string_params.extend(parse_query(&data.join("&")));
+        data.clear();
+    }
+
+    let method = method.unwrap_or_else(|| {
+        if use_get || (data.is_empty() && form.is_empty()) {
+            "GET"
+        } else {
+            "POST"
+        }
+        .to_string()
+    });
+
+    let mut request = Request::default();
+    request.name = format!("{method} {url}");
+    request.url = url.to_string();
+    request.method = serde_json::from_value::<RequestMethod>(json!(method)).ok();
+    if request.method.is_none() {
+        warnings.push(format!("{method} method is not supported"));
+    }
+    request.accept_invalid_certs = accept_invalid_certs;
+    request.timeout = timeout;
+
+    let content_type = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.to_ascii_lowercase());
+
+    let body = if !form.is_empty() {
+        warnings.push("Multipart form data was imported as a URL encoded form".to_string());
+        headers.retain(|(name, _)| !name.eq_ignore_ascii_case("content-type"));
+        Some(json!({ "type": "Form", "data": to_pairs(&form) }))
+    } else if !data.is_empty() {
+        if is_json {
+            if content_type.is_none() {
+                headers.push(("Content-Type".to_string(), "application/json".to_string()));
+            }
+            if !headers
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case("accept"))
+            {
+                headers.push(("Accept".to_string(), "application/json".to_string()));
+            }
+        }
+        let text = data.join(if is_json { "" } else { "&" });
+        let is_json = is_json || content_type.as_deref().is_some_and(|t| t.contains("json"));
+        Some(match content_type.as_deref() {
+            _ if is_json => match serde_json::from_str::<Value>(&text) {
+                Ok(data) => json!({ "type": "JSON", "data": data }),
+                Err(_) => json!({ "type": "Text", "data": text }),
+            },
+            Some(t) if t.contains("xml") => json!({ "type": "XML", "data": text }),
+            // curl sends data as a URL encoded form unless told otherwise
+            Some(t) if !t.starts_with("application/x-www-form-urlencoded") => {
+                json!({ "type": "Text", "data": text })
+            }
+            _ => json!({ "type": "Form", "data": to_pairs(&parse_query(&text)) }),
+        })
+    } else {
+        None
+    };
+
+    // curl includes a URL encoded form content type by default when sending data
+    if body.as_ref().is_some_and(|b| b["type"] == "Form")
+        && !headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+    {
+        headers.push((
+            "Content-Type".to_string(),
+            "application/x-www-form-urlencoded".to_string(),
+        ));
+    }
+
+    if let Some(body) = body {
+        match serde_json::from_value::<RequestBody>(body) {
+            Ok(body) => request.body = Some(body),
+            Err(err) => warnings.push(format!("Unable to import body: {err}")),
+        }
+    }
+
+    request.headers = import::to_name_value_pairs(to_pairs(&headers));
+    request.query_string_params = import::to_name_value_pairs(to_pairs(&query_string_params));
+
+    Ok(ParsedCurl {
+        request,
+        basic_auth,
+        proxy,
+        warnings,
+    })
+}
+
+/// Split a command line into arguments, following POSIX shell quoting rules
+/// (including ANSI-C $'...' strings and line continuations)
+fn split_arguments(command: &str) -> Result<Vec<String>, ApicizeAppError> {
+    let mut args: Vec<String> = vec![];
+    let mut current = String::default();
+    let mut in_arg = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err(unterminated()),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err(unterminated()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err(unterminated()),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('r') => current.push('\r'),
+                            Some('t') => current.push('\t'),
+                            Some('x') => {
+                                let hex = (0..2)
+                                    .filter_map(|_| chars.next_if(|c| c.is_ascii_hexdigit()))
+                                    .collect::<String>();
+                                match u8::from_str_radix(&hex, 16) {
+                                    Ok(b) => current.push(b as char),
+                                    Err(_) => current.push_str("\\x"),
+                                }
+                            }
+                            Some('u') => {
+                                let hex = (0..4)
+                                    .filter_map(|_| chars.next_if(|c| c.is_ascii_hexdigit()))
+                                    .collect::<String>();
+                                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                                    Some(c) => current.push(c),
+                                    None => current.push_str("\\u"),
+                                }
+                            }
+                            Some(c) => current.push(c),
+                            None => return Err(unterminated()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err(unterminated()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                // Line continuation
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(c) => {
+                    in_arg = true;
+                    current.push(c);
+                }
+                None => {}
+            },
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+fn unterminated() -> ApicizeAppError {
+    ApicizeAppError::ImportError("curl command has an unterminated quote".to_string())
+}
+
+/// Parse URL encoded name/value pairs
+pub fn parse_query(text: &str) -> Vec<(String, String)> {
+    text.split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (name, value) = p.split_once('=').unwrap_or((p, ""));
+            (decode_component(name), decode_component(value))
+        })
+        .collect()
+}
+
+fn decode_component(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                match std::str::from_utf8(&bytes[i + 1..i + 3])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(b) => {
+                        decoded.push(b);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn to_pairs(pairs: &[(String, String)]) -> Vec<Value> {
+    pairs
+        .iter()
+        .map(|(name, value)| json!({ "name": name, "value": value }))
+        .collect()
+}
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 pub enum DroppedFile {
     Text { data: String, extension: String },
     Binary { data: Vec<u8>, extension: String },
+    Har { data: String, extension: String },
 }
 
 impl DroppedFile {
@@ -41,6 +42,13 @@ impl DroppedFile {
             }
 
             if let Some(text) = parsed {
+                // HTTP Archives are imported as requests rather than used as text
+                if extension == "har" {
+                    return DroppedFile::Har {
+                        data: text,
+                        extension,
+                    };
+                }
                 return DroppedFile::Text {
                     data: text,
                     extension,
@@ -49,6 +49,12 @@ pub enum ApicizeAppError {
     #[error("invalid operation type '{0}'")]
     InvalidTypeForOperation(EntityType),
 
+    #[error("invalid run history '{0}'")]
+    InvalidRunHistory(String),
+
+    #[error("unable to import: {0}")]
+    ImportError(String),
+
     #[error("invalid request '{0}' index {1}")]
     InvalidResult(String, usize),
 
@@ -63,6 +69,9 @@ pub enum ApicizeAppError {
 
     #[error("concurrency_error '{0}'")]
     ConcurrencyError(String),
+
+    #[error("secret store error: {0}")]
+    SecretStoreError(String),
 }
 
 impl serde::Serialize for ApicizeAppError {
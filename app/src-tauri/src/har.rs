@@ -0,0 +1,423 @@
+//! HAR submodule
+//!
+//! This submodule converts HTTP Archive (HAR 1.2) files into request groups, and
+//! execution results into HAR files that can be opened in browser devtools
+
+use apicize_lib::{
+    editing::indexed_entities::IndexedEntityPosition, ExecutionResultDetail, Request, RequestBody,
+    RequestEntry, RequestGroup, RequestMethod, Workspace,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+
+use crate::{
+    curl::parse_query,
+    error::ApicizeAppError,
+    import::{self, get_text, to_name_value_pairs, ImportResult},
+    redact::Redactor,
+};
+
+/// Headers that are calculated when a request is dispatched
+const SKIPPED_HEADERS: [&str; 3] = ["content-length", "host", "connection"];
+
+/// Request groups converted from a HAR file, along with anything that could not be converted
+pub struct HarImport {
+    pub groups: Vec<(RequestGroup, Vec<Request>)>,
+    pub warnings: Vec<String>,
+}
+
+/// Returns true if the document is an HTTP Archive
+pub fn is_har_document(document: &Value) -> bool {
+    document
+        .pointer("/log/entries")
+        .is_some_and(|e| e.is_array())
+}
+
+/// Convert an HTTP Archive into a workspace
+pub fn import(document: &Value) -> Result<ImportResult, ApicizeAppError> {
+    let har = build_groups(document)?;
+    let mut workspace = Workspace::new()?;
+    for (group, requests) in har.groups {
+        let group_id = group.id.clone();
+        workspace
+            .requests
+            .add_entity(RequestEntry::Group(group), None, None)?;
+        for request in requests {
+            workspace.requests.add_entity(
+                RequestEntry::Request(request),
+                Some(&group_id),
+                Some(IndexedEntityPosition::Under),
+            )?;
+        }
+    }
+    Ok(ImportResult {
+        workspace,
+        warnings: har.warnings,
+    })
+}
+
+/// Convert HTTP Archive entries into groups, with a group for each page
+/// (or for each host, if entries are not associated with a page)
+pub fn build_groups(document: &Value) -> Result<HarImport, ApicizeAppError> {
+    let Some(entries) = document.pointer("/log/entries").and_then(|e| e.as_array()) else {
+        return Err(ApicizeAppError::ImportError(
+            "document is not an HTTP Archive".to_string(),
+        ));
+    };
+
+    let pages = document
+        .pointer("/log/pages")
+        .and_then(|p| p.as_array())
+        .map(|p| p.as_slice())
+        .unwrap_or_default();
+
+    let mut warnings: Vec<String> = vec![];
+    let mut grouped: Vec<(String, Vec<Request>)> = vec![];
+
+    for entry in entries {
+        let Some(request) = build_request(entry, &mut warnings) else {
+            continue;
+        };
+
+        let group_name = entry
+            .get("pageref")
+            .and_then(|p| p.as_str())
+            .and_then(|pageref| {
+                pages
+                    .iter()
+                    .find(|p| p.get("id").and_then(|i| i.as_str()) == Some(pageref))
+            })
+            .and_then(|p| p.get("title").and_then(|t| t.as_str()))
+            .filter(|t| !t.is_empty())
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| get_host(&request.url).to_string());
+
+        match grouped.iter_mut().find(|(name, _)| *name == group_name) {
+            Some((_, requests)) => requests.push(request),
+            None => grouped.push((group_name, vec![request])),
+        }
+    }
+
+    Ok(HarImport {
+        groups: grouped
+            .into_iter()
+            .map(|(name, requests)| {
+                let mut group = RequestGroup::default();
+                group.name = name;
+                (group, requests)
+            })
+            .collect(),
+        warnings,
+    })
+}
+
+fn build_request(entry: &Value, warnings: &mut Vec<String>) -> Option<Request> {
+    let har_request = entry.get("request")?;
+    let method = get_text(har_request.get("method")).to_uppercase();
+    let full_url = get_text(har_request.get("url"));
+    let url = full_url
+        .split_once('#')
+        .map(|(u, _)| u)
+        .unwrap_or(full_url.as_str());
+    let (base_url, query_string) = url.split_once('?').unwrap_or((url, ""));
+
+    let mut request = Request::default();
+    request.name = format!("{method} {}", get_path(base_url));
+    request.url = base_url.to_string();
+    request.method = serde_json::from_value::<RequestMethod>(json!(method)).ok();
+    if request.method.is_none() {
+        warnings.push(format!("{method} method on {url} is not supported"));
+    }
+
+    let query_string_params = match har_request.get("queryString").and_then(|q| q.as_array()) {
+        Some(query) => query.iter().map(to_pair).collect::<Vec<Value>>(),
+        None => parse_query(query_string)
+            .into_iter()
+            .map(|(name, value)| json!({ "name": name, "value": value }))
+            .collect(),
+    };
+
+    let mut headers = har_request
+        .get("headers")
+        .and_then(|h| h.as_array())
+        .map(|headers| {
+            headers
+                .iter()
+                .filter(|h| {
+                    let name = get_text(h.get("name")).to_ascii_lowercase();
+                    // HTTP/2 pseudo-headers (i.e. ":authority") are not sent as headers
+                    !name.starts_with(':') && !SKIPPED_HEADERS.contains(&name.as_str())
+                })
+                .map(to_pair)
+                .collect::<Vec<Value>>()
+        })
+        .unwrap_or_default();
+
+    if let Some(post_data) = har_request.get("postData") {
+        let mime_type = get_text(post_data.get("mimeType")).to_ascii_lowercase();
+        let text = get_text(post_data.get("text"));
+        let params = post_data
+            .get("params")
+            .and_then(|p| p.as_array())
+            .map(|p| p.as_slice())
+            .unwrap_or_default();
+
+        let body = if mime_type.contains("json") {
+            match serde_json::from_str::<Value>(&text) {
+                Ok(data) => Some(json!({ "type": "JSON", "data": data })),
+                Err(_) => Some(json!({ "type": "Text", "data": text })),
+            }
+        } else if mime_type.starts_with("application/x-www-form-urlencoded") {
+            let data = if params.is_empty() {
+                parse_query(&text)
+                    .into_iter()
+                    .map(|(name, value)| json!({ "name": name, "value": value }))
+                    .collect::<Vec<Value>>()
+            } else {
+                params.iter().map(to_pair).collect()
+            };
+            Some(json!({ "type": "Form", "data": data }))
+        } else if mime_type.starts_with("multipart/form-data") {
+            warnings.push(format!(
+                "Multipart form data for {url} was imported as a URL encoded form, file fields were not imported"
+            ));
+            headers.retain(|h| !get_text(h.get("name")).eq_ignore_ascii_case("content-type"));
+            headers.push(json!({
+                "name": "Content-Type",
+                "value": "application/x-www-form-urlencoded",
+            }));
+            let data = params
+                .iter()
+                .filter(|p| p.get("fileName").is_none())
+                .map(to_pair)
+                .collect::<Vec<Value>>();
+            Some(json!({ "type": "Form", "data": data }))
+        } else if text.is_empty() {
+            None
+        } else if mime_type.contains("xml") {
+            Some(json!({ "type": "XML", "data": text }))
+        } else {
+            Some(json!({ "type": "Text", "data": text }))
+        };
+
+        if let Some(body) = body {
+            match serde_json::from_value::<RequestBody>(body) {
+                Ok(body) => request.body = Some(body),
+                Err(err) => warnings.push(format!("Unable to import body for {url}: {err}")),
+            }
+        }
+    }
+
+    request.headers = to_name_value_pairs(headers);
+    request.query_string_params = to_name_value_pairs(query_string_params);
+
+    // Test for the status recorded in the archive
+    let status = entry
+        .pointer("/response/status")
+        .and_then(|s| s.as_u64())
+        .and_then(|s| u16::try_from(s).ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(200);
+    request.test = Some(import::status_test(status));
+
+    Some(request)
+}
+
+/// Build an HTTP Archive from execution result details, skipping group results;
+/// if the start timestamp is not specified, the execution is assumed to have just completed.
+/// If a redactor is specified, secrets are masked in details before they are converted
+pub fn export(
+    name: &str,
+    details: &[ExecutionResultDetail],
+    started_at: Option<i64>,
+    redactor: Option<&Redactor>,
+) -> Result<Value, ApicizeAppError> {
+    let mut details = details
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<Value>, serde_json::Error>>()?;
+    if let Some(redactor) = redactor {
+        details.iter_mut().for_each(|d| redactor.redact_value(d));
+    }
+
+    let started_at = match started_at {
+        Some(started_at) => started_at,
+        None => {
+            let elapsed = details
+                .iter()
+                .map(|d| get_millis(d.get("executedAt")) + get_millis(d.get("duration")))
+                .max()
+                .unwrap_or(0);
+            Utc::now().timestamp_millis() - elapsed
+        }
+    };
+    let format_time = |offset: i64| {
+        DateTime::from_timestamp_millis(started_at + offset)
+            .unwrap_or_default()
+            .to_rfc3339_opts(SecondsFormat::Millis, true)
+    };
+
+    let entries = details
+        .iter()
+        .filter(|d| d.get("entityType").and_then(|t| t.as_str()) == Some("request"))
+        .map(|detail| {
+            let duration = get_millis(detail.get("duration"));
+            let request = detail.pointer("/testContext/request");
+            let response = detail.pointer("/testContext/response");
+            let url = get_text(request.and_then(|r| r.get("url")));
+
+            let mut har_request = json!({
+                "method": get_text(request.and_then(|r| r.get("method"))),
+                "url": url,
+                "httpVersion": "HTTP/1.1",
+                "cookies": [],
+                "headers": to_har_headers(request.and_then(|r| r.get("headers"))),
+                "queryString": url
+                    .split_once('?')
+                    .map(|(_, query)| parse_query(query))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(name, value)| json!({ "name": name, "value": value }))
+                    .collect::<Vec<Value>>(),
+                "headersSize": -1,
+                "bodySize": -1,
+            });
+            if let Some(body) = request.and_then(|r| r.get("body")) {
+                let (mime_type, text, _) =
+                    to_har_content(body, request.and_then(|r| r.get("headers")));
+                har_request["postData"] = json!({ "mimeType": mime_type, "text": text });
+            }
+
+            let mut content = json!({ "size": 0, "mimeType": "" });
+            if let Some(body) = response.and_then(|r| r.get("body")) {
+                let (mime_type, text, encoding) =
+                    to_har_content(body, response.and_then(|r| r.get("headers")));
+                content = json!({ "size": text.len(), "mimeType": mime_type, "text": text });
+                if let Some(encoding) = encoding {
+                    content["encoding"] = json!(encoding);
+                }
+            }
+
+            let mut entry = json!({
+                "pageref": "page_1",
+                "startedDateTime": format_time(get_millis(detail.get("executedAt"))),
+                "time": duration,
+                "request": har_request,
+                "response": {
+                    "status": response.and_then(|r| r.get("status")).cloned().unwrap_or_else(|| json!(0)),
+                    "statusText": get_text(response.and_then(|r| r.get("statusText"))),
+                    "httpVersion": "HTTP/1.1",
+                    "cookies": [],
+                    "headers": to_har_headers(response.and_then(|r| r.get("headers"))),
+                    "content": content,
+                    "redirectURL": "",
+                    "headersSize": -1,
+                    "bodySize": -1,
+                },
+                "cache": {},
+                "timings": { "send": 0, "wait": duration, "receive": 0 },
+                "comment": get_text(detail.get("name")),
+            });
+            // Browsers record failed requests with a status of zero and a custom error field
+            if let Some(error) = detail.get("error") {
+                entry["response"]["_error"] = json!(error.to_string());
+            }
+            entry
+        })
+        .collect::<Vec<Value>>();
+
+    Ok(json!({
+        "log": {
+            "version": "1.2",
+            "creator": { "name": "Apicize", "version": env!("CARGO_PKG_VERSION") },
+            "pages": [{
+                "startedDateTime": format_time(0),
+                "id": "page_1",
+                "title": name,
+                "pageTimings": {},
+            }],
+            "entries": entries,
+        }
+    }))
+}
+
+/// Return MIME type, text and (optional) encoding of an execution body
+fn to_har_content(body: &Value, headers: Option<&Value>) -> (String, String, Option<&'static str>) {
+    let content_type = headers
+        .and_then(|h| h.as_object())
+        .and_then(|h| {
+            h.iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        })
+        .map(|(_, value)| get_text(Some(value)));
+
+    let body_type = body
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default();
+    let mime_type = content_type.unwrap_or_else(|| {
+        match body_type {
+            "JSON" => "application/json",
+            "XML" => "application/xml",
+            "Form" => "application/x-www-form-urlencoded",
+            "Binary" => "application/octet-stream",
+            _ => "text/plain",
+        }
+        .to_string()
+    });
+
+    if body_type == "Binary" {
+        let data = match body.get("data") {
+            Some(Value::String(data)) => data.clone(),
+            Some(Value::Array(bytes)) => STANDARD.encode(
+                &bytes
+                    .iter()
+                    .filter_map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                    .collect::<Vec<u8>>(),
+            ),
+            _ => String::default(),
+        };
+        (mime_type, data, Some("base64"))
+    } else {
+        (mime_type, get_text(body.get("text")), None)
+    }
+}
+
+fn to_har_headers(headers: Option<&Value>) -> Vec<Value> {
+    headers
+        .and_then(|h| h.as_object())
+        .map(|h| {
+            h.iter()
+                .map(|(name, value)| json!({ "name": name, "value": get_text(Some(value)) }))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn get_host(url: &str) -> &str {
+    let without_scheme = url.split_once("://").map(|(_, u)| u).unwrap_or(url);
+    without_scheme.split('/').next().unwrap_or(without_scheme)
+}
+
+fn get_path(url: &str) -> &str {
+    let without_scheme = url.split_once("://").map(|(_, u)| u).unwrap_or(url);
+    match without_scheme.find('/') {
+        Some(index) => &without_scheme[index..],
+        None => "/",
+    }
+}
+
+fn get_millis(value: Option<&Value>) -> i64 {
+    value
+        .and_then(|v| v.as_f64())
+        .map(|v| v as i64)
+        .unwrap_or(0)
+}
+
+fn to_pair(entry: &Value) -> Value {
+    json!({
+        "name": get_text(entry.get("name")),
+        "value": get_text(entry.get("value")),
+    })
+}
@@ -0,0 +1,211 @@
+//! Undo/redo history submodule
+//!
+//! This submodule tracks snapshots of a workspace taken before each edit
+//! so that edits can be undone and redone
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use apicize_lib::Workspace;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::mock::MockResponse;
+
+/// Maximum number of undo steps retained per workspace
+const MAX_HISTORY_ENTRIES: usize = 50;
+
+/// Edits with the same coalesce key are only merged if made within this long of each other
+const COALESCE_TIMEOUT: Duration = Duration::from_secs(2);
+
+struct HistoryEntry {
+    /// Description of the edit that followed this snapshot
+    description: String,
+    /// When set, consecutive edits with the same key are merged into one entry
+    coalesce_key: Option<String>,
+    /// When the last edit merged into this entry was made
+    edited_at: Instant,
+    /// Entity that was being edited, if applicable
+    entity_id: Option<String>,
+    /// Workspace state prior to the edit
+    workspace: Workspace,
+    /// Mock responses prior to the edit, if the edit changed them
+    mocks: Option<FxHashMap<String, MockResponse>>,
+}
+
+/// Per-workspace undo/redo stacks
+#[derive(Default)]
+pub struct WorkspaceHistory {
+    undo: VecDeque<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+    /// Redo entries cleared by the most recent record, restored if it is discarded
+    cleared_redo: Vec<HistoryEntry>,
+    /// True if the most recent record added an undo entry
+    last_recorded: bool,
+}
+
+/// Result of an undo or redo operation
+pub struct HistoryRestore {
+    /// Workspace to restore
+    pub workspace: Workspace,
+    /// Entity affected by the undone/redone edit, if applicable
+    pub entity_id: Option<String>,
+    /// Mock responses to restore, if the undone/redone edit changed them
+    pub mocks: Option<FxHashMap<String, MockResponse>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryState {
+    pub can_undo: bool,
+    pub can_redo: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub undo_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redo_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity_id: Option<String>,
+}
+
+impl WorkspaceHistory {
+    /// Record the workspace state prior to an edit.  If the coalesce key matches
+    /// the most recent entry and the previous edit was recent (i.e. the user is still
+    /// typing into the same entity), the existing snapshot is kept and no new entry is added
+    pub fn record(
+        &mut self,
+        workspace: &Workspace,
+        description: &str,
+        entity_id: Option<&str>,
+        coalesce_key: Option<&str>,
+    ) {
+        self.push(workspace, None, description, entity_id, coalesce_key);
+    }
+
+    /// Record the workspace state and mock responses prior to an edit that changes mocks
+    pub fn record_with_mocks(
+        &mut self,
+        workspace: &Workspace,
+        mocks: &FxHashMap<String, MockResponse>,
+        description: &str,
+        entity_id: Option<&str>,
+        coalesce_key: Option<&str>,
+    ) {
+        self.push(workspace, Some(mocks), description, entity_id, coalesce_key);
+    }
+
+    fn push(
+        &mut self,
+        workspace: &Workspace,
+        mocks: Option<&FxHashMap<String, MockResponse>>,
+        description: &str,
+        entity_id: Option<&str>,
+        coalesce_key: Option<&str>,
+    ) {
+        self.cleared_redo = std::mem::take(&mut self.redo);
+        self.last_recorded = false;
+
+        if let (Some(key), Some(last)) = (coalesce_key, self.undo.back_mut()) {
+            if last.coalesce_key.as_deref() == Some(key)
+                && last.edited_at.elapsed() < COALESCE_TIMEOUT
+            {
+                last.edited_at = Instant::now();
+                return;
+            }
+        }
+
+        if self.undo.len() >= MAX_HISTORY_ENTRIES {
+            self.undo.pop_front();
+        }
+
+        self.undo.push_back(HistoryEntry {
+            description: description.to_string(),
+            coalesce_key: coalesce_key.map(|k| k.to_string()),
+            edited_at: Instant::now(),
+            entity_id: entity_id.map(|id| id.to_string()),
+            workspace: workspace.clone(),
+            mocks: mocks.cloned(),
+        });
+        self.last_recorded = true;
+    }
+
+    /// Discard the most recent record, used when an edit fails or turns out to be a no-op;
+    /// cleared redo entries are restored, and the recorded workspace (if an entry was added)
+    /// is returned so a partially applied edit can be rolled back
+    pub fn discard_last(&mut self) -> Option<Workspace> {
+        self.redo = std::mem::take(&mut self.cleared_redo);
+        if std::mem::take(&mut self.last_recorded) {
+            self.undo.pop_back().map(|entry| entry.workspace)
+        } else {
+            None
+        }
+    }
+
+    /// Stop merging edits into the most recent entry, so the next edit starts a new undo step
+    pub fn break_coalescing(&mut self) {
+        if let Some(last) = self.undo.back_mut() {
+            last.coalesce_key = None;
+        }
+    }
+
+    /// Pop the last edit, storing the current workspace (and mocks, if the edit changed
+    /// them) so it can be redone
+    pub fn undo(
+        &mut self,
+        current: &Workspace,
+        current_mocks: &FxHashMap<String, MockResponse>,
+    ) -> Option<HistoryRestore> {
+        self.cleared_redo.clear();
+        self.last_recorded = false;
+        let entry = self.undo.pop_back()?;
+        self.redo.push(HistoryEntry {
+            description: entry.description,
+            coalesce_key: None,
+            edited_at: Instant::now(),
+            entity_id: entry.entity_id.clone(),
+            workspace: current.clone(),
+            mocks: entry.mocks.as_ref().map(|_| current_mocks.clone()),
+        });
+        Some(HistoryRestore {
+            workspace: entry.workspace,
+            entity_id: entry.entity_id,
+            mocks: entry.mocks,
+        })
+    }
+
+    /// Re-apply the last undone edit, storing the current workspace (and mocks, if the edit
+    /// changed them) so it can be undone again
+    pub fn redo(
+        &mut self,
+        current: &Workspace,
+        current_mocks: &FxHashMap<String, MockResponse>,
+    ) -> Option<HistoryRestore> {
+        self.cleared_redo.clear();
+        self.last_recorded = false;
+        let entry = self.redo.pop()?;
+        self.undo.push_back(HistoryEntry {
+            description: entry.description,
+            coalesce_key: None,
+            edited_at: Instant::now(),
+            entity_id: entry.entity_id.clone(),
+            workspace: current.clone(),
+            mocks: entry.mocks.as_ref().map(|_| current_mocks.clone()),
+        });
+        Some(HistoryRestore {
+            workspace: entry.workspace,
+            entity_id: entry.entity_id,
+            mocks: entry.mocks,
+        })
+    }
+
+    pub fn get_state(&self, entity_id: Option<String>) -> HistoryState {
+        HistoryState {
+            can_undo: !self.undo.is_empty(),
+            can_redo: !self.redo.is_empty(),
+            undo_description: self.undo.back().map(|e| e.description.clone()),
+            redo_description: self.redo.last().map(|e| e.description.clone()),
+            entity_id,
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Import submodule
+//!
+//! This submodule converts definitions from other tools (i.e. OpenAPI specifications,
+//! Postman collections, HTTP Archives) into Apicize workspaces
+
+use std::{fs, path::Path};
+
+use apicize_lib::{NameValuePair, Workspace};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::ApicizeAppError, har, openapi, postman};
+
+/// Supported import formats
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportFormat {
+    OpenApi,
+    Postman,
+    Har,
+}
+
+/// Workspace generated by an import, along with anything that could not be converted
+pub struct ImportResult {
+    pub workspace: Workspace,
+    pub warnings: Vec<String>,
+}
+
+impl ImportFormat {
+    /// Determine the format of a parsed document
+    pub fn detect(document: &Value) -> Option<ImportFormat> {
+        if document.get("openapi").is_some_and(|v| v.is_string()) {
+            Some(ImportFormat::OpenApi)
+        } else if postman::is_postman_document(document) {
+            Some(ImportFormat::Postman)
+        } else if har::is_har_document(document) {
+            Some(ImportFormat::Har)
+        } else {
+            None
+        }
+    }
+}
+
+/// Parse text as JSON, falling back to YAML
+pub fn parse_document(text: &str) -> Result<Value, ApicizeAppError> {
+    match serde_json::from_str::<Value>(text) {
+        Ok(value) => Ok(value),
+        Err(json_err) => serde_yaml::from_str::<Value>(text).map_err(|yaml_err| {
+            ApicizeAppError::ImportError(format!(
+                "document is neither valid JSON ({json_err}) nor YAML ({yaml_err})"
+            ))
+        }),
+    }
+}
+
+/// Import the specified file, detecting its format if not specified
+pub fn import_file(
+    file_name: &Path,
+    format: Option<ImportFormat>,
+) -> Result<ImportResult, ApicizeAppError> {
+    let text = fs::read_to_string(file_name)?;
+    import_text(&text, format)
+}
+
+/// Import the specified text, detecting its format if not specified
+pub fn import_text(
+    text: &str,
+    format: Option<ImportFormat>,
+) -> Result<ImportResult, ApicizeAppError> {
+    let document = parse_document(text)?;
+    let format = match format.or_else(|| ImportFormat::detect(&document)) {
+        Some(format) => format,
+        None => {
+            return Err(ApicizeAppError::ImportError(
+                "unrecognized document format".to_string(),
+            ))
+        }
+    };
+
+    match format {
+        ImportFormat::OpenApi => openapi::import(&document),
+        ImportFormat::Postman => postman::import(&document),
+        ImportFormat::Har => har::import(&document),
+    }
+}
+
+/// Generate a test that checks the response status
+pub fn status_test(status: u16) -> String {
+    format!(
+        "describe('status', () => {{\n   it('equals {status}', () => {{\n      expect(response.status).to.equal({status})\n   }})\n}})"
+    )
+}
+
+/// Return a JSON value as text, empty if missing or null
+pub fn get_text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Null) | None => String::default(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Convert name/value pair objects, returning None if there are none (or they are invalid)
+pub fn to_name_value_pairs(pairs: Vec<Value>) -> Option<Vec<NameValuePair>> {
+    if pairs.is_empty() {
+        None
+    } else {
+        serde_json::from_value::<Vec<NameValuePair>>(Value::Array(pairs)).ok()
+    }
+}
+
+/// Percent-encode text for use in a query string or form body
+pub fn encode_query_component(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
@@ -0,0 +1,493 @@
+//! Workbook includes submodule
+//!
+//! This submodule lets request groups live in their own files, referenced from the main
+//! workbook's "includes" property.  Included groups are loaded into the workspace when the
+//! workbook is opened and written back to their files when it is saved
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use apicize_lib::{IndexedEntities, RequestEntry, Workspace};
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    canonical::{self, SaveFormat},
+    error::ApicizeAppError,
+    mock::{self, MockResponse, MOCKS_PROPERTY},
+};
+
+/// Main workbook property listing included files
+const INCLUDES_PROPERTY: &str = "includes";
+
+/// Request group saved to its own file
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkbookInclude {
+    pub group_id: String,
+    /// File name, relative to the workbook's directory
+    pub file_name: String,
+}
+
+/// Main workbook reference to an included file, and where its group is placed
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IncludeReference {
+    file_name: String,
+    /// Parent group ID, if not a top level group
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_id: Option<String>,
+    /// Position amongst the parent's children
+    index: usize,
+}
+
+/// Contents of an included file
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IncludedGroup {
+    group_id: String,
+    /// Child IDs of the group and its descendant groups
+    child_ids: BTreeMap<String, Vec<String>>,
+    entities: BTreeMap<String, Value>,
+}
+
+/// Open a workbook, adding request groups from its included files
+pub fn open_workbook(
+    workbook_file_name: &Path,
+) -> Result<(Workspace, Vec<WorkbookInclude>), ApicizeAppError> {
+    let mut workspace = Workspace::open(&workbook_file_name.to_path_buf())?;
+
+    let main = serde_json::from_str::<Value>(&fs::read_to_string(workbook_file_name)?)?;
+    if let Some(requests) = main.get("requests") {
+        canonical::join_request_lines(requests, &mut workspace.requests)?;
+    }
+    let references = match main.get(INCLUDES_PROPERTY) {
+        Some(references) => serde_json::from_value::<Vec<IncludeReference>>(references.clone())?,
+        None => return Ok((workspace, vec![])),
+    };
+
+    // References are recorded as groups are removed in order, so they are restored in reverse
+    let directory = workbook_file_name.parent().unwrap_or(Path::new(""));
+    let mut includes = Vec::with_capacity(references.len());
+    for reference in references.into_iter().rev() {
+        let file_name = get_include_path(directory, &reference.file_name)?;
+        let mut included = serde_json::from_str::<IncludedGroup>(&fs::read_to_string(&file_name)?)
+            .map_err(|err| {
+                ApicizeAppError::InvalidOperation(format!(
+                    "unable to read included file {}: {err}",
+                    file_name.to_string_lossy()
+                ))
+            })?;
+        for entity in included.entities.values_mut() {
+            canonical::join_entity_lines(entity);
+        }
+        add_group(&mut workspace.requests, &reference, &included)?;
+        includes.push(WorkbookInclude {
+            group_id: included.group_id,
+            file_name: reference.file_name,
+        });
+    }
+    includes.reverse();
+    Ok((workspace, includes))
+}
+
+/// Save a workbook and its mock responses in the specified format, writing included
+/// request groups to their own files
+pub fn save_workbook(
+    workspace: &Workspace,
+    includes: &[WorkbookInclude],
+    mocks: &FxHashMap<String, MockResponse>,
+    workbook_file_name: &Path,
+    format: &SaveFormat,
+) -> Result<(), ApicizeAppError> {
+    // Groups are removed in navigation order, so that each index is relative to the
+    // groups remaining when it is removed
+    let order = get_ordered_ids(&workspace.requests);
+    let mut ordered = includes.iter().collect::<Vec<&WorkbookInclude>>();
+    ordered.sort_by_key(|include| order.iter().position(|id| *id == include.group_id));
+
+    let directory = workbook_file_name.parent().unwrap_or(Path::new(""));
+    let mut stripped = Cow::Borrowed(workspace);
+    let mut references = Vec::with_capacity(ordered.len());
+    for include in ordered {
+        let Some((parent_id, index)) = find_location(&stripped.requests, &include.group_id) else {
+            continue;
+        };
+        let mut included = take_group(&mut stripped.to_mut().requests, &include.group_id)?;
+
+        let file_name = get_include_path(directory, &include.file_name)?;
+        if file_name == workbook_file_name {
+            return Err(ApicizeAppError::InvalidOperation(format!(
+                "{} cannot be included by itself",
+                include.file_name
+            )));
+        }
+        if let Some(parent) = file_name.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if format.split() {
+            for entity in included.entities.values_mut() {
+                canonical::split_entity_lines(entity);
+            }
+        }
+        fs::write(
+            &file_name,
+            format.format_contents(&serde_json::to_value(&included)?)?,
+        )?;
+        references.push(IncludeReference {
+            file_name: include.file_name.clone(),
+            parent_id,
+            index,
+        });
+    }
+
+    stripped.save(&workbook_file_name.to_path_buf())?;
+
+    let saved_mocks = mock::get_saved_mocks(mocks, &workspace.requests);
+    if !references.is_empty() || !saved_mocks.is_empty() || format.canonical {
+        let mut main = serde_json::from_str::<Value>(&fs::read_to_string(workbook_file_name)?)?;
+        if let Value::Object(properties) = &mut main {
+            if !references.is_empty() {
+                properties.insert(
+                    INCLUDES_PROPERTY.to_string(),
+                    serde_json::to_value(references)?,
+                );
+            }
+            if !saved_mocks.is_empty() {
+                properties.insert(
+                    MOCKS_PROPERTY.to_string(),
+                    serde_json::to_value(saved_mocks)?,
+                );
+            }
+        }
+        if format.split() {
+            if let Some(requests) = main.get_mut("requests") {
+                canonical::split_request_lines(requests);
+            }
+        }
+        fs::write(workbook_file_name, format.format_contents(&main)?)?;
+    }
+    Ok(())
+}
+
+/// Save a request group to its own file, or back to the main workbook if no file name
+/// is specified
+pub fn set_include(
+    requests: &IndexedEntities<RequestEntry>,
+    includes: &mut Vec<WorkbookInclude>,
+    group_id: &str,
+    file_name: Option<String>,
+) -> Result<(), ApicizeAppError> {
+    includes.retain(|include| include.group_id != group_id);
+    let Some(file_name) = file_name else {
+        return Ok(());
+    };
+
+    match requests.entities.get(group_id) {
+        Some(RequestEntry::Group(_)) => {}
+        _ => return Err(ApicizeAppError::InvalidGroup(group_id.to_string())),
+    }
+    validate_include_filename(&file_name)?;
+    if includes
+        .iter()
+        .any(|include| Path::new(&include.file_name) == Path::new(&file_name))
+    {
+        return Err(ApicizeAppError::InvalidOperation(format!(
+            "{file_name} is already included by another group"
+        )));
+    }
+
+    // Included files cannot be nested, so neither the group's ancestors nor its
+    // descendants may be included
+    let mut ancestor_id = find_location(requests, group_id).and_then(|(parent_id, _)| parent_id);
+    while let Some(id) = ancestor_id {
+        if includes.iter().any(|include| include.group_id == id) {
+            return Err(ApicizeAppError::InvalidOperation(
+                "a group within an included group cannot be included".to_string(),
+            ));
+        }
+        ancestor_id = find_location(requests, &id).and_then(|(parent_id, _)| parent_id);
+    }
+    let mut pending = requests
+        .child_ids
+        .get(group_id)
+        .cloned()
+        .unwrap_or_default();
+    while let Some(id) = pending.pop() {
+        if includes.iter().any(|include| include.group_id == id) {
+            return Err(ApicizeAppError::InvalidOperation(
+                "a group containing an included group cannot be included".to_string(),
+            ));
+        }
+        pending.extend(requests.child_ids.get(&id).into_iter().flatten().cloned());
+    }
+
+    includes.push(WorkbookInclude {
+        group_id: group_id.to_string(),
+        file_name,
+    });
+    Ok(())
+}
+
+/// Return the paths of a workbook's included files
+pub fn get_include_filenames(
+    workbook_file_name: &Path,
+    includes: &[WorkbookInclude],
+) -> Vec<PathBuf> {
+    let directory = workbook_file_name.parent().unwrap_or(Path::new(""));
+    includes
+        .iter()
+        .filter_map(|include| get_include_path(directory, &include.file_name).ok())
+        .collect()
+}
+
+/// Validate an include file name, which must be relative to and within the workbook's
+/// directory
+pub fn validate_include_filename(file_name: &str) -> Result<(), ApicizeAppError> {
+    let path = Path::new(file_name);
+    if file_name.is_empty()
+        || !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(ApicizeAppError::InvalidOperation(format!(
+            "included file name '{file_name}' must be relative to the workbook's directory"
+        )));
+    }
+    Ok(())
+}
+
+fn get_include_path(directory: &Path, file_name: &str) -> Result<PathBuf, ApicizeAppError> {
+    validate_include_filename(file_name)?;
+    Ok(directory.join(file_name))
+}
+
+/// Return the group's parent ID (if not top level) and position amongst its siblings
+fn find_location(
+    requests: &IndexedEntities<RequestEntry>,
+    id: &str,
+) -> Option<(Option<String>, usize)> {
+    if let Some(index) = requests.top_level_ids.iter().position(|i| i == id) {
+        return Some((None, index));
+    }
+    requests
+        .child_ids
+        .iter()
+        .find_map(|(parent_id, child_ids)| {
+            child_ids
+                .iter()
+                .position(|i| i == id)
+                .map(|index| (Some(parent_id.clone()), index))
+        })
+}
+
+/// Return request and group IDs in navigation order
+fn get_ordered_ids(requests: &IndexedEntities<RequestEntry>) -> Vec<String> {
+    let mut ordered = Vec::with_capacity(requests.entities.len());
+    let mut pending = requests
+        .top_level_ids
+        .iter()
+        .rev()
+        .collect::<Vec<&String>>();
+    while let Some(id) = pending.pop() {
+        ordered.push(id.clone());
+        if let Some(child_ids) = requests.child_ids.get(id) {
+            pending.extend(child_ids.iter().rev());
+        }
+    }
+    ordered
+}
+
+/// Remove a group and its descendants from the requests
+fn take_group(
+    requests: &mut IndexedEntities<RequestEntry>,
+    group_id: &str,
+) -> Result<IncludedGroup, ApicizeAppError> {
+    requests.top_level_ids.retain(|id| id != group_id);
+    for child_ids in requests.child_ids.values_mut() {
+        child_ids.retain(|id| id != group_id);
+    }
+
+    let mut included = IncludedGroup {
+        group_id: group_id.to_string(),
+        child_ids: BTreeMap::new(),
+        entities: BTreeMap::new(),
+    };
+    let mut pending = vec![group_id.to_string()];
+    while let Some(id) = pending.pop() {
+        if let Some(entity) = requests.entities.remove(&id) {
+            included
+                .entities
+                .insert(id.clone(), serde_json::to_value(entity)?);
+        }
+        if let Some(child_ids) = requests.child_ids.remove(&id) {
+            pending.extend(child_ids.iter().cloned());
+            included.child_ids.insert(id, child_ids);
+        }
+    }
+    Ok(included)
+}
+
+/// Add an included group and its descendants to the requests
+fn add_group(
+    requests: &mut IndexedEntities<RequestEntry>,
+    reference: &IncludeReference,
+    included: &IncludedGroup,
+) -> Result<(), ApicizeAppError> {
+    if !included.entities.contains_key(&included.group_id) {
+        return Err(ApicizeAppError::InvalidGroup(included.group_id.clone()));
+    }
+    for (id, entity) in &included.entities {
+        requests
+            .entities
+            .insert(id.clone(), serde_json::from_value(entity.clone())?);
+    }
+    for (parent_id, child_ids) in &included.child_ids {
+        requests
+            .child_ids
+            .insert(parent_id.clone(), child_ids.clone());
+    }
+
+    let siblings = match &reference.parent_id {
+        Some(parent_id) if requests.entities.contains_key(parent_id) => {
+            requests.child_ids.entry(parent_id.clone()).or_default()
+        }
+        _ => &mut requests.top_level_ids,
+    };
+    siblings.insert(
+        reference.index.min(siblings.len()),
+        included.group_id.clone(),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use apicize_lib::{
+        editing::indexed_entities::IndexedEntityPosition, Request, RequestEntry, RequestGroup,
+        Workspace,
+    };
+    use rustc_hash::FxHashMap;
+    use uuid::Uuid;
+
+    use super::{open_workbook, save_workbook, WorkbookInclude};
+    use crate::canonical::SaveFormat;
+
+    fn request(id: &str) -> RequestEntry {
+        let mut request = Request::default();
+        request.id = id.to_string();
+        request.name = id.to_string();
+        RequestEntry::Request(request)
+    }
+
+    fn group(id: &str) -> RequestEntry {
+        let mut group = RequestGroup::default();
+        group.id = id.to_string();
+        group.name = id.to_string();
+        RequestEntry::Group(group)
+    }
+
+    /// Workspace with groups g1 (containing g1a, r1 and g1b, each group holding a request)
+    /// and g2 (containing g2a and g2b), followed by group g3 and request r3
+    fn sample() -> Workspace {
+        let mut workspace = Workspace::new().unwrap();
+        let requests = &mut workspace.requests;
+        let mut add = |entry: RequestEntry, relative_to: Option<&str>, under: bool| {
+            let position = relative_to.map(|_| {
+                if under {
+                    IndexedEntityPosition::Under
+                } else {
+                    IndexedEntityPosition::After
+                }
+            });
+            requests.add_entity(entry, relative_to, position).unwrap();
+        };
+        add(group("g1"), None, false);
+        add(group("g1a"), Some("g1"), true);
+        add(request("g1a-r"), Some("g1a"), true);
+        add(request("r1"), Some("g1a"), false);
+        add(group("g1b"), Some("r1"), false);
+        add(request("g1b-r"), Some("g1b"), true);
+        add(group("g2"), Some("g1"), false);
+        add(group("g2a"), Some("g2"), true);
+        add(group("g2b"), Some("g2a"), false);
+        add(group("g3"), Some("g2"), false);
+        add(request("r3"), Some("g3"), false);
+        workspace
+    }
+
+    /// Save the workspace with the specified groups included, open it again and check
+    /// that requests and includes come back in their original order
+    fn assert_round_trip(included_group_ids: &[&str]) {
+        let directory = std::env::temp_dir().join(format!("apicize-includes-{}", Uuid::new_v4()));
+        fs::create_dir_all(&directory).unwrap();
+        let file_name: PathBuf = directory.join("workbook.apicize");
+
+        let workspace = sample();
+        let includes = included_group_ids
+            .iter()
+            .map(|id| WorkbookInclude {
+                group_id: id.to_string(),
+                file_name: format!("{id}.apicize-group"),
+            })
+            .collect::<Vec<WorkbookInclude>>();
+        save_workbook(
+            &workspace,
+            &includes,
+            &FxHashMap::default(),
+            &file_name,
+            &SaveFormat::default(),
+        )
+        .unwrap();
+        let (opened, opened_includes) = open_workbook(&file_name).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            opened.requests.top_level_ids,
+            workspace.requests.top_level_ids
+        );
+        for (parent_id, child_ids) in &workspace.requests.child_ids {
+            assert_eq!(
+                opened.requests.child_ids.get(parent_id),
+                Some(child_ids),
+                "children of {parent_id}"
+            );
+        }
+        for id in workspace.requests.entities.keys() {
+            assert!(opened.requests.entities.contains_key(id), "entity {id}");
+        }
+
+        let mut expected = included_group_ids.to_vec();
+        expected.sort();
+        let mut actual = opened_includes
+            .iter()
+            .map(|include| include.group_id.as_str())
+            .collect::<Vec<&str>>();
+        actual.sort();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn round_trips_sibling_includes() {
+        assert_round_trip(&["g1a", "g1b"]);
+    }
+
+    #[test]
+    fn round_trips_includes_under_different_parents() {
+        assert_round_trip(&["g1b", "g2a"]);
+        assert_round_trip(&["g2b", "g1a"]);
+    }
+
+    #[test]
+    fn round_trips_top_level_include() {
+        assert_round_trip(&["g2"]);
+        assert_round_trip(&["g3", "g1a", "g2b"]);
+    }
+}
@@ -1,29 +1,70 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+pub mod canonical;
+pub mod cli;
+pub mod curl;
 pub mod dragdrop;
 pub mod error;
+pub mod har;
+pub mod history;
+pub mod import;
+pub mod includes;
+pub mod merge;
+pub mod mock;
+pub mod openapi;
 pub mod pkce;
+pub mod postman;
+pub mod private_params;
+pub mod recorder;
+pub mod recovery;
+pub mod redact;
+pub mod replace;
+pub mod result_diff;
+pub mod run_history;
+pub mod search;
+pub mod secrets;
 pub mod sessions;
 pub mod settings;
+pub mod snippet;
 pub mod trace;
+pub mod trace_export;
+pub mod watcher;
 pub mod workspaces;
 
 use apicize_lib::{
     clear_all_oauth2_tokens_from_cache, clear_oauth2_token_from_cache,
     editing::indexed_entities::IndexedEntityPosition, store_oauth2_token_in_cache, ApicizeRunner,
     Authorization, CachedTokenInfo, ExecutionReportFormat, ExecutionResultDetail,
-    ExecutionResultSummary, ExecutionStatus, ExternalData, Parameters, PkceTokenResult,
-    TestRunnerContext, Warnings, Workspace,
+    ExecutionResultSummary, ExecutionStatus, ExternalData, Identifiable, Parameters,
+    PkceTokenResult, Selection, TestRunnerContext, Warnings, Workspace,
 };
+use canonical::SaveFormat;
+use curl::CurlRequestResult;
 use dirs::home_dir;
 use dragdrop::DroppedFile;
 use error::ApicizeAppError;
+use history::HistoryState;
+use import::ImportFormat;
+use includes::WorkbookInclude;
+use merge::{MergeResolution, MergeResult};
+use mock::{MockResponse, MockServer, MockServerStatus};
 use pkce::{OAuth2PkceInfo, OAuth2PkceRequest, OAuth2PkceService};
+use private_params::PRIVATE_SECTION;
+use recorder::{ProxyRecorder, RecordedExchange, RecorderStatus};
+use recovery::{RecoveryInfo, WorkspaceRecovery};
+use redact::Redactor;
+use replace::{ReplaceOptions, ReplacePreview};
+use result_diff::{ResultDiff, ResultDiffOptions, ResultReference};
+use run_history::{RunHistory, RunHistoryEntry, RunHistoryPruneOptions};
+use search::{SearchOptions, SearchResult};
+use secrets::{EncryptedFileSecretStore, SecretStorage, SecretStore, SecretStoreStatus};
 use serde::{Deserialize, Serialize};
 use sessions::{Session, SessionInitialization, SessionSaveState, SessionStartupState, Sessions};
 use settings::{ApicizeSettings, ColorScheme};
+use snippet::{RequestSnippet, SnippetFormat};
 use std::{
+    collections::HashSet,
     env,
     fs::{self, exists},
     io::{self},
@@ -36,7 +77,10 @@ use tauri::{
 };
 use tauri_plugin_clipboard::Clipboard;
 use tokio_util::sync::CancellationToken;
-use trace::{ReqwestEvent, ReqwestLogger};
+use trace::{ReqwestEvent, ReqwestLogger, TracePage, TraceQuery};
+use trace_export::TraceExportOptions;
+use uuid::Uuid;
+use watcher::{WorkbookChange, WorkbookWatcher};
 use workspaces::{
     Entities, Entity, EntityType, Navigation, OpenWorkspaceResult, WorkspaceInfo,
     WorkspaceSaveStatus, Workspaces,
@@ -48,6 +92,14 @@ struct AuthState {
     pkce: Mutex<OAuth2PkceService>,
 }
 
+struct MockState {
+    server: Mutex<MockServer>,
+}
+
+struct RecorderState {
+    proxy: Mutex<ProxyRecorder>,
+}
+
 struct SettingsState {
     settings: RwLock<ApicizeSettings>,
 }
@@ -60,6 +112,20 @@ struct SessionsState {
     pub sessions: RwLock<Sessions>,
 }
 
+struct SecretsState {
+    store: Mutex<Option<Arc<dyn SecretStore>>>,
+}
+
+struct WatcherState {
+    watcher: Mutex<WorkbookWatcher>,
+}
+
+struct RecoveryState {
+    recovery: Mutex<WorkspaceRecovery>,
+    /// Unsaved work left by a previous app session
+    available: Mutex<Vec<RecoveryInfo>>,
+}
+
 static REQWEST_LOGGER: OnceLock<ReqwestLogger> = OnceLock::new();
 
 fn copy_files(source: &Path, destination: &Path) -> io::Result<()> {
@@ -84,6 +150,12 @@ fn copy_files(source: &Path, destination: &Path) -> io::Result<()> {
 
 #[tokio::main]
 async fn main() {
+    // Run requests headlessly if launched as "apicize run ..."
+    let args: Vec<String> = env::args().collect();
+    if cli::is_cli_mode(&args) {
+        std::process::exit(cli::run_from_args(&args).await);
+    }
+
     tauri::Builder::default()
         .setup(|app| {
             // When first loading, we either:
@@ -153,17 +225,37 @@ async fn main() {
                 let _ = handle.enter();
                 ReqwestLogger::new(app.handle().clone())
             });
+            reqwest_logger.configure(
+                settings.trace_retention,
+                settings.trace_spool,
+                settings.trace_spool_days,
+            );
 
             let _ = log::set_logger(reqwest_logger);
 
             log::set_max_level(log::LevelFilter::Trace);
 
+            // Set up secret store, passphrase-protected stores remain locked until unlocked
+            let secret_store = open_secret_store(settings.secret_storage);
+            if let Some(store) = &secret_store {
+                restore_tokens(store.clone());
+            }
+            app.manage(SecretsState {
+                store: Mutex::new(secret_store),
+            });
+
+            // Set up workbook watcher, workbooks are watched as they are opened
+            app.manage(WatcherState {
+                watcher: Mutex::new(WorkbookWatcher::default()),
+            });
+
             create_workspace(
                 app.handle().clone(),
                 &mut sessions,
                 &mut workspaces,
                 &mut settings,
                 load_workbook,
+                None,
                 true,
                 None,
                 true,
@@ -185,11 +277,40 @@ async fn main() {
                 pkce: Mutex::new(OAuth2PkceService::new(app.handle().clone())),
             });
 
+            // Set up mock server
+            app.manage(MockState {
+                server: Mutex::new(MockServer::new(app.handle().clone())),
+            });
+
+            // Set up recording proxy
+            app.manage(RecorderState {
+                proxy: Mutex::new(ProxyRecorder::new(app.handle().clone())),
+            });
+
             // Set up workspaces
             app.manage(WorkspacesState {
                 workspaces: RwLock::new(workspaces),
             });
 
+            // Set up crash recovery, offering unsaved work left by a previous app session
+            app.manage(RecoveryState {
+                recovery: Mutex::new(WorkspaceRecovery::default()),
+                available: Mutex::new(WorkspaceRecovery::find_recoveries()),
+            });
+
+            let recovery_handle = app.handle().clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(recovery::RECOVERY_INTERVAL);
+                tauri::async_runtime::block_on(save_recovery_snapshots(&recovery_handle));
+            });
+
+            // Periodically check open workbooks for changes made outside of Apicize
+            let watcher_handle = app.handle().clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(watcher::WATCH_INTERVAL);
+                tauri::async_runtime::block_on(check_workbook_changes(&watcher_handle));
+            });
+
             Ok(())
         })
         .plugin(tauri_plugin_os::init())
@@ -232,6 +353,7 @@ async fn main() {
             generate_settings_defaults,
             new_workspace,
             open_workspace,
+            import_workspace,
             save_workspace,
             close_workspace,
             clone_workspace,
@@ -242,7 +364,24 @@ async fn main() {
             cancel_request,
             generate_report,
             get_result_detail,
+            list_run_history,
+            get_run_history_detail,
+            load_run_history,
+            prune_run_history,
+            diff_results,
             store_token,
+            get_secret_store_status,
+            unlock_secret_store,
+            unlock_private_parameters,
+            reload_workspace,
+            merge_workspace_changes,
+            resolve_merge_conflict,
+            keep_workspace_changes,
+            list_recoveries,
+            restore_recovery,
+            discard_recovery,
+            list_request_group_files,
+            set_request_group_file,
             clear_all_cached_authorizations,
             clear_cached_authorization,
             // get_environment_variables,
@@ -254,18 +393,47 @@ async fn main() {
             retrieve_access_token,
             refresh_token,
             get_clipboard_file_data,
+            add_request_from_clipboard_curl,
+            add_requests_from_har,
+            export_results_har,
             get,
             get_title,
             get_dirty,
             get_request_active_authorization,
             get_request_active_data,
+            get_request_snippet,
+            search,
+            get_mock,
+            update_mock,
+            start_mock_server,
+            stop_mock_server,
+            get_mock_server_status,
+            start_recording_proxy,
+            stop_recording_proxy,
+            get_recording_proxy_status,
+            list_recorded_exchanges,
+            clear_recorded_exchanges,
+            add_requests_from_recording,
+            preview_replace,
+            apply_replace,
             list,
             add,
             update,
             delete,
             move_entity,
+            delete_request_entries,
+            move_request_entries,
+            duplicate_request_entries,
+            assign_request_entries_parameter,
+            undo,
+            redo,
+            get_history_state,
             list_logs,
             clear_logs,
+            set_reveal_secrets,
+            write_clipboard_text,
+            query_logs,
+            export_trace,
             get_entity_type,
             find_descendant_groups,
             get_storage_information,
@@ -361,6 +529,13 @@ fn generate_settings_defaults(app: AppHandle) -> Result<ApicizeSettings, Apicize
         editor_indent_size: 3,
         editor_check_js_syntax: true,
         editor_detect_existing_indent: true,
+        trace_retention: trace::DEFAULT_TRACE_RETENTION,
+        trace_spool: false,
+        trace_spool_days: trace::DEFAULT_TRACE_SPOOL_DAYS,
+        secret_storage: SecretStorage::default(),
+        encrypt_private_parameters: false,
+        canonical_workbook_format: false,
+        split_multiline_strings: false,
     })
 }
 
@@ -371,6 +546,7 @@ fn create_workspace(
     workspaces: &mut Workspaces,
     settings: &mut ApicizeSettings,
     open_existing_file_name: Option<String>,
+    imported_workspace: Option<Workspace>,
     create_new_if_error: bool,
     current_session_id: Option<String>,
     open_in_new_session: bool,
@@ -404,8 +580,9 @@ fn create_workspace(
         }
     }
 
-    let workspace_result = match &open_existing_file_name {
-        Some(file_name) => {
+    let mut workspace_result = match (&open_existing_file_name, imported_workspace) {
+        (_, Some(workspace)) => Ok(workspaces.add_imported_workspace(workspace)),
+        (Some(file_name), None) => {
             if let Some(existing_workspace_id) = &existing_workspace_id {
                 Ok(OpenWorkspaceResult {
                     workspace_id: existing_workspace_id.clone(),
@@ -418,10 +595,14 @@ fn create_workspace(
                     startup_state: SessionStartupState::default(),
                 })
             } else {
-                match Workspace::open(&PathBuf::from(&file_name)) {
-                    Ok(workspace) => {
+                match includes::open_workbook(&PathBuf::from(&file_name)) {
+                    Ok((workspace, workbook_includes)) => {
                         save_recent_file_name = Some(file_name.clone());
-                        Ok(workspaces.add_workspace(workspace, file_name, false))
+                        let result = workspaces.add_workspace(workspace, file_name, false);
+                        workspaces
+                            .get_workspace_info_mut(&result.workspace_id)?
+                            .includes = workbook_includes;
+                        Ok(result)
                     }
                     Err(err) => {
                         if create_new_if_error {
@@ -435,10 +616,38 @@ fn create_workspace(
                 }
             }
         }
-        None => Ok(workspaces.add_workspace(Workspace::new()?, "", true)),
+        (None, None) => Ok(workspaces.add_workspace(Workspace::new()?, "", true)),
     }?;
 
     let info = workspaces.get_workspace_info_mut(&workspace_result.workspace_id)?;
+
+    // Add vault parameters held in the secret store and encrypted private parameters
+    // to newly opened workspaces
+    if existing_workspace_id.is_none() {
+        let mut added = false;
+        if let Some(store) = get_secret_store(&app) {
+            match secrets::restore_vault(store.as_ref(), &mut info.workspace) {
+                Ok(restored) => added = restored,
+                Err(err) => eprintln!("Unable to restore vault parameters: {err}"),
+            }
+        }
+
+        // Each workbook's private parameters are unlocked with its own passphrase
+        if !info.file_name.is_empty() && private_params::is_encrypted(Path::new(&info.file_name)) {
+            workspace_result.startup_state.error =
+                Some("Private parameters are encrypted, unlock them to use them".to_string());
+        }
+
+        if added {
+            info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+        }
+
+        if !info.file_name.is_empty() {
+            info.base = Some(info.workspace.clone());
+            info.base_mocks = info.mocks.clone();
+        }
+        watch_workbook(&app, &workspace_result.workspace_id, info);
+    }
     let trace_title = {
         let session_name = match &current_session_id {
             Some(id) => id.as_str(),
@@ -468,6 +677,7 @@ fn create_workspace(
         let active_session_id = sessions.add_session(Session {
             workspace_id: workspace_result.workspace_id.clone(),
             startup_state: Some(workspace_result.startup_state),
+            reveal_secrets: false,
         });
 
         let webview_url = tauri::WebviewUrl::App("index.html".into());
@@ -576,6 +786,39 @@ fn position_window_builder<'a>(
         .center()
 }
 
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn import_workspace(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    settings_state: State<'_, SettingsState>,
+    file_name: String,
+    format: Option<ImportFormat>,
+    session_id: Option<String>,
+    open_in_new_session: bool,
+) -> Result<Vec<String>, ApicizeAppError> {
+    let result = import::import_file(&PathBuf::from(&file_name), format)?;
+
+    let sessions = &mut sessions_state.sessions.write().await;
+    let workspaces = &mut workspaces_state.workspaces.write().await;
+    let settings = &mut settings_state.settings.write().await;
+
+    create_workspace(
+        app,
+        sessions,
+        workspaces,
+        settings,
+        None,
+        Some(result.workspace),
+        false,
+        session_id,
+        open_in_new_session,
+    )?;
+
+    Ok(result.warnings)
+}
+
 #[tauri::command]
 async fn clone_workspace(
     app: AppHandle,
@@ -593,6 +836,7 @@ async fn clone_workspace(
     let active_session_id = sessions.add_session(Session {
         workspace_id: workspace_id.clone(),
         startup_state,
+        reveal_secrets: false,
     });
 
     println!(
@@ -633,6 +877,7 @@ async fn new_workspace(
         workspaces,
         settings,
         None,
+        None,
         true,
         current_session_id,
         open_in_new_session,
@@ -659,6 +904,7 @@ async fn open_workspace(
         workspaces,
         settings,
         Some(file_name),
+        None,
         false,
         session_id,
         open_in_new_session,
@@ -688,6 +934,17 @@ async fn save_workspace(
         }
     }
 
+    let (secret_storage, encrypt_private_parameters, save_format) = {
+        let settings = settings_state.settings.read().await;
+        (
+            settings.secret_storage,
+            settings.encrypt_private_parameters,
+            SaveFormat {
+                canonical: settings.canonical_workbook_format,
+                split_multiline_strings: settings.split_multiline_strings,
+            },
+        )
+    };
     let mut workspaces = workspaces_state.workspaces.write().await;
     let info = workspaces.get_workspace_info_mut(&session.workspace_id)?;
     let save_as = match file_name {
@@ -701,9 +958,77 @@ async fn save_workspace(
         }
     };
 
+    // Vault parameters kept in a secret store are not written to the global parameters file
+    let mut stripped = match (secret_storage, get_secret_store(&app)) {
+        (SecretStorage::File, _) => None,
+        (_, Some(store)) => Some(secrets::store_vault(store.as_ref(), &info.workspace)?),
+        (_, None) => {
+            if secrets::has_vault(&info.workspace) {
+                return Err(ApicizeAppError::SecretStoreError(
+                    "the secret store must be unlocked to save vault parameters".to_string(),
+                ));
+            }
+            None
+        }
+    };
+
     let save_to = PathBuf::from(&save_as);
-    match info.workspace.save(&save_to) {
+
+    // Private parameters are written to an encrypted file once the workbook is unlocked;
+    // an existing encrypted file is only replaced if it decrypts with the same passphrase
+    let private_passphrase = info.private_passphrase.clone();
+    if encrypt_private_parameters {
+        match &private_passphrase {
+            Some(passphrase) => {
+                if private_params::is_encrypted(&save_to)
+                    && private_params::open(&save_to, passphrase).is_err()
+                {
+                    return Err(ApicizeAppError::SecretStoreError(format!(
+                        "{} is encrypted with a different passphrase",
+                        private_params::get_encrypted_private_filename(&save_to).to_string_lossy()
+                    )));
+                }
+                stripped = Some(private_params::save(
+                    &save_to,
+                    passphrase,
+                    stripped.as_ref().unwrap_or(&info.workspace),
+                )?);
+            }
+            None => {
+                if secrets::has_parameters(&info.workspace, PRIVATE_SECTION)
+                    || private_params::is_encrypted(&save_to)
+                {
+                    return Err(ApicizeAppError::SecretStoreError(
+                        "private parameters must be unlocked to save them".to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    match includes::save_workbook(
+        stripped.as_ref().unwrap_or(&info.workspace),
+        &info.includes,
+        &info.mocks,
+        &save_to,
+        &save_format,
+    ) {
         Ok(..) => {
+            // Remove whichever private parameter file is superseded, provided its contents
+            // could be loaded with the unlocked passphrase
+            if let Some(passphrase) = &private_passphrase {
+                let superseded = if encrypt_private_parameters {
+                    Some(private_params::get_private_filename(&save_to))
+                } else if private_params::open(&save_to, passphrase).is_ok() {
+                    Some(private_params::get_encrypted_private_filename(&save_to))
+                } else {
+                    None
+                };
+                if let Some(file_name) = superseded {
+                    private_params::remove_file(&file_name)?;
+                }
+            }
+
             let mut settings = settings_state.settings.write().await;
             if settings.update_recent_workbook_file_name(&save_as) {
                 settings.save()?;
@@ -719,10 +1044,17 @@ async fn save_workspace(
                 .to_string_lossy()
                 .to_string();
 
+            // Edits after a save start a new undo step
+            info.history.break_coalescing();
+            info.base = Some(info.workspace.clone());
+            info.base_mocks = info.mocks.clone();
+            info.merge_theirs = None;
+            info.merge_theirs_mocks.clear();
+            watch_workbook(&app, &session.workspace_id, info);
             dispatch_save_state(&app, &sessions, &session.workspace_id, info, false);
             Ok(())
         }
-        Err(err) => Err(ApicizeAppError::FileAccessError(err)),
+        Err(err) => Err(err),
     }
 }
 
@@ -760,6 +1092,7 @@ async fn close_workspace(
     app: AppHandle,
     sessions_state: State<'_, SessionsState>,
     workspaces_state: State<'_, WorkspacesState>,
+    mock_state: State<'_, MockState>,
     session_id: &str,
 ) -> Result<(), ApicizeAppError> {
     let mut sessions = sessions_state.sessions.write().await;
@@ -789,8 +1122,13 @@ async fn close_workspace(
         if editor_count == 0 {
             let workbook_auth_ids = workspaces.list_workbook_authorization_ids(&workspace_id)?;
             for auth_id in workbook_auth_ids {
-                clear_cached_authorization(auth_id).await;
+                clear_oauth2_token_from_cache(&auth_id).await;
             }
+            mock_state
+                .server
+                .lock()
+                .unwrap()
+                .stop_for_workspace(&workspace_id);
             workspaces.remove_workspace(&workspace_id);
         }
         println!("*** {trace_title} ***");
@@ -866,14 +1204,29 @@ async fn save_settings(
     settings_state: State<'_, SettingsState>,
 ) -> Result<(), String> {
     let mut settings = settings_state.settings.write().await;
+    let storage_changed = settings.secret_storage != updated_settings.secret_storage;
     settings.clone_from(&updated_settings);
-    match settings.save() {
-        Ok(..) => {
-            app.emit("update_settings", updated_settings).unwrap();
-            Ok(())
+    if let Some(logger) = REQWEST_LOGGER.get() {
+        logger.configure(
+            settings.trace_retention,
+            settings.trace_spool,
+            settings.trace_spool_days,
+        );
+    }
+    if let Err(err) = settings.save() {
+        return Err(err.error.to_string());
+    }
+    drop(settings);
+
+    // Settings lock is released first, since workspaces are updated with the new store
+    if storage_changed {
+        let store = open_secret_store(updated_settings.secret_storage);
+        if let Err(err) = set_secret_store(&app, store).await {
+            return Err(err.to_string());
         }
-        Err(err) => Err(err.error.to_string()),
     }
+    app.emit("update_settings", updated_settings).unwrap();
+    Ok(())
 }
 
 fn cancellation_tokens() -> &'static Mutex<FxHashMap<String, CancellationToken>> {
@@ -937,6 +1290,9 @@ async fn run_request(
         (cloned_workspace, other_session_ids)
     };
 
+    // Secrets are masked in results persisted to execution history
+    let redactor = Redactor::for_workspace(&cloned_workspace);
+
     // Phase 3: Emit status updates outside of any locks
     let execution_status = ExecutionStatus {
         request_or_group_id: request_or_group_id.to_string(),
@@ -958,8 +1314,20 @@ async fn run_request(
         true, // enable detailed trace capture to get read/write data
     ));
 
-    // Phase 5: Execute request (no locks held)
-    let responses = runner.run(vec![request_or_group_id.to_string()]).await;
+    // Phase 5: Execute request (no locks held), attributing network trace events to this run
+    let run_id = Uuid::new_v4().to_string();
+    if let Some(logger) = REQWEST_LOGGER.get() {
+        logger.begin_run(&run_id, request_or_group_id);
+    }
+    let responses = ReqwestLogger::attribute_run(
+        &run_id,
+        request_or_group_id,
+        runner.run(vec![request_or_group_id.to_string()]),
+    )
+    .await;
+    if let Some(logger) = REQWEST_LOGGER.get() {
+        logger.end_run(&run_id);
+    }
     
     // Clean up cancellation token
     cancellation_tokens()
@@ -972,6 +1340,30 @@ async fn run_request(
         Some(Ok(result)) => {
             // Assemble results outside of lock
             let (summaries, details) = result.assemble_results(&runner);
+
+            // Persist results to execution history (saved workbooks only), off the async runtime
+            if !workbook_full_name.is_empty() {
+                let request_or_group_id = request_or_group_id.to_string();
+                let stored_summaries = summaries.clone();
+                let stored_details = details.clone();
+                let stored = tauri::async_runtime::spawn_blocking(move || {
+                    RunHistory::for_workbook(&workbook_full_name).and_then(|history| {
+                        history.store(
+                            &run_id,
+                            &request_or_group_id,
+                            &stored_summaries,
+                            &stored_details,
+                            &redactor,
+                        )
+                    })
+                })
+                .await;
+                match stored {
+                    Ok(Err(err)) => eprintln!("Unable to store execution history: {err}"),
+                    Err(err) => eprintln!("Unable to store execution history: {err}"),
+                    Ok(Ok(_)) => {}
+                }
+            }
             
             // Quick write lock just for state updates
             {
@@ -1051,596 +1443,1962 @@ async fn get_result_detail(
     let sessions = sessions_state.sessions.read().await;
     let session = sessions.get_session(session_id)?;
     let workspaces = workspaces_state.workspaces.read().await;
-    workspaces.get_result_detail(&session.workspace_id, request_id, index)
+    let detail = workspaces.get_result_detail(&session.workspace_id, request_id, index)?;
+    if session.reveal_secrets {
+        Ok(detail)
+    } else {
+        workspaces
+            .get_redactor(&session.workspace_id)?
+            .redact_serialized(&detail)
+    }
+}
+
+/// Retrieve execution history for the workbook open in the session
+async fn get_session_run_history(
+    sessions_state: &State<'_, SessionsState>,
+    workspaces_state: &State<'_, WorkspacesState>,
+    session_id: &str,
+) -> Result<RunHistory, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    let info = workspaces.get_workspace_info(&session.workspace_id)?;
+    RunHistory::for_workbook(&info.file_name)
 }
 
 #[tauri::command]
-async fn generate_report(
+async fn list_run_history(
     sessions_state: State<'_, SessionsState>,
     workspaces_state: State<'_, WorkspacesState>,
     session_id: &str,
-    request_id: &str,
+    request_or_group_id: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<RunHistoryEntry>, ApicizeAppError> {
+    get_session_run_history(&sessions_state, &workspaces_state, session_id)
+        .await?
+        .list(request_or_group_id, limit)
+}
+
+#[tauri::command]
+async fn get_run_history_detail(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    run_id: &str,
     index: usize,
-    format: ExecutionReportFormat,
-) -> Result<String, ApicizeAppError> {
+) -> Result<ExecutionResultDetail, ApicizeAppError> {
+    let detail = get_session_run_history(&sessions_state, &workspaces_state, session_id)
+        .await?
+        .load_detail(run_id, index)?;
+
     let sessions = sessions_state.sessions.read().await;
     let session = sessions.get_session(session_id)?;
-    let workspaces = workspaces_state.workspaces.read().await;
-    workspaces.generate_report(&session.workspace_id, request_id, index, format)
+    if session.reveal_secrets {
+        Ok(detail)
+    } else {
+        workspaces_state
+            .workspaces
+            .read()
+            .await
+            .get_redactor(&session.workspace_id)?
+            .redact_serialized(&detail)
+    }
 }
 
+/// Load a stored run as the current results for its request or group,
+/// so that it can be viewed like a fresh execution
 #[tauri::command]
-async fn store_token(authorization_id: String, token_info: CachedTokenInfo) {
-    store_oauth2_token_in_cache(&authorization_id, token_info).await
-}
+async fn load_run_history(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    run_id: &str,
+) -> Result<Vec<ExecutionResultSummary>, ApicizeAppError> {
+    let record = get_session_run_history(&sessions_state, &workspaces_state, session_id)
+        .await?
+        .load(run_id)?;
 
-#[tauri::command]
-async fn clear_all_cached_authorizations() -> usize {
-    clear_all_oauth2_tokens_from_cache().await
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let request_or_group_id = record.entry.request_or_group_id;
+    let redacted_summaries = {
+        let mut workspaces = workspaces_state.workspaces.write().await;
+        let redactor = workspaces.get_redactor(&session.workspace_id)?;
+        let info = workspaces.get_workspace_info_mut(&session.workspace_id)?;
+        info.result_summaries
+            .insert(request_or_group_id.clone(), record.summaries.clone());
+        info.result_details
+            .insert(request_or_group_id.clone(), record.details);
+        redactor.redact_serialized(&record.summaries)?
+    };
+
+    // Sessions that have not revealed secrets get masked results
+    let summaries_for = |session: &Session| {
+        if session.reveal_secrets {
+            record.summaries.clone()
+        } else {
+            redacted_summaries.clone()
+        }
+    };
+    if let Some(other_session_ids) =
+        get_workspace_sessions(&session.workspace_id, &sessions, Some(session_id))
+    {
+        for other_session_id in other_session_ids {
+            let Ok(other_session) = sessions.get_session(&other_session_id) else {
+                continue;
+            };
+            let execution_status = ExecutionStatus {
+                request_or_group_id: request_or_group_id.clone(),
+                running: false,
+                results: Some(summaries_for(other_session)),
+            };
+            app.emit_to(&other_session_id, "update_execution", &execution_status)
+                .unwrap();
+        }
+    }
+
+    Ok(summaries_for(session))
 }
 
 #[tauri::command]
-async fn clear_cached_authorization(authorization_id: String) -> bool {
-    clear_oauth2_token_from_cache(authorization_id.as_str()).await
+async fn prune_run_history(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    options: RunHistoryPruneOptions,
+) -> Result<usize, ApicizeAppError> {
+    get_session_run_history(&sessions_state, &workspaces_state, session_id)
+        .await?
+        .prune(&options)
 }
 
 #[tauri::command]
-fn get_clipboard_image(clipboard: State<Clipboard>) -> Result<Vec<u8>, String> {
-    match clipboard.has_image() {
-        Ok(has_image) => {
-            if has_image {
-                clipboard.read_image_binary()
-            } else {
-                Err(String::from("Clipboard does not contain an image"))
-            }
+async fn diff_results(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    left: ResultReference,
+    right: ResultReference,
+    options: Option<ResultDiffOptions>,
+) -> Result<ResultDiff, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    let info = workspaces.get_workspace_info(&session.workspace_id)?;
+
+    let get_detail = |reference: &ResultReference| match &reference.run_id {
+        Some(run_id) => {
+            RunHistory::for_workbook(&info.file_name)?.load_detail(run_id, reference.index)
         }
-        Err(msg) => Err(msg),
+        None => workspaces.get_result_detail(
+            &session.workspace_id,
+            &reference.request_or_group_id,
+            reference.index,
+        ),
+    };
+
+    let diff = ResultDiff::compare(
+        &get_detail(&left)?,
+        &get_detail(&right)?,
+        &options.unwrap_or_default(),
+    )?;
+    if session.reveal_secrets {
+        Ok(diff)
+    } else {
+        workspaces
+            .get_redactor(&session.workspace_id)?
+            .redact_serialized(&diff)
     }
 }
 
 #[tauri::command]
-fn get_clipboard_file_data(paths: Vec<String>) -> Result<DroppedFile, String> {
-    for file_path in paths {
-        match exists(&file_path) {
-            Ok(found) => {
-                if found {
-                    match fs::read(&file_path) {
-                        Ok(data) => {
-                            return Ok(DroppedFile::from_data(&file_path, data));
-                        }
-                        Err(err) => {
-                            return Err(err.to_string());
-                        }
-                    }
-                }
+async fn export_results_har(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    request_or_group_id: &str,
+    run_id: Option<String>,
+    file_name: String,
+) -> Result<(), ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    let info = workspaces.get_workspace_info(&session.workspace_id)?;
+
+    let redactor = if session.reveal_secrets {
+        None
+    } else {
+        Some(workspaces.get_redactor(&session.workspace_id)?)
+    };
+
+    let har = match run_id {
+        Some(run_id) => {
+            let record = RunHistory::for_workbook(&info.file_name)?.load(&run_id)?;
+            har::export(
+                &record.entry.name,
+                &record.details,
+                Some(record.entry.timestamp),
+                redactor.as_ref(),
+            )?
+        }
+        None => match info.result_details.get(request_or_group_id) {
+            Some(details) => {
+                let name = info
+                    .workspace
+                    .requests
+                    .entities
+                    .get(request_or_group_id)
+                    .map(|e| e.get_name().to_string())
+                    .unwrap_or_default();
+                har::export(&name, details, None, redactor.as_ref())?
             }
-            Err(err) => {
-                return Err(err.to_string());
+            None => {
+                return Err(ApicizeAppError::InvalidRequest(
+                    request_or_group_id.to_string(),
+                ))
             }
-        }
-    }
-    Err("Unable to locate dropped paths".to_string())
+        },
+    };
+
+    fs::write(&file_name, serde_json::to_string_pretty(&har)?)?;
+    Ok(())
 }
 
 #[tauri::command]
-fn set_pkce_port(state: State<'_, AuthState>, port: u16) {
-    let mut pkce = state.pkce.lock().unwrap();
-    pkce.activate_listener(port);
+async fn generate_report(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    request_id: &str,
+    index: usize,
+    format: ExecutionReportFormat,
+) -> Result<String, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    let report = workspaces.generate_report(&session.workspace_id, request_id, index, format)?;
+    if session.reveal_secrets {
+        Ok(report)
+    } else {
+        let redactor = workspaces.get_redactor(&session.workspace_id)?;
+        Ok(redactor.redact(&report))
+    }
 }
 
 #[tauri::command]
-fn generate_authorization_info(
-    state: State<'_, AuthState>,
-    auth: OAuth2PkceInfo,
-    port: u16,
-) -> Result<OAuth2PkceRequest, String> {
-    let pkce = state.pkce.lock().unwrap();
-    pkce.generate_authorization_info(auth, port)
+async fn set_reveal_secrets(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    session_id: &str,
+    reveal: bool,
+) -> Result<(), ApicizeAppError> {
+    let mut sessions = sessions_state.sessions.write().await;
+    let session = sessions.get_session_mut(session_id)?;
+    if session.reveal_secrets != reveal {
+        session.reveal_secrets = reveal;
+        // Have the session reload logs so they reflect the new setting
+        app.emit_to(session_id, "list_logs", ()).unwrap();
+    }
+    Ok(())
 }
 
-// #[tauri::command]
-// fn launch_pkce_window(
-//     state: State<'_, AppState>,
-//     auth: OAuth2PkceInfo,
-//     port: u16,
-// ) -> Result<OAuth2PkceRequest, String> {
-//     let mut pkce = state.pkce.lock().unwrap();
-//     pkce.launch_pkce_window(auth, port)
-// }
-
 #[tauri::command]
-async fn retrieve_access_token(
-    token_url: &str,
-    redirect_url: &str,
-    code: &str,
-    client_id: &str,
-    verifier: &str,
-) -> Result<PkceTokenResult, String> {
-    OAuth2PkceService::retrieve_access_token(token_url, redirect_url, code, client_id, verifier)
-        .await
+async fn write_clipboard_text(
+    clipboard: State<'_, Clipboard>,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    text: String,
+) -> Result<(), ApicizeAppError> {
+    let text = {
+        let sessions = sessions_state.sessions.read().await;
+        let session = sessions.get_session(session_id)?;
+        if session.reveal_secrets {
+            text
+        } else {
+            let workspaces = workspaces_state.workspaces.read().await;
+            let redactor = workspaces.get_redactor(&session.workspace_id)?;
+            redactor.redact(&text)
+        }
+    };
+    clipboard
+        .write_text(text)
+        .map_err(ApicizeAppError::InvalidOperation)
 }
 
 #[tauri::command]
-async fn refresh_token(
-    token_url: &str,
-    refresh_token: &str,
-    client_id: &str,
-) -> Result<PkceTokenResult, String> {
-    OAuth2PkceService::refresh_token(token_url, refresh_token, client_id).await
+async fn store_token(
+    app: AppHandle,
+    authorization_id: String,
+    token_info: serde_json::Value,
+) -> Result<(), ApicizeAppError> {
+    let cached = serde_json::from_value::<CachedTokenInfo>(token_info.clone())?;
+    store_oauth2_token_in_cache(&authorization_id, cached).await;
+    if let Some(store) = get_secret_store(&app) {
+        secrets::store_token(store.as_ref(), &authorization_id, Some(token_info))?;
+    }
+    Ok(())
 }
 
 #[tauri::command]
-fn is_release_mode() -> bool {
-    !cfg!(debug_assertions)
+async fn clear_all_cached_authorizations(app: AppHandle) -> usize {
+    if let Some(store) = get_secret_store(&app) {
+        if let Err(err) = secrets::clear_tokens(store.as_ref()) {
+            eprintln!("Unable to clear stored tokens: {err}");
+        }
+    }
+    clear_all_oauth2_tokens_from_cache().await
 }
 
-/// Retrieve other sessions for the updated workspace
-fn get_workspace_sessions(
-    workspace_id: &str,
-    sessions: &Sessions,
-    skip_session_id: Option<&str>,
-) -> Option<Vec<String>> {
-    let results = sessions
-        .sessions
-        .iter()
-        .filter_map(|(sid, s)| {
-            if let Some(skip) = skip_session_id {
-                if skip == sid.as_str() {
-                    return None;
+#[tauri::command]
+async fn clear_cached_authorization(app: AppHandle, authorization_id: String) -> bool {
+    if let Some(store) = get_secret_store(&app) {
+        if let Err(err) = secrets::store_token(store.as_ref(), &authorization_id, None) {
+            eprintln!("Unable to clear stored token: {err}");
+        }
+    }
+    clear_oauth2_token_from_cache(authorization_id.as_str()).await
+}
+
+/// Return the secret store, if configured and unlocked
+fn get_secret_store(app: &AppHandle) -> Option<Arc<dyn SecretStore>> {
+    app.try_state::<SecretsState>()
+        .and_then(|state| state.store.lock().ok().and_then(|store| store.clone()))
+}
+
+/// Open the secret store for the storage setting, unless it requires a passphrase
+fn open_secret_store(storage: SecretStorage) -> Option<Arc<dyn SecretStore>> {
+    match storage {
+        SecretStorage::Keyring => match EncryptedFileSecretStore::open_with_keyring() {
+            Ok(store) => Some(Arc::new(store)),
+            Err(err) => {
+                eprintln!("Unable to open keyring secret store: {err}");
+                None
+            }
+        },
+        SecretStorage::File | SecretStorage::EncryptedFile => None,
+    }
+}
+
+/// Load OAuth2 tokens saved in the secret store into the token cache
+fn restore_tokens(store: Arc<dyn SecretStore>) {
+    tauri::async_runtime::spawn(async move {
+        match secrets::load_tokens(store.as_ref()) {
+            Ok(tokens) => {
+                for (authorization_id, token_info) in tokens {
+                    store_oauth2_token_in_cache(&authorization_id, token_info).await;
                 }
             }
-            if workspace_id != s.workspace_id {
-                None
+            Err(err) => eprintln!("Unable to restore stored tokens: {err}"),
+        }
+    });
+}
+
+/// Replace the secret store, adding its vault parameters and tokens to open workspaces
+async fn set_secret_store(
+    app: &AppHandle,
+    store: Option<Arc<dyn SecretStore>>,
+) -> Result<(), ApicizeAppError> {
+    let secrets_state = app.state::<SecretsState>();
+    match secrets_state.store.lock() {
+        Ok(mut current) => *current = store.clone(),
+        Err(err) => return Err(ApicizeAppError::ConcurrencyError(err.to_string())),
+    }
+
+    let Some(store) = store else {
+        return Ok(());
+    };
+    restore_tokens(store.clone());
+
+    let sessions_state = app.state::<SessionsState>();
+    let workspaces_state = app.state::<WorkspacesState>();
+    let sessions = sessions_state.sessions.read().await;
+    let mut workspaces = workspaces_state.workspaces.write().await;
+    let workspace_ids = workspaces
+        .workspaces
+        .keys()
+        .cloned()
+        .collect::<Vec<String>>();
+    for workspace_id in workspace_ids {
+        let info = workspaces.get_workspace_info_mut(&workspace_id)?;
+        if secrets::restore_vault(store.as_ref(), &mut info.workspace)? {
+            info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+            dispatch_save_state(app, &sessions, &workspace_id, info, true);
+        }
+    }
+    Ok(())
+}
+
+/// Unlock the private parameters of the session's workbook; if the workbook has no
+/// encrypted private parameters yet, the new passphrase must be confirmed
+#[tauri::command]
+async fn unlock_private_parameters(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    passphrase: String,
+    confirmation: Option<String>,
+) -> Result<(), ApicizeAppError> {
+    if passphrase.is_empty() {
+        return Err(ApicizeAppError::SecretStoreError(
+            "a passphrase is required".to_string(),
+        ));
+    }
+
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let mut workspaces = workspaces_state.workspaces.write().await;
+    let info = workspaces.get_workspace_info_mut(&session.workspace_id)?;
+    if info.private_passphrase.is_some() {
+        return Ok(());
+    }
+
+    // An incorrect passphrase fails to decrypt, leaving the workspace untouched
+    let parameters = if info.file_name.is_empty() {
+        None
+    } else {
+        private_params::open(Path::new(&info.file_name), &passphrase)?
+    };
+    match parameters {
+        Some(parameters) => {
+            if parameters.add_to(&mut info.workspace, PRIVATE_SECTION)? {
+                info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+                dispatch_save_state(&app, &sessions, &session.workspace_id, info, true);
+            }
+        }
+        None => {
+            if confirmation.as_ref() != Some(&passphrase) {
+                return Err(ApicizeAppError::SecretStoreError(
+                    "the passphrase confirmation does not match".to_string(),
+                ));
+            }
+        }
+    }
+
+    info.private_passphrase = Some(passphrase);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_secret_store_status(
+    app: AppHandle,
+    settings_state: State<'_, SettingsState>,
+) -> Result<SecretStoreStatus, ApicizeAppError> {
+    let storage = settings_state.settings.read().await.secret_storage;
+    Ok(SecretStoreStatus {
+        storage,
+        unlocked: storage == SecretStorage::File || get_secret_store(&app).is_some(),
+    })
+}
+
+#[tauri::command]
+async fn unlock_secret_store(
+    app: AppHandle,
+    settings_state: State<'_, SettingsState>,
+    passphrase: String,
+    confirmation: Option<String>,
+) -> Result<SecretStoreStatus, ApicizeAppError> {
+    let storage = settings_state.settings.read().await.secret_storage;
+    let store: Arc<dyn SecretStore> = match storage {
+        SecretStorage::EncryptedFile => Arc::new(EncryptedFileSecretStore::open_with_passphrase(
+            &passphrase,
+            confirmation.as_deref(),
+        )?),
+        SecretStorage::Keyring => Arc::new(EncryptedFileSecretStore::open_with_keyring()?),
+        SecretStorage::File => {
+            return Err(ApicizeAppError::InvalidOperation(
+                "secret storage is not configured".to_string(),
+            ))
+        }
+    };
+    set_secret_store(&app, Some(store)).await?;
+    Ok(SecretStoreStatus {
+        storage,
+        unlocked: true,
+    })
+}
+
+#[tauri::command]
+fn get_clipboard_image(clipboard: State<Clipboard>) -> Result<Vec<u8>, String> {
+    match clipboard.has_image() {
+        Ok(has_image) => {
+            if has_image {
+                clipboard.read_image_binary()
             } else {
-                Some(sid.clone())
+                Err(String::from("Clipboard does not contain an image"))
             }
-        })
-        .collect::<Vec<String>>();
-    match results.is_empty() {
-        true => None,
-        false => Some(results),
+        }
+        Err(msg) => Err(msg),
     }
 }
 
 #[tauri::command]
-async fn get_request_active_authorization(
+fn get_clipboard_file_data(paths: Vec<String>) -> Result<DroppedFile, String> {
+    for file_path in paths {
+        match exists(&file_path) {
+            Ok(found) => {
+                if found {
+                    match fs::read(&file_path) {
+                        Ok(data) => {
+                            return Ok(DroppedFile::from_data(&file_path, data));
+                        }
+                        Err(err) => {
+                            return Err(err.to_string());
+                        }
+                    }
+                }
+            }
+            Err(err) => {
+                return Err(err.to_string());
+            }
+        }
+    }
+    Err("Unable to locate dropped paths".to_string())
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn add_request_from_clipboard_curl(
+    app: AppHandle,
+    clipboard: State<'_, Clipboard>,
     sessions_state: State<'_, SessionsState>,
     workspaces_state: State<'_, WorkspacesState>,
     session_id: &str,
-    request_id: &str,
-) -> Result<Option<Authorization>, ApicizeAppError> {
+    relative_to_id: Option<&str>,
+    relative_position: Option<IndexedEntityPosition>,
+) -> Result<CurlRequestResult, ApicizeAppError> {
+    let command = clipboard
+        .read_text()
+        .map_err(ApicizeAppError::ImportError)?;
+    let parsed = curl::parse(&command)?;
+    let warnings = parsed.warnings.clone();
+
     let sessions = sessions_state.sessions.read().await;
     let session = sessions.get_session(session_id)?;
-    let workspaces = workspaces_state.workspaces.read().await;
-    Ok(workspaces
-        .get_request_active_authorization(&session.workspace_id, request_id)?
-        .clone())
+    let workspace_id = session.workspace_id.clone();
+    let mut workspaces = workspaces_state.workspaces.write().await;
+
+    let id = workspaces.add_request_from_curl(
+        &workspace_id,
+        relative_to_id,
+        relative_position,
+        parsed,
+    )?;
+
+    let info = workspaces.get_workspace_info_mut(&workspace_id)?;
+    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+
+    dispatch_save_state(&app, &sessions, &workspace_id, info, true);
+
+    Ok(CurlRequestResult { id, warnings })
 }
 
 #[tauri::command]
-async fn get_request_active_data(
+#[allow(clippy::too_many_arguments)]
+async fn add_requests_from_har(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    data: &str,
+    relative_to_id: Option<&str>,
+    relative_position: Option<IndexedEntityPosition>,
+) -> Result<Vec<String>, ApicizeAppError> {
+    let har = har::build_groups(&import::parse_document(data)?)?;
+
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspace_id = session.workspace_id.clone();
+    let mut workspaces = workspaces_state.workspaces.write().await;
+
+    workspaces.add_request_groups(&workspace_id, relative_to_id, relative_position, har.groups)?;
+
+    let info = workspaces.get_workspace_info_mut(&workspace_id)?;
+    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+
+    dispatch_save_state(&app, &sessions, &workspace_id, info, true);
+
+    Ok(har.warnings)
+}
+
+#[tauri::command]
+async fn get_mock(
     sessions_state: State<'_, SessionsState>,
     workspaces_state: State<'_, WorkspacesState>,
     session_id: &str,
     request_id: &str,
-) -> Result<Option<ExternalData>, ApicizeAppError> {
+) -> Result<Option<MockResponse>, ApicizeAppError> {
     let sessions = sessions_state.sessions.read().await;
     let session = sessions.get_session(session_id)?;
     let workspaces = workspaces_state.workspaces.read().await;
-    Ok(workspaces
-        .get_request_active_data(&session.workspace_id, request_id)?
-        .clone())
+    workspaces.get_mock(&session.workspace_id, request_id)
 }
 
 #[tauri::command]
-async fn list(
+async fn update_mock(
+    app: AppHandle,
     sessions_state: State<'_, SessionsState>,
     workspaces_state: State<'_, WorkspacesState>,
     session_id: &str,
-    entity_type: EntityType,
-    request_id: Option<&str>,
-) -> Result<Entities, ApicizeAppError> {
+    request_id: &str,
+    mock: Option<MockResponse>,
+) -> Result<(), ApicizeAppError> {
     let sessions = sessions_state.sessions.read().await;
     let session = sessions.get_session(session_id)?;
-    let workspaces = workspaces_state.workspaces.read().await;
-    let result = match entity_type {
-        EntityType::Parameters => {
-            Entities::Parameters(workspaces.list_parameters(&session.workspace_id, request_id)?)
-        }
-        EntityType::Data => Entities::Data {
-            data: workspaces.list_data(&session.workspace_id)?,
-        },
-        _ => {
-            return Err(ApicizeAppError::InvalidTypeForOperation(entity_type));
-        }
+    let mut workspaces = workspaces_state.workspaces.write().await;
+    workspaces.update_mock(&session.workspace_id, request_id, mock)?;
+
+    let info = workspaces.get_workspace_info(&session.workspace_id)?;
+    dispatch_save_state(&app, &sessions, &session.workspace_id, info, false);
+    Ok(())
+}
+
+#[tauri::command]
+async fn start_mock_server(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    mock_state: State<'_, MockState>,
+    session_id: &str,
+    port: u16,
+) -> Result<MockServerStatus, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let status = mock_state
+        .server
+        .lock()
+        .unwrap()
+        .start(&session.workspace_id, port)?;
+    app.emit("mock-server-status", Some(&status)).unwrap();
+    Ok(status)
+}
+
+#[tauri::command]
+fn stop_mock_server(app: AppHandle, mock_state: State<'_, MockState>) {
+    mock_state.server.lock().unwrap().stop();
+    app.emit("mock-server-status", None::<MockServerStatus>)
+        .unwrap();
+}
+
+#[tauri::command]
+fn get_mock_server_status(mock_state: State<'_, MockState>) -> Option<MockServerStatus> {
+    mock_state.server.lock().unwrap().get_status()
+}
+
+#[tauri::command]
+fn start_recording_proxy(
+    app: AppHandle,
+    recorder_state: State<'_, RecorderState>,
+    port: u16,
+    bind_address: Option<&str>,
+) -> Result<RecorderStatus, ApicizeAppError> {
+    let status = recorder_state
+        .proxy
+        .lock()
+        .unwrap()
+        .start(port, bind_address)?;
+    app.emit("recorder-status", Some(&status)).unwrap();
+    Ok(status)
+}
+
+#[tauri::command]
+fn stop_recording_proxy(app: AppHandle, recorder_state: State<'_, RecorderState>) {
+    recorder_state.proxy.lock().unwrap().stop();
+    app.emit("recorder-status", None::<RecorderStatus>).unwrap();
+}
+
+#[tauri::command]
+fn get_recording_proxy_status(recorder_state: State<'_, RecorderState>) -> Option<RecorderStatus> {
+    recorder_state.proxy.lock().unwrap().get_status()
+}
+
+#[tauri::command]
+async fn list_recorded_exchanges(
+    recorder_state: State<'_, RecorderState>,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+) -> Result<Vec<RecordedExchange>, ApicizeAppError> {
+    let exchanges = recorder_state.proxy.lock().unwrap().list_exchanges();
+    match get_trace_redactor(&sessions_state, &workspaces_state, session_id).await? {
+        Some(redactor) => Ok(exchanges
+            .iter()
+            .map(|e| redactor.redact_exchange(e))
+            .collect()),
+        None => Ok(exchanges),
+    }
+}
+
+#[tauri::command]
+fn clear_recorded_exchanges(recorder_state: State<'_, RecorderState>) {
+    recorder_state.proxy.lock().unwrap().clear_exchanges();
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn add_requests_from_recording(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    recorder_state: State<'_, RecorderState>,
+    session_id: &str,
+    exchange_ids: Vec<String>,
+    relative_to_id: Option<&str>,
+    relative_position: Option<IndexedEntityPosition>,
+) -> Result<Vec<String>, ApicizeAppError> {
+    // Captured exchanges are converted the same way as HTTP Archive entries
+    let document = recorder_state.proxy.lock().unwrap().to_har(&exchange_ids)?;
+    let har = har::build_groups(&document)?;
+
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspace_id = session.workspace_id.clone();
+    let mut workspaces = workspaces_state.workspaces.write().await;
+
+    workspaces.add_request_groups(&workspace_id, relative_to_id, relative_position, har.groups)?;
+
+    let info = workspaces.get_workspace_info_mut(&workspace_id)?;
+    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+
+    dispatch_save_state(&app, &sessions, &workspace_id, info, true);
+
+    Ok(har.warnings)
+}
+
+#[tauri::command]
+fn set_pkce_port(state: State<'_, AuthState>, port: u16) {
+    let mut pkce = state.pkce.lock().unwrap();
+    pkce.activate_listener(port);
+}
+
+#[tauri::command]
+fn generate_authorization_info(
+    state: State<'_, AuthState>,
+    auth: OAuth2PkceInfo,
+    port: u16,
+) -> Result<OAuth2PkceRequest, String> {
+    let pkce = state.pkce.lock().unwrap();
+    pkce.generate_authorization_info(auth, port)
+}
+
+// #[tauri::command]
+// fn launch_pkce_window(
+//     state: State<'_, AppState>,
+//     auth: OAuth2PkceInfo,
+//     port: u16,
+// ) -> Result<OAuth2PkceRequest, String> {
+//     let mut pkce = state.pkce.lock().unwrap();
+//     pkce.launch_pkce_window(auth, port)
+// }
+
+#[tauri::command]
+async fn retrieve_access_token(
+    token_url: &str,
+    redirect_url: &str,
+    code: &str,
+    client_id: &str,
+    verifier: &str,
+) -> Result<PkceTokenResult, String> {
+    OAuth2PkceService::retrieve_access_token(token_url, redirect_url, code, client_id, verifier)
+        .await
+}
+
+#[tauri::command]
+async fn refresh_token(
+    token_url: &str,
+    refresh_token: &str,
+    client_id: &str,
+) -> Result<PkceTokenResult, String> {
+    OAuth2PkceService::refresh_token(token_url, refresh_token, client_id).await
+}
+
+#[tauri::command]
+fn is_release_mode() -> bool {
+    !cfg!(debug_assertions)
+}
+
+/// Retrieve other sessions for the updated workspace
+fn get_workspace_sessions(
+    workspace_id: &str,
+    sessions: &Sessions,
+    skip_session_id: Option<&str>,
+) -> Option<Vec<String>> {
+    let results = sessions
+        .sessions
+        .iter()
+        .filter_map(|(sid, s)| {
+            if let Some(skip) = skip_session_id {
+                if skip == sid.as_str() {
+                    return None;
+                }
+            }
+            if workspace_id != s.workspace_id {
+                None
+            } else {
+                Some(sid.clone())
+            }
+        })
+        .collect::<Vec<String>>();
+    match results.is_empty() {
+        true => None,
+        false => Some(results),
+    }
+}
+
+#[tauri::command]
+async fn get_request_active_authorization(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    request_id: &str,
+) -> Result<Option<Authorization>, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    Ok(workspaces
+        .get_request_active_authorization(&session.workspace_id, request_id)?
+        .clone())
+}
+
+#[tauri::command]
+async fn get_request_active_data(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    request_id: &str,
+) -> Result<Option<ExternalData>, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    Ok(workspaces
+        .get_request_active_data(&session.workspace_id, request_id)?
+        .clone())
+}
+
+#[tauri::command]
+async fn get_request_snippet(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    request_id: &str,
+    format: SnippetFormat,
+) -> Result<RequestSnippet, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    let resolved = workspaces.get_resolved_request(&session.workspace_id, request_id)?;
+    let snippet = RequestSnippet::render(&resolved, format)?;
+    if session.reveal_secrets {
+        Ok(snippet)
+    } else {
+        workspaces
+            .get_redactor(&session.workspace_id)?
+            .redact_serialized(&snippet)
+    }
+}
+
+#[tauri::command]
+async fn search(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    options: SearchOptions,
+) -> Result<Vec<SearchResult>, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    let redactor = if session.reveal_secrets {
+        None
+    } else {
+        Some(workspaces.get_redactor(&session.workspace_id)?)
+    };
+    workspaces.search(&session.workspace_id, &options, redactor)
+}
+
+#[tauri::command]
+async fn preview_replace(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    options: ReplaceOptions,
+) -> Result<Vec<ReplacePreview>, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    workspaces.preview_replace(&session.workspace_id, &options)
+}
+
+#[tauri::command]
+async fn apply_replace(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    options: ReplaceOptions,
+) -> Result<Vec<ReplacePreview>, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspace_id = session.workspace_id.clone();
+    let mut workspaces = workspaces_state.workspaces.write().await;
+
+    let changes = workspaces.apply_replace(&workspace_id, &options)?;
+    if changes.is_empty() {
+        return Ok(changes);
+    }
+
+    // Send updated requests to every session, since any of them may have one open
+    if let Some(session_ids) = get_workspace_sessions(&workspace_id, &sessions, None) {
+        let mut updated_ids = HashSet::<&str>::new();
+        for change in &changes {
+            if !updated_ids.insert(&change.id) {
+                continue;
+            }
+            let events = [
+                Entity::RequestEntry(workspaces.get_request_entry(&workspace_id, &change.id)?),
+                Entity::Headers(workspaces.get_request_headers(&workspace_id, &change.id)?),
+                Entity::Body(workspaces.get_request_body(&workspace_id, &change.id)?),
+            ];
+            for session_id in &session_ids {
+                for event in &events {
+                    app.emit_to(session_id, "update", event).unwrap();
+                }
+            }
+        }
+    }
+
+    let info = workspaces.get_workspace_info_mut(&workspace_id)?;
+    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+    dispatch_save_state(&app, &sessions, &workspace_id, info, true);
+
+    Ok(changes)
+}
+
+#[tauri::command]
+async fn list(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    entity_type: EntityType,
+    request_id: Option<&str>,
+) -> Result<Entities, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    let result = match entity_type {
+        EntityType::Parameters => {
+            Entities::Parameters(workspaces.list_parameters(&session.workspace_id, request_id)?)
+        }
+        EntityType::Data => Entities::Data {
+            data: workspaces.list_data(&session.workspace_id)?,
+        },
+        _ => {
+            return Err(ApicizeAppError::InvalidTypeForOperation(entity_type));
+        }
+    };
+    Ok(result)
+}
+
+#[tauri::command]
+async fn get(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    entity_type: EntityType,
+    entity_id: &str,
+) -> Result<Entity, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    Ok(match entity_type {
+        EntityType::RequestEntry => {
+            Entity::RequestEntry(workspaces.get_request_entry(&session.workspace_id, entity_id)?)
+        }
+        EntityType::Headers => {
+            Entity::Headers(workspaces.get_request_headers(&session.workspace_id, entity_id)?)
+        }
+        EntityType::Body => {
+            Entity::Body(workspaces.get_request_body(&session.workspace_id, entity_id)?)
+        }
+        EntityType::Scenario => Entity::Scenario(
+            workspaces
+                .get_scenario(&session.workspace_id, entity_id)?
+                .clone(),
+        ),
+        EntityType::Authorization => {
+            Entity::Authorization(workspaces.get_authorization(&session.workspace_id, entity_id)?)
+        }
+        EntityType::Certificate => {
+            Entity::Certificate(workspaces.get_certificate(&session.workspace_id, entity_id)?)
+        }
+        EntityType::Proxy => Entity::Proxy(
+            workspaces
+                .get_proxy(&session.workspace_id, entity_id)?
+                .clone(),
+        ),
+        EntityType::Data => Entity::Data(workspaces.get_data(&session.workspace_id, entity_id)?),
+        _ => {
+            return Err(ApicizeAppError::InvalidTypeForOperation(entity_type));
+        }
+    })
+}
+
+#[tauri::command]
+async fn get_title(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    entity_type: EntityType,
+    entity_id: &str,
+) -> Result<String, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    Ok(match entity_type {
+        EntityType::RequestEntry => {
+            workspaces.get_request_title(&session.workspace_id, entity_id)?
+        }
+        EntityType::Scenario => workspaces.get_scenario_title(&session.workspace_id, entity_id)?,
+        EntityType::Authorization => {
+            workspaces.get_authorization_title(&session.workspace_id, entity_id)?
+        }
+        EntityType::Certificate => {
+            workspaces.get_certificate_title(&session.workspace_id, entity_id)?
+        }
+        EntityType::Proxy => workspaces.get_proxy_title(&session.workspace_id, entity_id)?,
+        EntityType::Data => workspaces.get_data_title(&session.workspace_id, entity_id)?,
+        _ => {
+            return Err(ApicizeAppError::InvalidTypeForOperation(entity_type));
+        }
+    })
+}
+
+#[tauri::command]
+async fn get_dirty(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+) -> Result<bool, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    workspaces.get_dirty(&session.workspace_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn add(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    entity_type: EntityType,
+    relative_to_id: Option<&str>,
+    relative_position: Option<IndexedEntityPosition>,
+    clone_from_id: Option<&str>,
+) -> Result<String, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspace_id = session.workspace_id.clone();
+    let mut workspaces = workspaces_state.workspaces.write().await;
+
+    let id = match entity_type {
+        EntityType::Request => workspaces.add_request(
+            &workspace_id,
+            relative_to_id,
+            relative_position,
+            clone_from_id,
+        ),
+        EntityType::Group => workspaces.add_request_group(
+            &workspace_id,
+            relative_to_id,
+            relative_position,
+            clone_from_id,
+        ),
+        EntityType::Scenario => workspaces.add_scenario(
+            &workspace_id,
+            relative_to_id,
+            relative_position,
+            clone_from_id,
+        ),
+        EntityType::Authorization => workspaces.add_authorization(
+            &workspace_id,
+            relative_to_id,
+            relative_position,
+            clone_from_id,
+        ),
+        EntityType::Certificate => workspaces.add_certificate(
+            &workspace_id,
+            relative_to_id,
+            relative_position,
+            clone_from_id,
+        ),
+        EntityType::Proxy => workspaces.add_proxy(
+            &workspace_id,
+            relative_to_id,
+            relative_position,
+            clone_from_id,
+        ),
+        EntityType::Data => {
+            let result = workspaces.add_data(&workspace_id, clone_from_id)?;
+            dispatch_data_list_notification(
+                &app,
+                &sessions,
+                &workspace_id,
+                workspaces.list_data(&workspace_id)?,
+            );
+            Ok(result)
+        }
+        _ => Err(ApicizeAppError::InvalidOperation(format!(
+            "Unable to add {entity_type}",
+        ))),
+    }?;
+
+    let info = workspaces.get_workspace_info_mut(&workspace_id)?;
+    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+
+    dispatch_save_state(&app, &sessions, &workspace_id, info, true);
+
+    Ok(id)
+}
+
+#[tauri::command]
+async fn update(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    entity: Entity,
+) -> Result<(), ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let mut workspaces = workspaces_state.workspaces.write().await;
+
+    let other_sessions = get_workspace_sessions(&session.workspace_id, &sessions, Some(session_id));
+
+    let event = if other_sessions.is_some() {
+        Some(entity.clone())
+    } else {
+        None
+    };
+
+    let mut extra_event: Option<Entity> = None;
+
+    // Perform updates and, when applicable, navigation updates
+    let result = match entity {
+        Entity::Request(request) => workspaces.update_request(&session.workspace_id, request),
+        Entity::Group(group) => workspaces.update_group(&session.workspace_id, group),
+        Entity::Headers(header_info) => {
+            let request_info =
+                workspaces.update_request_headers(&session.workspace_id, header_info)?;
+            extra_event = Some(Entity::Request(request_info));
+            Ok(None)
+        }
+        Entity::Body(body_info) => {
+            let request_info = workspaces.update_request_body(&session.workspace_id, body_info)?;
+            extra_event = Some(Entity::Request(request_info));
+            Ok(None)
+        }
+        Entity::Scenario(scenario) => workspaces.update_scenario(&session.workspace_id, scenario),
+        Entity::Authorization(authorization) => {
+            workspaces.update_authorization(&session.workspace_id, authorization)
+        }
+        Entity::Certificate(certificate) => {
+            workspaces.update_certificate(&session.workspace_id, certificate)
+        }
+        Entity::Proxy(proxy) => workspaces.update_proxy(&session.workspace_id, proxy),
+        Entity::Data(data) => workspaces.update_data(&session.workspace_id, data),
+        Entity::Defaults(defaults) => {
+            workspaces.update_defaults(&session.workspace_id, defaults)?;
+            Ok(None)
+        }
+        _ => {
+            return Err(ApicizeAppError::InvalidOperation(
+                "Unable to perform update on entity".to_owned(),
+            ));
+        }
+    }?;
+
+    let info = &workspaces.get_workspace_info(&session.workspace_id)?;
+
+    let display_name = &info.display_name;
+
+    // Publish any navigation updates to all sessions/windows for this workspace
+    if let Some(session_ids) = get_workspace_sessions(&session.workspace_id, &sessions, None) {
+        for session_id in session_ids {
+            if let Some(updated_navigation) = &result {
+                app.emit_to(&session_id, "navigation_entry", updated_navigation)
+                    .unwrap();
+            }
+            app.emit_to(&session_id, "dirty", true).unwrap();
+            if let Some(w) = app.get_webview_window(&session_id) {
+                w.set_title(format_window_title(display_name, true).as_str())
+                    .unwrap();
+            }
+        }
+    }
+
+    // Publish updates on all other sessions than the one sending the update
+    // so they can update themselves
+    if let Some(other_session_ids) = other_sessions {
+        if let Some(event_to_send) = event {
+            for other_session_id in other_session_ids {
+                app.emit_to(&other_session_id, "update", &event_to_send)
+                    .unwrap();
+                if let Some(extra_event_to_send) = &extra_event {
+                    app.emit_to(&other_session_id, "update", extra_event_to_send)
+                        .unwrap();
+                }
+            }
+        }
+    }
+
+    dispatch_save_state(&app, &sessions, &session.workspace_id, info, false);
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn delete(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    entity_type: EntityType,
+    entity_id: &str,
+) -> Result<(), ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let mut workspaces = workspaces_state.workspaces.write().await;
+
+    let workspace_id = &session.workspace_id;
+
+    match entity_type {
+        EntityType::RequestEntry => workspaces.delete_request_entry(workspace_id, entity_id),
+        EntityType::Request => workspaces.delete_request_entry(workspace_id, entity_id),
+        EntityType::Group => workspaces.delete_request_entry(workspace_id, entity_id),
+        EntityType::Body => workspaces.delete_request_entry(workspace_id, entity_id),
+        EntityType::Scenario => workspaces.delete_scenario(workspace_id, entity_id),
+        EntityType::Authorization => workspaces.delete_authorization(workspace_id, entity_id),
+        EntityType::Certificate => workspaces.delete_certificate(workspace_id, entity_id),
+        EntityType::Proxy => workspaces.delete_proxy(workspace_id, entity_id),
+        EntityType::Data => {
+            workspaces.delete_data(workspace_id, entity_id)?;
+            dispatch_data_list_notification(
+                &app,
+                &sessions,
+                workspace_id,
+                workspaces.list_data(workspace_id)?,
+            );
+            Ok(())
+        }
+        _ => Err(ApicizeAppError::InvalidOperation(
+            "Unable to perform delete on entity".to_owned(),
+        )),
+    }?;
+
+    let info = workspaces.get_workspace_info_mut(workspace_id)?;
+    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+    dispatch_save_state(&app, &sessions, workspace_id, info, true);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn move_entity(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    entity_type: EntityType,
+    entity_id: &str,
+    relative_to_id: &str,
+    relative_position: IndexedEntityPosition,
+) -> Result<Vec<String>, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspace_id = session.workspace_id.clone();
+    let mut workspaces = workspaces_state.workspaces.write().await;
+
+    let was_moved = match entity_type {
+        EntityType::RequestEntry => workspaces.move_request_entry(
+            &workspace_id,
+            entity_id,
+            relative_to_id,
+            relative_position,
+        ),
+        EntityType::Request => workspaces.move_request_entry(
+            &workspace_id,
+            entity_id,
+            relative_to_id,
+            relative_position,
+        ),
+        EntityType::Group => workspaces.move_request_entry(
+            &workspace_id,
+            entity_id,
+            relative_to_id,
+            relative_position,
+        ),
+        EntityType::Scenario => {
+            workspaces.move_scenario(&workspace_id, entity_id, relative_to_id, relative_position)
+        }
+        EntityType::Authorization => workspaces.move_authorization(
+            &workspace_id,
+            entity_id,
+            relative_to_id,
+            relative_position,
+        ),
+        EntityType::Certificate => {
+            workspaces.move_certificate(&workspace_id, entity_id, relative_to_id, relative_position)
+        }
+        EntityType::Proxy => {
+            workspaces.move_proxy(&workspace_id, entity_id, relative_to_id, relative_position)
+        }
+        _ => Err(ApicizeAppError::InvalidOperation(format!(
+            "Unable to move {entity_type}",
+        ))),
+    }?;
+
+    let results = if was_moved {
+        let info = workspaces.get_workspace_info_mut(&workspace_id)?;
+        info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+
+        dispatch_save_state(&app, &sessions, &workspace_id, info, true);
+
+        workspaces.find_parent_ids(&workspace_id, entity_type, entity_id)?
+    } else {
+        vec![]
     };
-    Ok(result)
+
+    Ok(results)
 }
 
 #[tauri::command]
-async fn get(
+async fn delete_request_entries(
+    app: AppHandle,
     sessions_state: State<'_, SessionsState>,
     workspaces_state: State<'_, WorkspacesState>,
     session_id: &str,
-    entity_type: EntityType,
-    entity_id: &str,
-) -> Result<Entity, ApicizeAppError> {
+    entity_ids: Vec<String>,
+) -> Result<(), ApicizeAppError> {
     let sessions = sessions_state.sessions.read().await;
     let session = sessions.get_session(session_id)?;
-    let workspaces = workspaces_state.workspaces.read().await;
-    Ok(match entity_type {
-        EntityType::RequestEntry => {
-            Entity::RequestEntry(workspaces.get_request_entry(&session.workspace_id, entity_id)?)
-        }
-        EntityType::Headers => {
-            Entity::Headers(workspaces.get_request_headers(&session.workspace_id, entity_id)?)
-        }
-        EntityType::Body => {
-            Entity::Body(workspaces.get_request_body(&session.workspace_id, entity_id)?)
-        }
-        EntityType::Scenario => Entity::Scenario(
-            workspaces
-                .get_scenario(&session.workspace_id, entity_id)?
-                .clone(),
-        ),
-        EntityType::Authorization => {
-            Entity::Authorization(workspaces.get_authorization(&session.workspace_id, entity_id)?)
-        }
-        EntityType::Certificate => {
-            Entity::Certificate(workspaces.get_certificate(&session.workspace_id, entity_id)?)
-        }
-        EntityType::Proxy => Entity::Proxy(
-            workspaces
-                .get_proxy(&session.workspace_id, entity_id)?
-                .clone(),
-        ),
-        EntityType::Data => Entity::Data(workspaces.get_data(&session.workspace_id, entity_id)?),
-        _ => {
-            return Err(ApicizeAppError::InvalidTypeForOperation(entity_type));
-        }
-    })
+    let workspace_id = session.workspace_id.clone();
+    let mut workspaces = workspaces_state.workspaces.write().await;
+
+    workspaces.delete_request_entries(&workspace_id, &entity_ids)?;
+
+    let info = workspaces.get_workspace_info_mut(&workspace_id)?;
+    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+    dispatch_save_state(&app, &sessions, &workspace_id, info, true);
+    Ok(())
 }
 
 #[tauri::command]
-async fn get_title(
+#[allow(clippy::too_many_arguments)]
+async fn move_request_entries(
+    app: AppHandle,
     sessions_state: State<'_, SessionsState>,
     workspaces_state: State<'_, WorkspacesState>,
     session_id: &str,
-    entity_type: EntityType,
-    entity_id: &str,
-) -> Result<String, ApicizeAppError> {
+    entity_ids: Vec<String>,
+    relative_to_id: &str,
+    relative_position: IndexedEntityPosition,
+) -> Result<Vec<String>, ApicizeAppError> {
     let sessions = sessions_state.sessions.read().await;
     let session = sessions.get_session(session_id)?;
-    let workspaces = workspaces_state.workspaces.read().await;
-    Ok(match entity_type {
-        EntityType::RequestEntry => {
-            workspaces.get_request_title(&session.workspace_id, entity_id)?
-        }
-        EntityType::Scenario => workspaces.get_scenario_title(&session.workspace_id, entity_id)?,
-        EntityType::Authorization => {
-            workspaces.get_authorization_title(&session.workspace_id, entity_id)?
-        }
-        EntityType::Certificate => {
-            workspaces.get_certificate_title(&session.workspace_id, entity_id)?
-        }
-        EntityType::Proxy => workspaces.get_proxy_title(&session.workspace_id, entity_id)?,
-        EntityType::Data => workspaces.get_data_title(&session.workspace_id, entity_id)?,
-        _ => {
-            return Err(ApicizeAppError::InvalidTypeForOperation(entity_type));
+    let workspace_id = session.workspace_id.clone();
+    let mut workspaces = workspaces_state.workspaces.write().await;
+
+    let moved_ids = workspaces.move_request_entries(
+        &workspace_id,
+        &entity_ids,
+        relative_to_id,
+        relative_position,
+    )?;
+
+    // Moved entries share a parent, so return the parent IDs of the first one
+    match moved_ids.first() {
+        Some(moved_id) => {
+            let info = workspaces.get_workspace_info_mut(&workspace_id)?;
+            info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+            dispatch_save_state(&app, &sessions, &workspace_id, info, true);
+            workspaces.find_parent_ids(&workspace_id, EntityType::RequestEntry, moved_id)
         }
-    })
+        None => Ok(vec![]),
+    }
 }
 
 #[tauri::command]
-async fn get_dirty(
+async fn duplicate_request_entries(
+    app: AppHandle,
     sessions_state: State<'_, SessionsState>,
     workspaces_state: State<'_, WorkspacesState>,
     session_id: &str,
-) -> Result<bool, ApicizeAppError> {
+    entity_ids: Vec<String>,
+) -> Result<Vec<String>, ApicizeAppError> {
     let sessions = sessions_state.sessions.read().await;
     let session = sessions.get_session(session_id)?;
-    let workspaces = workspaces_state.workspaces.read().await;
-    workspaces.get_dirty(&session.workspace_id)
+    let workspace_id = session.workspace_id.clone();
+    let mut workspaces = workspaces_state.workspaces.write().await;
+
+    let new_ids = workspaces.duplicate_request_entries(&workspace_id, &entity_ids)?;
+
+    let info = workspaces.get_workspace_info_mut(&workspace_id)?;
+    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+    dispatch_save_state(&app, &sessions, &workspace_id, info, true);
+    Ok(new_ids)
 }
 
-#[allow(clippy::too_many_arguments)]
 #[tauri::command]
-async fn add(
+async fn assign_request_entries_parameter(
     app: AppHandle,
     sessions_state: State<'_, SessionsState>,
     workspaces_state: State<'_, WorkspacesState>,
     session_id: &str,
+    entity_ids: Vec<String>,
     entity_type: EntityType,
-    relative_to_id: Option<&str>,
-    relative_position: Option<IndexedEntityPosition>,
-    clone_from_id: Option<&str>,
-) -> Result<String, ApicizeAppError> {
+    selection: Option<Selection>,
+) -> Result<(), ApicizeAppError> {
     let sessions = sessions_state.sessions.read().await;
     let session = sessions.get_session(session_id)?;
     let workspace_id = session.workspace_id.clone();
     let mut workspaces = workspaces_state.workspaces.write().await;
 
-    let id = match entity_type {
-        EntityType::Request => workspaces.add_request(
-            &workspace_id,
-            relative_to_id,
-            relative_position,
-            clone_from_id,
-        ),
-        EntityType::Group => workspaces.add_request_group(
-            &workspace_id,
-            relative_to_id,
-            relative_position,
-            clone_from_id,
-        ),
-        EntityType::Scenario => workspaces.add_scenario(
-            &workspace_id,
-            relative_to_id,
-            relative_position,
-            clone_from_id,
-        ),
-        EntityType::Authorization => workspaces.add_authorization(
-            &workspace_id,
-            relative_to_id,
-            relative_position,
-            clone_from_id,
-        ),
-        EntityType::Certificate => workspaces.add_certificate(
-            &workspace_id,
-            relative_to_id,
-            relative_position,
-            clone_from_id,
-        ),
-        EntityType::Proxy => workspaces.add_proxy(
-            &workspace_id,
-            relative_to_id,
-            relative_position,
-            clone_from_id,
-        ),
-        EntityType::Data => {
-            let result = workspaces.add_data(&workspace_id, clone_from_id)?;
-            dispatch_data_list_notification(
-                &app,
-                &sessions,
-                &workspace_id,
-                workspaces.list_data(&workspace_id)?,
-            );
-            Ok(result)
+    workspaces.assign_request_entries_parameter(
+        &workspace_id,
+        &entity_ids,
+        entity_type,
+        selection,
+    )?;
+
+    // Send updated entries to every session, since any of them may have one open
+    if let Some(session_ids) = get_workspace_sessions(&workspace_id, &sessions, None) {
+        for entity_id in &entity_ids {
+            let event =
+                Entity::RequestEntry(workspaces.get_request_entry(&workspace_id, entity_id)?);
+            for session_id in &session_ids {
+                app.emit_to(session_id, "update", &event).unwrap();
+            }
         }
-        _ => Err(ApicizeAppError::InvalidOperation(format!(
-            "Unable to add {entity_type}",
-        ))),
-    }?;
+    }
 
     let info = workspaces.get_workspace_info_mut(&workspace_id)?;
     info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
-
     dispatch_save_state(&app, &sessions, &workspace_id, info, true);
+    Ok(())
+}
 
-    Ok(id)
+#[tauri::command]
+async fn undo(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+) -> Result<HistoryState, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let mut workspaces = workspaces_state.workspaces.write().await;
+
+    let entity_id = workspaces.undo(&session.workspace_id)?;
+    dispatch_history_restore(
+        &app,
+        &sessions,
+        &workspaces,
+        &session.workspace_id,
+        entity_id,
+    )
 }
 
 #[tauri::command]
-async fn update(
+async fn redo(
     app: AppHandle,
     sessions_state: State<'_, SessionsState>,
     workspaces_state: State<'_, WorkspacesState>,
     session_id: &str,
-    entity: Entity,
-) -> Result<(), ApicizeAppError> {
+) -> Result<HistoryState, ApicizeAppError> {
     let sessions = sessions_state.sessions.read().await;
     let session = sessions.get_session(session_id)?;
     let mut workspaces = workspaces_state.workspaces.write().await;
 
-    let other_sessions = get_workspace_sessions(&session.workspace_id, &sessions, Some(session_id));
+    let entity_id = workspaces.redo(&session.workspace_id)?;
+    dispatch_history_restore(
+        &app,
+        &sessions,
+        &workspaces,
+        &session.workspace_id,
+        entity_id,
+    )
+}
 
-    let event = if other_sessions.is_some() {
-        Some(entity.clone())
-    } else {
-        None
+#[tauri::command]
+async fn get_history_state(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+) -> Result<HistoryState, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    let info = workspaces.get_workspace_info(&session.workspace_id)?;
+    Ok(info.history.get_state(None))
+}
+
+/// Record the state of a workspace's workbook files, so that later external changes are reported
+fn watch_workbook(app: &AppHandle, workspace_id: &str, info: &WorkspaceInfo) {
+    if let Some(state) = app.try_state::<WatcherState>() {
+        if let Ok(mut watcher) = state.watcher.lock() {
+            watcher.watch(workspace_id, &info.file_name, &info.includes);
+        }
+    }
+}
+
+/// Notify sessions of workbooks whose files were changed outside of Apicize
+async fn check_workbook_changes(app: &AppHandle) {
+    let (Some(sessions_state), Some(workspaces_state), Some(watcher_state)) = (
+        app.try_state::<SessionsState>(),
+        app.try_state::<WorkspacesState>(),
+        app.try_state::<WatcherState>(),
+    ) else {
+        return;
     };
 
-    let mut extra_event: Option<Entity> = None;
+    let sessions = sessions_state.sessions.read().await;
+    let workspaces = workspaces_state.workspaces.read().await;
+    let Ok(mut watcher) = watcher_state.watcher.lock() else {
+        return;
+    };
 
-    // Perform updates and, when applicable, navigation updates
-    let result = match entity {
-        Entity::Request(request) => workspaces.update_request(&session.workspace_id, request),
-        Entity::Group(group) => workspaces.update_group(&session.workspace_id, group),
-        Entity::Headers(header_info) => {
-            let request_info =
-                workspaces.update_request_headers(&session.workspace_id, header_info)?;
-            extra_event = Some(Entity::Request(request_info));
-            Ok(None)
-        }
-        Entity::Body(body_info) => {
-            let request_info = workspaces.update_request_body(&session.workspace_id, body_info)?;
-            extra_event = Some(Entity::Request(request_info));
-            Ok(None)
-        }
-        Entity::Scenario(scenario) => workspaces.update_scenario(&session.workspace_id, scenario),
-        Entity::Authorization(authorization) => {
-            workspaces.update_authorization(&session.workspace_id, authorization)
-        }
-        Entity::Certificate(certificate) => {
-            workspaces.update_certificate(&session.workspace_id, certificate)
+    for workspace_id in watcher.get_workspace_ids() {
+        let Ok(info) = workspaces.get_workspace_info(&workspace_id) else {
+            watcher.unwatch(&workspace_id);
+            continue;
+        };
+        let file_names = watcher.check(&workspace_id);
+        if file_names.is_empty() {
+            continue;
         }
-        Entity::Proxy(proxy) => workspaces.update_proxy(&session.workspace_id, proxy),
-        Entity::Data(data) => workspaces.update_data(&session.workspace_id, data),
-        Entity::Defaults(defaults) => {
-            workspaces.update_defaults(&session.workspace_id, defaults)?;
-            Ok(None)
+
+        let change = WorkbookChange {
+            workspace_id: workspace_id.clone(),
+            file_names,
+            dirty: info.dirty,
+        };
+        if let Some(session_ids) = get_workspace_sessions(&workspace_id, &sessions, None) {
+            for session_id in session_ids {
+                app.emit_to(&session_id, "workbook_changed", &change)
+                    .unwrap();
+            }
         }
-        _ => {
-            return Err(ApicizeAppError::InvalidOperation(
-                "Unable to perform update on entity".to_owned(),
-            ));
+    }
+}
+
+/// Open a workbook and its included files from disk, including parameters held in the
+/// secret store and, if the workbook's passphrase is unlocked, encrypted private parameters
+fn open_workbook_file(
+    app: &AppHandle,
+    file_name: &str,
+    private_passphrase: Option<&str>,
+) -> Result<(Workspace, Vec<WorkbookInclude>), ApicizeAppError> {
+    let workbook_file_name = PathBuf::from(file_name);
+    let (mut workspace, workbook_includes) = includes::open_workbook(&workbook_file_name)?;
+    if let Some(store) = get_secret_store(app) {
+        secrets::restore_vault(store.as_ref(), &mut workspace)?;
+    }
+    if let Some(passphrase) = private_passphrase {
+        if let Some(parameters) = private_params::open(&workbook_file_name, passphrase)? {
+            parameters.add_to(&mut workspace, PRIVATE_SECTION)?;
         }
-    }?;
+    }
+    Ok((workspace, workbook_includes))
+}
 
-    let info = &workspaces.get_workspace_info(&session.workspace_id)?;
+#[tauri::command]
+async fn reload_workspace(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+) -> Result<HistoryState, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let mut workspaces = workspaces_state.workspaces.write().await;
+    let info = workspaces.get_workspace_info_mut(&session.workspace_id)?;
+    if info.file_name.is_empty() {
+        return Err(ApicizeAppError::FileNameRequired());
+    }
 
-    let display_name = &info.display_name;
+    // Edits made before the reload cannot be undone against the reloaded workbook
+    (info.workspace, info.includes) =
+        open_workbook_file(&app, &info.file_name, info.private_passphrase.as_deref())?;
+    info.mocks = mock::load_mocks(&info.file_name);
+    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+    info.history = Default::default();
+    info.base = Some(info.workspace.clone());
+    info.base_mocks = info.mocks.clone();
+    info.merge_theirs = None;
+    info.merge_theirs_mocks.clear();
+    info.dirty = false;
+    watch_workbook(&app, &session.workspace_id, info);
+
+    dispatch_history_restore(&app, &sessions, &workspaces, &session.workspace_id, None)
+}
+
+#[tauri::command]
+async fn merge_workspace_changes(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+) -> Result<MergeResult, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let mut workspaces = workspaces_state.workspaces.write().await;
+    let info = workspaces.get_workspace_info_mut(&session.workspace_id)?;
+    if info.file_name.is_empty() {
+        return Err(ApicizeAppError::FileNameRequired());
+    }
 
-    // Publish any navigation updates to all sessions/windows for this workspace
-    if let Some(session_ids) = get_workspace_sessions(&session.workspace_id, &sessions, None) {
-        for session_id in session_ids {
-            if let Some(updated_navigation) = &result {
-                app.emit_to(&session_id, "navigation_entry", updated_navigation)
-                    .unwrap();
-            }
-            app.emit_to(&session_id, "dirty", true).unwrap();
-            if let Some(w) = app.get_webview_window(&session_id) {
-                w.set_title(format_window_title(display_name, true).as_str())
-                    .unwrap();
+    let (theirs, their_includes) =
+        open_workbook_file(&app, &info.file_name, info.private_passphrase.as_deref())?;
+    let their_mocks = mock::load_mocks(&info.file_name);
+    info.history.record_with_mocks(
+        &info.workspace,
+        &info.mocks,
+        "Merge workbook changes",
+        None,
+        None,
+    );
+    let mocks = info.mocks.clone();
+    let merged = merge::merge_workspaces(&mut info.workspace, info.base.as_ref(), &theirs)
+        .and_then(|mut result| {
+            merge::merge_mocks(
+                &mut info.mocks,
+                info.base.as_ref().map(|_| &info.base_mocks),
+                &their_mocks,
+                &info.workspace.requests,
+                &mut result,
+            )?;
+            Ok(result)
+        });
+    let result = match merged {
+        Ok(result) => result,
+        Err(err) => {
+            if let Some(workspace) = info.history.discard_last() {
+                info.workspace = workspace;
             }
+            info.mocks = mocks;
+            return Err(err);
         }
-    }
+    };
 
-    // Publish updates on all other sessions than the one sending the update
-    // so they can update themselves
-    if let Some(other_session_ids) = other_sessions {
-        if let Some(event_to_send) = event {
-            for other_session_id in other_session_ids {
-                app.emit_to(&other_session_id, "update", &event_to_send)
-                    .unwrap();
-                if let Some(extra_event_to_send) = &extra_event {
-                    app.emit_to(&other_session_id, "update", extra_event_to_send)
-                        .unwrap();
-                }
-            }
+    // Groups moved to included files externally stay in those files, unless already included
+    for include in their_includes {
+        if info
+            .workspace
+            .requests
+            .entities
+            .contains_key(&include.group_id)
+            && !info.includes.iter().any(|i| i.group_id == include.group_id)
+        {
+            info.includes.push(include);
         }
     }
 
-    dispatch_save_state(&app, &sessions, &session.workspace_id, info, false);
+    // The workbook on disk becomes the base for later merges, and is kept for resolving
+    // conflicts until the workspace is saved or reloaded
+    info.base = Some(theirs.clone());
+    info.base_mocks = their_mocks.clone();
+    info.merge_theirs = Some(theirs);
+    info.merge_theirs_mocks = their_mocks;
+    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+    info.dirty = true;
+    watch_workbook(&app, &session.workspace_id, info);
 
-    Ok(())
+    dispatch_history_restore(&app, &sessions, &workspaces, &session.workspace_id, None)?;
+    Ok(result)
 }
 
 #[tauri::command]
-async fn delete(
+async fn resolve_merge_conflict(
     app: AppHandle,
     sessions_state: State<'_, SessionsState>,
     workspaces_state: State<'_, WorkspacesState>,
     session_id: &str,
     entity_type: EntityType,
     entity_id: &str,
-) -> Result<(), ApicizeAppError> {
+    resolution: MergeResolution,
+) -> Result<HistoryState, ApicizeAppError> {
     let sessions = sessions_state.sessions.read().await;
     let session = sessions.get_session(session_id)?;
     let mut workspaces = workspaces_state.workspaces.write().await;
+    let info = workspaces.get_workspace_info_mut(&session.workspace_id)?;
+    let Some(theirs) = &info.merge_theirs else {
+        return Err(ApicizeAppError::InvalidOperation(
+            "workbook changes have not been merged".to_string(),
+        ));
+    };
 
-    let workspace_id = &session.workspace_id;
+    info.history.record_with_mocks(
+        &info.workspace,
+        &info.mocks,
+        "Resolve merge conflict",
+        Some(entity_id),
+        None,
+    );
+    merge::resolve_conflict(
+        &mut info.workspace,
+        theirs,
+        &entity_type,
+        entity_id,
+        resolution,
+    )?;
+    // A request's mock response is resolved along with the request
+    if matches!(entity_type, EntityType::RequestEntry | EntityType::Request) {
+        merge::resolve_mock_conflict(
+            &mut info.mocks,
+            &info.merge_theirs_mocks,
+            entity_id,
+            resolution,
+        );
+    }
+    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+    info.dirty = true;
+
+    dispatch_history_restore(
+        &app,
+        &sessions,
+        &workspaces,
+        &session.workspace_id,
+        Some(entity_id.to_string()),
+    )
+}
 
-    match entity_type {
-        EntityType::RequestEntry => workspaces.delete_request_entry(workspace_id, entity_id),
-        EntityType::Request => workspaces.delete_request_entry(workspace_id, entity_id),
-        EntityType::Group => workspaces.delete_request_entry(workspace_id, entity_id),
-        EntityType::Body => workspaces.delete_request_entry(workspace_id, entity_id),
-        EntityType::Scenario => workspaces.delete_scenario(workspace_id, entity_id),
-        EntityType::Authorization => workspaces.delete_authorization(workspace_id, entity_id),
-        EntityType::Certificate => workspaces.delete_certificate(workspace_id, entity_id),
-        EntityType::Proxy => workspaces.delete_proxy(workspace_id, entity_id),
-        EntityType::Data => {
-            workspaces.delete_data(workspace_id, entity_id)?;
-            dispatch_data_list_notification(
-                &app,
-                &sessions,
-                workspace_id,
-                workspaces.list_data(workspace_id)?,
-            );
-            Ok(())
-        }
-        _ => Err(ApicizeAppError::InvalidOperation(
-            "Unable to perform delete on entity".to_owned(),
-        )),
-    }?;
+#[tauri::command]
+async fn keep_workspace_changes(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+) -> Result<(), ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let mut workspaces = workspaces_state.workspaces.write().await;
+    let info = workspaces.get_workspace_info_mut(&session.workspace_id)?;
+    if info.file_name.is_empty() {
+        return Err(ApicizeAppError::FileNameRequired());
+    }
 
-    let info = workspaces.get_workspace_info_mut(workspace_id)?;
-    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
-    dispatch_save_state(&app, &sessions, workspace_id, info, true);
+    // The workspace is marked as modified so that saving it replaces the external changes,
+    // and the workbook on disk becomes the base for later merges
+    info.base =
+        Some(open_workbook_file(&app, &info.file_name, info.private_passphrase.as_deref())?.0);
+    info.base_mocks = mock::load_mocks(&info.file_name);
+    info.merge_theirs = None;
+    info.merge_theirs_mocks.clear();
+    info.dirty = true;
+    watch_workbook(&app, &session.workspace_id, info);
+    dispatch_save_state(&app, &sessions, &session.workspace_id, info, false);
     Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
 #[tauri::command]
-async fn move_entity(
+async fn list_request_group_files(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+) -> Result<Vec<WorkbookInclude>, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    let session = sessions.get_session(session_id)?;
+    let workspaces = workspaces_state.workspaces.read().await;
+    let info = workspaces.get_workspace_info(&session.workspace_id)?;
+    Ok(info.includes.clone())
+}
+
+#[tauri::command]
+async fn set_request_group_file(
     app: AppHandle,
     sessions_state: State<'_, SessionsState>,
     workspaces_state: State<'_, WorkspacesState>,
     session_id: &str,
-    entity_type: EntityType,
-    entity_id: &str,
-    relative_to_id: &str,
-    relative_position: IndexedEntityPosition,
-) -> Result<Vec<String>, ApicizeAppError> {
+    group_id: &str,
+    file_name: Option<String>,
+) -> Result<(), ApicizeAppError> {
     let sessions = sessions_state.sessions.read().await;
     let session = sessions.get_session(session_id)?;
-    let workspace_id = session.workspace_id.clone();
     let mut workspaces = workspaces_state.workspaces.write().await;
+    let info = workspaces.get_workspace_info_mut(&session.workspace_id)?;
+    includes::set_include(
+        &info.workspace.requests,
+        &mut info.includes,
+        group_id,
+        file_name,
+    )?;
+    info.dirty = true;
+    dispatch_save_state(&app, &sessions, &session.workspace_id, info, false);
+    Ok(())
+}
 
-    let was_moved = match entity_type {
-        EntityType::RequestEntry => workspaces.move_request_entry(
-            &workspace_id,
-            entity_id,
-            relative_to_id,
-            relative_position,
-        ),
-        EntityType::Request => workspaces.move_request_entry(
-            &workspace_id,
-            entity_id,
-            relative_to_id,
-            relative_position,
-        ),
-        EntityType::Group => workspaces.move_request_entry(
-            &workspace_id,
-            entity_id,
-            relative_to_id,
-            relative_position,
-        ),
-        EntityType::Scenario => {
-            workspaces.move_scenario(&workspace_id, entity_id, relative_to_id, relative_position)
-        }
-        EntityType::Authorization => workspaces.move_authorization(
-            &workspace_id,
-            entity_id,
-            relative_to_id,
-            relative_position,
-        ),
-        EntityType::Certificate => {
-            workspaces.move_certificate(&workspace_id, entity_id, relative_to_id, relative_position)
-        }
-        EntityType::Proxy => {
-            workspaces.move_proxy(&workspace_id, entity_id, relative_to_id, relative_position)
+/// Save snapshots of workspaces with unsaved changes for crash recovery
+async fn save_recovery_snapshots(app: &AppHandle) {
+    let (Some(workspaces_state), Some(recovery_state)) = (
+        app.try_state::<WorkspacesState>(),
+        app.try_state::<RecoveryState>(),
+    ) else {
+        return;
+    };
+
+    let workspaces = workspaces_state.workspaces.read().await;
+    if let Ok(mut recovery) = recovery_state.recovery.lock() {
+        if let Err(err) = recovery.update(&workspaces.workspaces) {
+            eprintln!("Unable to save recovery snapshots: {err}");
         }
-        _ => Err(ApicizeAppError::InvalidOperation(format!(
-            "Unable to move {entity_type}",
-        ))),
-    }?;
+    }
+}
 
-    let results = if was_moved {
-        let info = workspaces.get_workspace_info_mut(&workspace_id)?;
-        info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+#[tauri::command]
+fn list_recoveries(
+    recovery_state: State<'_, RecoveryState>,
+) -> Result<Vec<RecoveryInfo>, ApicizeAppError> {
+    match recovery_state.available.lock() {
+        Ok(available) => Ok(available.clone()),
+        Err(err) => Err(ApicizeAppError::ConcurrencyError(err.to_string())),
+    }
+}
 
-        dispatch_save_state(&app, &sessions, &workspace_id, info, true);
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn restore_recovery(
+    app: AppHandle,
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    settings_state: State<'_, SettingsState>,
+    recovery_state: State<'_, RecoveryState>,
+    session_id: &str,
+    recovery_id: &str,
+) -> Result<(), ApicizeAppError> {
+    let file_name = WorkspaceRecovery::get_file_name(recovery_id)?;
+    let sessions = &mut sessions_state.sessions.write().await;
+    let workspaces = &mut workspaces_state.workspaces.write().await;
+    let settings = &mut settings_state.settings.write().await;
 
-        workspaces.find_parent_ids(&workspace_id, entity_type, entity_id)?
-    } else {
-        vec![]
-    };
+    // Open the workbook the snapshot was taken from in the session, then apply the snapshot
+    create_workspace(
+        app.clone(),
+        sessions,
+        workspaces,
+        settings,
+        if file_name.is_empty() {
+            None
+        } else {
+            Some(file_name)
+        },
+        None,
+        true,
+        Some(session_id.to_string()),
+        false,
+    )?;
 
-    Ok(results)
+    let workspace_id = sessions.get_session(session_id)?.workspace_id.clone();
+    let info = workspaces.get_workspace_info_mut(&workspace_id)?;
+    WorkspaceRecovery::restore(recovery_id, &mut info.workspace, &mut info.mocks)?;
+    info.navigation = Navigation::new(&info.workspace, &info.executing_request_ids);
+    info.dirty = true;
+
+    WorkspaceRecovery::discard(recovery_id)?;
+    if let Ok(mut available) = recovery_state.available.lock() {
+        available.retain(|r| r.recovery_id != recovery_id);
+    }
+
+    dispatch_save_state(&app, sessions, &workspace_id, info, true);
+    Ok(())
 }
 
 #[tauri::command]
-async fn list_logs() -> Result<Vec<ReqwestEvent>, ApicizeAppError> {
-    match REQWEST_LOGGER.get() {
-        Some(logger) => logger.get_logs(),
-        None => Err(ApicizeAppError::ConcurrencyError(
+fn discard_recovery(
+    recovery_state: State<'_, RecoveryState>,
+    recovery_id: &str,
+) -> Result<(), ApicizeAppError> {
+    WorkspaceRecovery::discard(recovery_id)?;
+    if let Ok(mut available) = recovery_state.available.lock() {
+        available.retain(|r| r.recovery_id != recovery_id);
+    }
+    Ok(())
+}
+
+/// Notify all sessions of a workspace that its state was restored from undo/redo history
+fn dispatch_history_restore(
+    app: &AppHandle,
+    sessions: &Sessions,
+    workspaces: &Workspaces,
+    workspace_id: &str,
+    entity_id: Option<String>,
+) -> Result<HistoryState, ApicizeAppError> {
+    let info = workspaces.get_workspace_info(workspace_id)?;
+    let state = info.history.get_state(entity_id);
+
+    dispatch_save_state(app, sessions, workspace_id, info, true);
+    dispatch_data_list_notification(app, sessions, workspace_id, info.workspace.data.clone());
+
+    if let Some(session_ids) = get_workspace_sessions(workspace_id, sessions, None) {
+        for session_id in session_ids {
+            app.emit_to(&session_id, "history", &state).unwrap();
+        }
+    }
+
+    Ok(state)
+}
+
+/// Return a redactor for network trace and recorded output sent to the session, or None
+/// if the session has revealed secrets
+async fn get_trace_redactor(
+    sessions_state: &State<'_, SessionsState>,
+    workspaces_state: &State<'_, WorkspacesState>,
+    session_id: &str,
+) -> Result<Option<Redactor>, ApicizeAppError> {
+    let sessions = sessions_state.sessions.read().await;
+    if sessions.get_session(session_id)?.reveal_secrets {
+        Ok(None)
+    } else {
+        let workspaces = workspaces_state.workspaces.read().await;
+        Ok(Some(workspaces.get_all_redactor()))
+    }
+}
+
+#[tauri::command]
+async fn list_logs(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+) -> Result<Vec<ReqwestEvent>, ApicizeAppError> {
+    let Some(logger) = REQWEST_LOGGER.get() else {
+        return Err(ApicizeAppError::ConcurrencyError(
             "Unable to access Reqwest logger".to_string(),
-        )),
+        ));
+    };
+    let logs = logger.get_logs()?;
+    match get_trace_redactor(&sessions_state, &workspaces_state, session_id).await? {
+        Some(redactor) => Ok(logs.iter().map(|e| redactor.redact_event(e)).collect()),
+        None => Ok(logs),
     }
 }
 
+#[tauri::command]
+async fn query_logs(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    query: TraceQuery,
+) -> Result<TracePage, ApicizeAppError> {
+    let Some(logger) = REQWEST_LOGGER.get() else {
+        return Err(ApicizeAppError::ConcurrencyError(
+            "Unable to access Reqwest logger".to_string(),
+        ));
+    };
+    let redactor = get_trace_redactor(&sessions_state, &workspaces_state, session_id).await?;
+    Ok(logger.query_logs(&query, redactor.as_ref()))
+}
+
 #[tauri::command]
 async fn clear_logs() -> Result<(), ApicizeAppError> {
     match REQWEST_LOGGER.get() {
@@ -1651,6 +3409,54 @@ async fn clear_logs() -> Result<(), ApicizeAppError> {
     }
 }
 
+#[tauri::command]
+async fn export_trace(
+    sessions_state: State<'_, SessionsState>,
+    workspaces_state: State<'_, WorkspacesState>,
+    session_id: &str,
+    run_id: &str,
+    file_name: String,
+    options: Option<TraceExportOptions>,
+) -> Result<usize, ApicizeAppError> {
+    let Some(logger) = REQWEST_LOGGER.get() else {
+        return Err(ApicizeAppError::ConcurrencyError(
+            "Unable to access Reqwest logger".to_string(),
+        ));
+    };
+
+    let events = logger.get_run_events(run_id);
+    if events.is_empty() {
+        return Err(ApicizeAppError::InvalidOperation(format!(
+            "No network trace is available for run {run_id}"
+        )));
+    }
+
+    let request_or_group_id = events.iter().find_map(|e| e.get_request_or_group_id());
+    let name = {
+        let sessions = sessions_state.sessions.read().await;
+        let session = sessions.get_session(session_id)?;
+        let workspaces = workspaces_state.workspaces.read().await;
+        let info = workspaces.get_workspace_info(&session.workspace_id)?;
+        request_or_group_id
+            .map(|id| match info.workspace.requests.entities.get(id) {
+                Some(entry) => entry.get_title(),
+                None => id.to_string(),
+            })
+            .unwrap_or_default()
+    };
+
+    let redactor = get_trace_redactor(&sessions_state, &workspaces_state, session_id).await?;
+    let exported = trace_export::export(
+        run_id,
+        &name,
+        &events,
+        &options.unwrap_or_default(),
+        redactor.as_ref(),
+    )?;
+    fs::write(&file_name, exported)?;
+    Ok(events.len())
+}
+
 #[tauri::command]
 async fn get_entity_type(
     sessions_state: State<'_, SessionsState>,
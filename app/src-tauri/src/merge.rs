@@ -0,0 +1,852 @@
+//! Merge submodule
+//!
+//! This submodule merges workbook changes made outside of Apicize into an open workspace,
+//! using a three-way merge of each entity against the workbook as last loaded or saved
+
+use std::collections::VecDeque;
+
+use apicize_lib::{IndexedEntities, RequestEntry, Workspace};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::ApicizeAppError, mock::MockResponse, workspaces::EntityType};
+
+/// Entity changed (or reordered amongst its siblings) both in the open workspace and on
+/// disk, where the open workspace's version has been kept
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeConflict {
+    pub entity_type: EntityType,
+    pub id: String,
+    pub name: String,
+    /// Entity as last loaded or saved, if it existed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<Value>,
+    /// Entity in the open workspace, if not deleted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ours: Option<Value>,
+    /// Entity on disk, if not deleted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theirs: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeResult {
+    /// Number of entities added, updated, moved or deleted, and sibling lists reordered,
+    /// from disk
+    pub applied: usize,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// How to resolve a merge conflict
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeResolution {
+    /// Keep the open workspace's version
+    Ours,
+    /// Use the version on disk
+    Theirs,
+}
+
+/// Merge changes from disk (theirs) into the open workspace (ours), relative to the workbook
+/// as last loaded or saved (base).  Changes made only on one side are applied, entities
+/// changed on both sides are reported as conflicts with the open workspace's version kept.
+/// Without a base, entities only on disk are added and differing entities are conflicts
+pub fn merge_workspaces(
+    ours: &mut Workspace,
+    base: Option<&Workspace>,
+    theirs: &Workspace,
+) -> Result<MergeResult, ApicizeAppError> {
+    let mut result = MergeResult::default();
+    merge_entities(
+        &mut ours.requests,
+        base.map(|b| &b.requests),
+        &theirs.requests,
+        get_request_entity_type,
+        &mut result,
+    )?;
+    merge_entities(
+        &mut ours.scenarios,
+        base.map(|b| &b.scenarios),
+        &theirs.scenarios,
+        |_| EntityType::Scenario,
+        &mut result,
+    )?;
+    merge_entities(
+        &mut ours.authorizations,
+        base.map(|b| &b.authorizations),
+        &theirs.authorizations,
+        |_| EntityType::Authorization,
+        &mut result,
+    )?;
+    merge_entities(
+        &mut ours.certificates,
+        base.map(|b| &b.certificates),
+        &theirs.certificates,
+        |_| EntityType::Certificate,
+        &mut result,
+    )?;
+    merge_entities(
+        &mut ours.proxies,
+        base.map(|b| &b.proxies),
+        &theirs.proxies,
+        |_| EntityType::Proxy,
+        &mut result,
+    )?;
+    merge_data(ours, base, theirs, &mut result)?;
+    Ok(result)
+}
+
+/// Resolve a conflict by keeping the open workspace's entity or replacing it with the
+/// version on disk (theirs), which is added, deleted or reordered as needed
+pub fn resolve_conflict(
+    ours: &mut Workspace,
+    theirs: &Workspace,
+    entity_type: &EntityType,
+    id: &str,
+    resolution: MergeResolution,
+) -> Result<(), ApicizeAppError> {
+    if resolution == MergeResolution::Ours {
+        return Ok(());
+    }
+    match entity_type {
+        EntityType::RequestEntry | EntityType::Request | EntityType::Group => {
+            accept_theirs(&mut ours.requests, &theirs.requests, id)
+        }
+        EntityType::Scenario => accept_theirs(&mut ours.scenarios, &theirs.scenarios, id),
+        EntityType::Authorization => {
+            accept_theirs(&mut ours.authorizations, &theirs.authorizations, id)
+        }
+        EntityType::Certificate => accept_theirs(&mut ours.certificates, &theirs.certificates, id),
+        EntityType::Proxy => accept_theirs(&mut ours.proxies, &theirs.proxies, id),
+        EntityType::Data => {
+            let index = ours.data.iter().position(|d| d.id == id);
+            match (theirs.data.iter().find(|d| d.id == id), index) {
+                (Some(data), Some(index)) => ours.data[index] = data.clone(),
+                (Some(data), None) => ours.data.push(data.clone()),
+                (None, Some(index)) => {
+                    ours.data.remove(index);
+                }
+                (None, None) => {}
+            }
+        }
+        _ => {
+            return Err(ApicizeAppError::InvalidTypeForOperation(
+                entity_type.clone(),
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Three-way merge of mock responses.  A mock changed on both sides is reported as a
+/// conflict of its request, unless the request itself conflicts
+pub fn merge_mocks(
+    ours: &mut FxHashMap<String, MockResponse>,
+    base: Option<&FxHashMap<String, MockResponse>>,
+    theirs: &FxHashMap<String, MockResponse>,
+    requests: &IndexedEntities<RequestEntry>,
+    result: &mut MergeResult,
+) -> Result<(), ApicizeAppError> {
+    let mut ids = ours
+        .keys()
+        .chain(theirs.keys())
+        .cloned()
+        .collect::<Vec<String>>();
+    ids.sort();
+    ids.dedup();
+
+    for id in ids {
+        let ours_mock = ours.get(&id);
+        let theirs_mock = theirs.get(&id);
+        let base_mock = base.and_then(|b| b.get(&id));
+        if ours_mock == theirs_mock || theirs_mock == base_mock {
+            continue;
+        }
+
+        if ours_mock == base_mock {
+            match theirs_mock {
+                Some(mock) => {
+                    ours.insert(id, mock.clone());
+                }
+                None => {
+                    ours.remove(&id);
+                }
+            }
+            result.applied += 1;
+        } else if !result.conflicts.iter().any(|c| c.id == id) {
+            result.conflicts.push(MergeConflict {
+                entity_type: EntityType::Request,
+                name: requests
+                    .entities
+                    .get(&id)
+                    .map(|request| request.get_name().to_string())
+                    .unwrap_or_default(),
+                base: base_mock.map(serde_json::to_value).transpose()?,
+                ours: ours_mock.map(serde_json::to_value).transpose()?,
+                theirs: theirs_mock.map(serde_json::to_value).transpose()?,
+                id,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Resolve a request's mock response by keeping the open workspace's or using the one on
+/// disk (theirs)
+pub fn resolve_mock_conflict(
+    ours: &mut FxHashMap<String, MockResponse>,
+    theirs: &FxHashMap<String, MockResponse>,
+    request_id: &str,
+    resolution: MergeResolution,
+) {
+    if resolution == MergeResolution::Ours {
+        return;
+    }
+    match theirs.get(request_id) {
+        Some(mock) => {
+            ours.insert(request_id.to_string(), mock.clone());
+        }
+        None => {
+            ours.remove(request_id);
+        }
+    }
+}
+
+fn get_request_entity_type(entry: &RequestEntry) -> EntityType {
+    match entry {
+        RequestEntry::Request(_) => EntityType::Request,
+        RequestEntry::Group(_) => EntityType::Group,
+    }
+}
+
+/// Three-way merge of one entity collection
+fn merge_entities<T: Clone + Serialize>(
+    ours: &mut IndexedEntities<T>,
+    base: Option<&IndexedEntities<T>>,
+    theirs: &IndexedEntities<T>,
+    entity_type: fn(&T) -> EntityType,
+    result: &mut MergeResult,
+) -> Result<(), ApicizeAppError> {
+    let theirs_locations = walk(theirs);
+    let base_locations = base.map(walk).unwrap_or_default();
+    let ours_locations = walk(ours);
+
+    // Visit entities in the open workspace's order, then those only on disk
+    let mut ids = ours_locations
+        .iter()
+        .map(|(id, _, _)| id.clone())
+        .collect::<Vec<String>>();
+    let mut seen = ids.iter().cloned().collect::<FxHashSet<String>>();
+    for (id, _, _) in &theirs_locations {
+        if seen.insert(id.clone()) {
+            ids.push(id.clone());
+        }
+    }
+
+    let mut deletions = Vec::new();
+    let mut additions = FxHashSet::default();
+    for id in ids {
+        let ours_entity = ours.entities.get(&id);
+        let theirs_entity = theirs.entities.get(&id);
+        let base_entity = base.and_then(|b| b.entities.get(&id));
+
+        let ours_value = ours_entity.map(serde_json::to_value).transpose()?;
+        let theirs_value = theirs_entity.map(serde_json::to_value).transpose()?;
+        let base_value = base_entity.map(serde_json::to_value).transpose()?;
+        if ours_value == theirs_value || theirs_value == base_value {
+            continue;
+        }
+
+        if ours_value == base_value {
+            // Changed only on disk, including entities added on disk
+            match theirs_entity {
+                Some(entity) => {
+                    if ours_entity.is_none() {
+                        additions.insert(id.clone());
+                    }
+                    ours.entities.insert(id.clone(), entity.clone());
+                }
+                None => deletions.push(id.clone()),
+            }
+            result.applied += 1;
+        } else {
+            let kind = ours_entity
+                .or(theirs_entity)
+                .or(base_entity)
+                .map(entity_type)
+                .unwrap_or(EntityType::RequestEntry);
+            result.conflicts.push(MergeConflict {
+                entity_type: kind,
+                id: id.clone(),
+                name: get_name(&ours_value)
+                    .or(get_name(&theirs_value))
+                    .or(get_name(&base_value))
+                    .unwrap_or_default(),
+                base: base_value,
+                ours: ours_value,
+                theirs: theirs_value,
+            });
+        }
+    }
+
+    // Entities added or moved on disk are placed where they are on disk, parents first
+    for (id, parent_id, previous_id) in &theirs_locations {
+        let moved = !additions.contains(id)
+            && ours.entities.contains_key(id)
+            && get_parent(&ours_locations, id) == get_parent(&base_locations, id)
+            && get_parent(&base_locations, id).is_some_and(|p| p != parent_id);
+        if additions.contains(id) || moved {
+            if moved {
+                remove_from_siblings(ours, id);
+                result.applied += 1;
+            }
+            place(
+                ours,
+                theirs,
+                id.clone(),
+                parent_id.clone(),
+                previous_id.clone(),
+            );
+        }
+    }
+
+    // Siblings reordered on disk are reordered in the open workspace, unless they were
+    // reordered differently there
+    if let Some(base) = base {
+        let mut parent_ids = vec![None];
+        parent_ids.extend(theirs.child_ids.keys().cloned().map(Some));
+        for parent_id in parent_ids {
+            let (Some(ours_siblings), Some(base_siblings), Some(theirs_siblings)) = (
+                get_siblings(ours, &parent_id),
+                get_siblings(base, &parent_id),
+                get_siblings(theirs, &parent_id),
+            ) else {
+                continue;
+            };
+            let common = |siblings: &Vec<String>| {
+                siblings
+                    .iter()
+                    .filter(|id| {
+                        ours_siblings.contains(id)
+                            && base_siblings.contains(id)
+                            && theirs_siblings.contains(id)
+                    })
+                    .cloned()
+                    .collect::<Vec<String>>()
+            };
+            let ours_order = common(ours_siblings);
+            let base_order = common(base_siblings);
+            let theirs_order = common(theirs_siblings);
+            if theirs_order == base_order || theirs_order == ours_order {
+                continue;
+            }
+
+            if ours_order == base_order {
+                if let Some(siblings) = get_siblings_mut(ours, &parent_id) {
+                    follow_order(siblings, &theirs_order);
+                }
+                result.applied += 1;
+            } else if let Some(id) = theirs_order
+                .iter()
+                .zip(&ours_order)
+                .find(|(t, o)| t != o)
+                .map(|(t, _)| t.clone())
+            {
+                // Reported against the first sibling out of place, unless it already conflicts
+                if result.conflicts.iter().any(|c| c.id == id) {
+                    continue;
+                }
+                let ours_value = ours
+                    .entities
+                    .get(&id)
+                    .map(serde_json::to_value)
+                    .transpose()?;
+                result.conflicts.push(MergeConflict {
+                    entity_type: ours
+                        .entities
+                        .get(&id)
+                        .map(entity_type)
+                        .unwrap_or(EntityType::RequestEntry),
+                    id: id.clone(),
+                    name: get_name(&ours_value).unwrap_or_default(),
+                    base: base
+                        .entities
+                        .get(&id)
+                        .map(serde_json::to_value)
+                        .transpose()?,
+                    ours: ours_value,
+                    theirs: theirs
+                        .entities
+                        .get(&id)
+                        .map(serde_json::to_value)
+                        .transpose()?,
+                });
+            }
+        }
+    }
+
+    // Deleting a group whose remaining children were kept would orphan them
+    for id in &deletions {
+        let keeps_children = ours
+            .child_ids
+            .get(id)
+            .is_some_and(|children| children.iter().any(|c| !deletions.contains(c)));
+        if keeps_children {
+            let ours_value = ours
+                .entities
+                .get(id)
+                .map(serde_json::to_value)
+                .transpose()?;
+            result.applied -= 1;
+            result.conflicts.push(MergeConflict {
+                entity_type: ours
+                    .entities
+                    .get(id)
+                    .map(entity_type)
+                    .unwrap_or(EntityType::RequestEntry),
+                id: id.clone(),
+                name: get_name(&ours_value).unwrap_or_default(),
+                base: base
+                    .and_then(|b| b.entities.get(id))
+                    .map(serde_json::to_value)
+                    .transpose()?,
+                ours: ours_value,
+                theirs: None,
+            });
+        } else {
+            remove_from_siblings(ours, id);
+            ours.entities.remove(id);
+            ours.child_ids.remove(id);
+        }
+    }
+    Ok(())
+}
+
+/// Three-way merge of external data definitions
+fn merge_data(
+    ours: &mut Workspace,
+    base: Option<&Workspace>,
+    theirs: &Workspace,
+    result: &mut MergeResult,
+) -> Result<(), ApicizeAppError> {
+    let mut ids = ours
+        .data
+        .iter()
+        .map(|d| d.id.clone())
+        .collect::<Vec<String>>();
+    for data in &theirs.data {
+        if !ids.contains(&data.id) {
+            ids.push(data.id.clone());
+        }
+    }
+
+    for id in ids {
+        let index = ours.data.iter().position(|d| d.id == id);
+        let theirs_data = theirs.data.iter().find(|d| d.id == id);
+        let ours_value = index
+            .map(|i| serde_json::to_value(&ours.data[i]))
+            .transpose()?;
+        let theirs_value = theirs_data.map(serde_json::to_value).transpose()?;
+        let base_value = base
+            .and_then(|b| b.data.iter().find(|d| d.id == id))
+            .map(serde_json::to_value)
+            .transpose()?;
+        if ours_value == theirs_value || theirs_value == base_value {
+            continue;
+        }
+
+        if ours_value == base_value {
+            match (theirs_data, index) {
+                (Some(data), Some(index)) => ours.data[index] = data.clone(),
+                (Some(data), None) => ours.data.push(data.clone()),
+                (None, Some(index)) => {
+                    ours.data.remove(index);
+                }
+                (None, None) => {}
+            }
+            result.applied += 1;
+        } else {
+            result.conflicts.push(MergeConflict {
+                entity_type: EntityType::Data,
+                id: id.clone(),
+                name: get_name(&ours_value)
+                    .or(get_name(&theirs_value))
+                    .or(get_name(&base_value))
+                    .unwrap_or_default(),
+                base: base_value,
+                ours: ours_value,
+                theirs: theirs_value,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Replace, add or remove an entity so that it matches theirs; if the entity has the same
+/// parent in both, its siblings are put in the same order as in theirs
+fn accept_theirs<T: Clone>(ours: &mut IndexedEntities<T>, theirs: &IndexedEntities<T>, id: &str) {
+    match theirs.entities.get(id) {
+        Some(entity) => {
+            let added = ours
+                .entities
+                .insert(id.to_string(), entity.clone())
+                .is_none();
+            let Some((_, parent_id, previous_id)) =
+                walk(theirs).into_iter().find(|(i, _, _)| i == id)
+            else {
+                return;
+            };
+            if added {
+                place(ours, theirs, id.to_string(), parent_id, previous_id);
+            } else if let Some(theirs_siblings) = get_siblings(theirs, &parent_id) {
+                let theirs_siblings = theirs_siblings.clone();
+                if let Some(siblings) = get_siblings_mut(ours, &parent_id)
+                    .filter(|siblings| siblings.iter().any(|s| s == id))
+                {
+                    follow_order(siblings, &theirs_siblings);
+                }
+            }
+        }
+        None => remove_tree(ours, id),
+    }
+}
+
+/// Remove an entity and its descendants
+fn remove_tree<T>(entities: &mut IndexedEntities<T>, id: &str) {
+    remove_from_siblings(entities, id);
+    let mut pending = vec![id.to_string()];
+    while let Some(id) = pending.pop() {
+        entities.entities.remove(&id);
+        if let Some(children) = entities.child_ids.remove(&id) {
+            pending.extend(children);
+        }
+    }
+}
+
+fn remove_from_siblings<T>(entities: &mut IndexedEntities<T>, id: &str) {
+    entities.top_level_ids.retain(|i| i != id);
+    for siblings in entities.child_ids.values_mut() {
+        siblings.retain(|i| i != id);
+    }
+}
+
+/// Insert an entity ID under the same parent (or section) and after the same preceding
+/// sibling as in theirs, when those exist in ours
+fn place<T>(
+    ours: &mut IndexedEntities<T>,
+    theirs: &IndexedEntities<T>,
+    id: String,
+    parent_id: Option<String>,
+    previous_id: Option<String>,
+) {
+    let siblings = match parent_id {
+        Some(parent_id)
+            if ours.entities.contains_key(&parent_id)
+                || !theirs.entities.contains_key(&parent_id) =>
+        {
+            ours.child_ids.entry(parent_id).or_default()
+        }
+        _ => &mut ours.top_level_ids,
+    };
+    let index = match previous_id {
+        Some(previous_id) => siblings
+            .iter()
+            .position(|s| *s == previous_id)
+            .map_or(siblings.len(), |i| i + 1),
+        None => 0,
+    };
+    siblings.insert(index, id);
+}
+
+/// Return the child IDs of a parent (or section), or the top level IDs if no parent
+fn get_siblings<'a, T>(
+    entities: &'a IndexedEntities<T>,
+    parent_id: &Option<String>,
+) -> Option<&'a Vec<String>> {
+    match parent_id {
+        Some(parent_id) => entities.child_ids.get(parent_id),
+        None => Some(&entities.top_level_ids),
+    }
+}
+
+fn get_siblings_mut<'a, T>(
+    entities: &'a mut IndexedEntities<T>,
+    parent_id: &Option<String>,
+) -> Option<&'a mut Vec<String>> {
+    match parent_id {
+        Some(parent_id) => entities.child_ids.get_mut(parent_id),
+        None => Some(&mut entities.top_level_ids),
+    }
+}
+
+/// Reorder siblings that are also in the specified order to follow it, leaving other
+/// siblings where they are
+fn follow_order(siblings: &mut [String], order: &[String]) {
+    let positions = siblings
+        .iter()
+        .enumerate()
+        .filter(|(_, id)| order.contains(id))
+        .map(|(position, _)| position)
+        .collect::<Vec<usize>>();
+    let ordered = order
+        .iter()
+        .filter(|id| siblings.contains(id))
+        .cloned()
+        .collect::<Vec<String>>();
+    for (position, id) in positions.into_iter().zip(ordered) {
+        siblings[position] = id;
+    }
+}
+
+fn get_parent<'a>(
+    locations: &'a [(String, Option<String>, Option<String>)],
+    id: &str,
+) -> Option<&'a Option<String>> {
+    locations
+        .iter()
+        .find(|(i, _, _)| i == id)
+        .map(|(_, parent_id, _)| parent_id)
+}
+
+fn get_name(value: &Option<Value>) -> Option<String> {
+    value
+        .as_ref()
+        .and_then(|v| v.get("name"))
+        .and_then(|n| n.as_str())
+        .map(|n| n.to_string())
+}
+
+/// Return each entity ID with its parent (or section) and preceding sibling, ordered so
+/// that parents come before their children
+fn walk<T>(entities: &IndexedEntities<T>) -> Vec<(String, Option<String>, Option<String>)> {
+    let mut results = Vec::with_capacity(entities.entities.len());
+    let mut queue = VecDeque::<(Option<&String>, &Vec<String>)>::new();
+    queue.push_back((None, &entities.top_level_ids));
+
+    // Child lists keyed by something other than an entity (i.e. parameter sections) are roots
+    let mut sections = entities
+        .child_ids
+        .iter()
+        .filter(|(key, _)| !entities.entities.contains_key(*key))
+        .collect::<Vec<(&String, &Vec<String>)>>();
+    sections.sort_by(|a, b| a.0.cmp(b.0));
+    for (section, ids) in sections {
+        queue.push_back((Some(section), ids));
+    }
+
+    while let Some((parent_id, ids)) = queue.pop_front() {
+        let mut previous_id: Option<&String> = None;
+        for id in ids {
+            results.push((id.clone(), parent_id.cloned(), previous_id.cloned()));
+            if let Some(children) = entities.child_ids.get(id) {
+                queue.push_back((Some(id), children));
+            }
+            previous_id = Some(id);
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use apicize_lib::{
+        editing::indexed_entities::IndexedEntityPosition, Request, RequestEntry, RequestGroup,
+        Workspace,
+    };
+
+    use super::{merge_workspaces, resolve_conflict, MergeResolution};
+    use crate::workspaces::EntityType;
+
+    fn request(id: &str) -> RequestEntry {
+        let mut request = Request::default();
+        request.id = id.to_string();
+        request.name = id.to_string();
+        RequestEntry::Request(request)
+    }
+
+    fn group(id: &str) -> RequestEntry {
+        let mut group = RequestGroup::default();
+        group.id = id.to_string();
+        group.name = id.to_string();
+        RequestEntry::Group(group)
+    }
+
+    /// Workspace with group g1 (containing r1 and r2) followed by request r3
+    fn sample() -> Workspace {
+        let mut workspace = Workspace::new().unwrap();
+        let requests = &mut workspace.requests;
+        requests.add_entity(group("g1"), None, None).unwrap();
+        requests
+            .add_entity(
+                request("r1"),
+                Some("g1"),
+                Some(IndexedEntityPosition::Under),
+            )
+            .unwrap();
+        requests
+            .add_entity(
+                request("r2"),
+                Some("r1"),
+                Some(IndexedEntityPosition::After),
+            )
+            .unwrap();
+        requests
+            .add_entity(
+                request("r3"),
+                Some("g1"),
+                Some(IndexedEntityPosition::After),
+            )
+            .unwrap();
+        workspace
+    }
+
+    fn rename(workspace: &mut Workspace, id: &str, name: &str) {
+        match workspace.requests.entities.get_mut(id).unwrap() {
+            RequestEntry::Request(request) => request.name = name.to_string(),
+            RequestEntry::Group(group) => group.name = name.to_string(),
+        }
+    }
+
+    fn name_of(workspace: &Workspace, id: &str) -> String {
+        workspace.requests.entities[id].get_name().to_string()
+    }
+
+    fn delete(workspace: &mut Workspace, id: &str) {
+        let requests = &mut workspace.requests;
+        requests.entities.remove(id);
+        requests.child_ids.remove(id);
+        requests.top_level_ids.retain(|i| i != id);
+        for siblings in requests.child_ids.values_mut() {
+            siblings.retain(|i| i != id);
+        }
+    }
+
+    fn children(workspace: &Workspace, id: &str) -> Vec<String> {
+        workspace.requests.child_ids[id].clone()
+    }
+
+    #[test]
+    fn applies_one_sided_edits() {
+        let base = sample();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        rename(&mut theirs, "r1", "Edited on disk");
+        rename(&mut ours, "r2", "Edited in workspace");
+
+        let result = merge_workspaces(&mut ours, Some(&base), &theirs).unwrap();
+        assert_eq!(result.applied, 1);
+        assert!(result.conflicts.is_empty());
+        assert_eq!(name_of(&ours, "r1"), "Edited on disk");
+        assert_eq!(name_of(&ours, "r2"), "Edited in workspace");
+    }
+
+    #[test]
+    fn reports_conflicting_edits() {
+        let base = sample();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        rename(&mut theirs, "r1", "Edited on disk");
+        rename(&mut ours, "r1", "Edited in workspace");
+
+        let result = merge_workspaces(&mut ours, Some(&base), &theirs).unwrap();
+        assert_eq!(result.applied, 0);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].id, "r1");
+        assert_eq!(name_of(&ours, "r1"), "Edited in workspace");
+
+        resolve_conflict(
+            &mut ours,
+            &theirs,
+            &EntityType::Request,
+            "r1",
+            MergeResolution::Theirs,
+        )
+        .unwrap();
+        assert_eq!(name_of(&ours, "r1"), "Edited on disk");
+    }
+
+    #[test]
+    fn reports_delete_on_disk_of_edited_entity() {
+        let base = sample();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        delete(&mut theirs, "r3");
+        rename(&mut ours, "r3", "Edited in workspace");
+
+        let result = merge_workspaces(&mut ours, Some(&base), &theirs).unwrap();
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].id, "r3");
+        assert!(result.conflicts[0].theirs.is_none());
+        assert!(ours.requests.top_level_ids.contains(&"r3".to_string()));
+    }
+
+    #[test]
+    fn applies_move_on_disk() {
+        let base = sample();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        theirs.requests.top_level_ids.retain(|id| id != "r3");
+        theirs
+            .requests
+            .child_ids
+            .get_mut("g1")
+            .unwrap()
+            .push("r3".to_string());
+
+        let result = merge_workspaces(&mut ours, Some(&base), &theirs).unwrap();
+        assert_eq!(result.applied, 1);
+        assert!(result.conflicts.is_empty());
+        assert!(!ours.requests.top_level_ids.contains(&"r3".to_string()));
+        assert_eq!(children(&ours, "g1"), vec!["r1", "r2", "r3"]);
+    }
+
+    #[test]
+    fn applies_reorder_on_disk() {
+        let base = sample();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        theirs
+            .requests
+            .child_ids
+            .insert("g1".to_string(), vec!["r2".to_string(), "r1".to_string()]);
+        ours.requests
+            .add_entity(
+                request("r4"),
+                Some("r2"),
+                Some(IndexedEntityPosition::After),
+            )
+            .unwrap();
+
+        let result = merge_workspaces(&mut ours, Some(&base), &theirs).unwrap();
+        assert_eq!(result.applied, 1);
+        assert!(result.conflicts.is_empty());
+        assert_eq!(children(&ours, "g1"), vec!["r2", "r1", "r4"]);
+    }
+
+    #[test]
+    fn keeps_deleted_group_with_kept_children() {
+        let base = sample();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        delete(&mut theirs, "r1");
+        delete(&mut theirs, "r2");
+        delete(&mut theirs, "g1");
+        rename(&mut ours, "r1", "Edited in workspace");
+
+        let result = merge_workspaces(&mut ours, Some(&base), &theirs).unwrap();
+        assert_eq!(result.applied, 1);
+        let mut conflict_ids = result
+            .conflicts
+            .iter()
+            .map(|c| c.id.as_str())
+            .collect::<Vec<&str>>();
+        conflict_ids.sort();
+        assert_eq!(conflict_ids, vec!["g1", "r1"]);
+        assert!(ours.requests.top_level_ids.contains(&"g1".to_string()));
+        assert_eq!(children(&ours, "g1"), vec!["r1"]);
+        assert!(!ours.requests.entities.contains_key("r2"));
+    }
+}
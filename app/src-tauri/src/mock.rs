@@ -0,0 +1,389 @@
+//! Mock server submodule
+//!
+//! This submodule serves canned responses for workbook requests from a local HTTP listener,
+//! so front-end work can proceed against endpoints that do not exist yet
+
+use std::{collections::BTreeMap, fs, thread::JoinHandle, time::Duration};
+
+use actix_web::{
+    http::StatusCode,
+    rt::time::sleep,
+    web::{self, Data},
+    App, HttpRequest, HttpResponse, HttpResponseBuilder, HttpServer,
+};
+use apicize_lib::{IndexedEntities, NameValuePair, RequestEntry};
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{error::ApicizeAppError, pkce::StopHandle, workspaces::WorkspaceInfo, WorkspacesState};
+
+/// Top level workbook property mock responses are stored in
+pub const MOCKS_PROPERTY: &str = "mocks";
+
+/// Canned response for a request
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MockResponse {
+    /// If true, replay the last recorded response for the request instead of the values below
+    #[serde(default)]
+    pub replay_last_result: bool,
+    /// HTTP status code
+    #[serde(default = "default_status")]
+    pub status: u16,
+    /// HTTP headers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Vec<NameValuePair>>,
+    /// Response body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Delay, in milliseconds, before responding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u64>,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+/// Running mock server information
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MockServerStatus {
+    pub workspace_id: String,
+    pub port: u16,
+    pub url: String,
+}
+
+/// Response to send, resolved from a mock definition
+struct MockReply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    delay: Option<u64>,
+}
+
+/// Load mock responses stored in a workbook file, keyed by request ID
+pub fn load_mocks(file_name: &str) -> FxHashMap<String, MockResponse> {
+    fs::read_to_string(file_name)
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .and_then(|mut workbook| workbook.get_mut(MOCKS_PROPERTY).map(|m| m.take()))
+        .and_then(|mocks| serde_json::from_value(mocks).ok())
+        .unwrap_or_default()
+}
+
+/// Return the mock responses to save with a workbook, sorted by request ID so saves are
+/// stable; mocks of requests no longer in the workbook are dropped
+pub fn get_saved_mocks<'a>(
+    mocks: &'a FxHashMap<String, MockResponse>,
+    requests: &IndexedEntities<RequestEntry>,
+) -> BTreeMap<&'a String, &'a MockResponse> {
+    mocks
+        .iter()
+        .filter(|(request_id, _)| {
+            matches!(
+                requests.entities.get(*request_id),
+                Some(RequestEntry::Request(_))
+            )
+        })
+        .collect()
+}
+
+pub struct MockServer {
+    tauri: AppHandle,
+    status: Option<MockServerStatus>,
+    stop: Option<web::Data<StopHandle>>,
+    server: Option<JoinHandle<Result<(), std::io::Error>>>,
+}
+
+#[derive(Clone)]
+struct MockServerData {
+    tauri: AppHandle,
+    workspace_id: String,
+}
+
+impl MockServer {
+    pub fn new(app_handle: AppHandle) -> Self {
+        MockServer {
+            tauri: app_handle,
+            status: None,
+            stop: None,
+            server: None,
+        }
+    }
+
+    pub fn get_status(&self) -> Option<MockServerStatus> {
+        self.status.clone()
+    }
+
+    /// Start serving mocks for the specified workspace, replacing any running server
+    pub fn start(
+        &mut self,
+        workspace_id: &str,
+        port: u16,
+    ) -> Result<MockServerStatus, ApicizeAppError> {
+        if port == 0 {
+            return Err(ApicizeAppError::InvalidOperation(
+                "a mock server port is required".to_string(),
+            ));
+        }
+
+        self.stop();
+
+        let data = MockServerData {
+            tauri: self.tauri.clone(),
+            workspace_id: workspace_id.to_string(),
+        };
+        let stop_handle = web::Data::new(StopHandle::default());
+        let cloned_stop_handle = stop_handle.clone();
+
+        self.server = Some(std::thread::spawn(move || {
+            init_mock_server(data, port, cloned_stop_handle)
+        }));
+        self.stop = Some(stop_handle);
+
+        let status = MockServerStatus {
+            workspace_id: workspace_id.to_string(),
+            port,
+            url: format!("http://127.0.0.1:{port}"),
+        };
+        self.status = Some(status.clone());
+        Ok(status)
+    }
+
+    /// Stop the mock server, if running
+    pub fn stop(&mut self) {
+        self.status = None;
+
+        if let Some(h) = self.stop.take() {
+            h.stop(false);
+        }
+
+        if let Some(s) = self.server.take() {
+            if let Err(err) = s.join() {
+                self.tauri
+                    .emit("mock-server-error", format!("{err:?}"))
+                    .unwrap();
+            }
+        }
+    }
+
+    /// Stop the mock server if it is serving the specified workspace
+    pub fn stop_for_workspace(&mut self, workspace_id: &str) {
+        if self
+            .status
+            .as_ref()
+            .is_some_and(|s| s.workspace_id == workspace_id)
+        {
+            self.stop();
+        }
+    }
+}
+
+#[actix_web::main]
+async fn init_mock_server(
+    data: MockServerData,
+    port: u16,
+    stop_handle: Data<StopHandle>,
+) -> Result<(), std::io::Error> {
+    let tauri = data.tauri.clone();
+    let app_data = web::Data::new(data);
+
+    let http_server = HttpServer::new(move || {
+        App::new()
+            .app_data(app_data.clone())
+            .default_service(web::to(serve_mock))
+    })
+    .bind(("127.0.0.1", port));
+
+    match http_server {
+        Ok(server) => {
+            println!("*** Started mock server at 127.0.0.1:{port} ***");
+            let running_server = server.run();
+            stop_handle.register(running_server.handle());
+            running_server.await
+        }
+        Err(err) => {
+            tauri
+                .emit(
+                    "mock-server-error",
+                    format!("Unable to start mock server at http://127.0.0.1:{port}, {err}"),
+                )
+                .unwrap();
+            eprintln!("Unable to start mock server at http://127.0.0.1:{port}, {err}");
+            Err(err)
+        }
+    }
+}
+
+/// Respond with the mock for the request matching the incoming method and path; mocks
+/// are resolved from the workspace on every call so edits take effect immediately
+async fn serve_mock(request: HttpRequest, data: Data<MockServerData>) -> HttpResponse {
+    let reply = {
+        let state = data.tauri.state::<WorkspacesState>();
+        let workspaces = state.workspaces.read().await;
+        match workspaces.get_workspace_info(&data.workspace_id) {
+            Ok(info) => find_reply(info, request.method().as_str(), request.path()),
+            Err(err) => {
+                return HttpResponse::ServiceUnavailable().body(err.to_string());
+            }
+        }
+    };
+
+    let Some(reply) = reply else {
+        return HttpResponse::NotFound().body(format!(
+            "No mock defined for {} {}",
+            request.method(),
+            request.path()
+        ));
+    };
+
+    if let Some(delay) = reply.delay.filter(|d| *d > 0) {
+        sleep(Duration::from_millis(delay)).await;
+    }
+
+    let mut response = HttpResponseBuilder::new(
+        StatusCode::from_u16(reply.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+    );
+    for header in reply.headers {
+        response.append_header(header);
+    }
+    response.body(reply.body)
+}
+
+/// Find the mock matching the method and path, preferring literal path segments
+/// over variable ones
+fn find_reply(info: &WorkspaceInfo, method: &str, path: &str) -> Option<MockReply> {
+    let path_segments = split_path(path);
+    info.mocks
+        .iter()
+        .filter_map(|(request_id, mock)| {
+            let Some(RequestEntry::Request(request)) =
+                info.workspace.requests.entities.get(request_id)
+            else {
+                return None;
+            };
+            let request_method = serde_json::to_value(&request.method)
+                .ok()
+                .and_then(|m| m.as_str().map(|m| m.to_string()))
+                .unwrap_or_else(|| "GET".to_string());
+            if !request_method.eq_ignore_ascii_case(method) {
+                return None;
+            }
+            match_path(&request.url, &path_segments).map(|wildcards| (wildcards, request_id, mock))
+        })
+        .min_by_key(|(wildcards, request_id, _)| (*wildcards, *request_id))
+        .map(|(_, request_id, mock)| {
+            if mock.replay_last_result {
+                if let Some(reply) = replay_last_result(info, request_id, mock.delay) {
+                    return reply;
+                }
+            }
+            MockReply {
+                status: mock.status,
+                headers: mock
+                    .headers
+                    .iter()
+                    .flatten()
+                    .filter(|h| h.disabled != Some(true))
+                    .map(|h| (h.name.clone(), h.value.clone()))
+                    .collect(),
+                body: mock.body.clone().unwrap_or_default().into_bytes(),
+                delay: mock.delay,
+            }
+        })
+}
+
+/// Return the number of variable segments if the request URL's path matches, or None
+fn match_path(url: &str, path_segments: &[&str]) -> Option<usize> {
+    // Skip scheme and host, which may be a substitution variable
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = without_scheme
+        .find('/')
+        .map_or("", |i| &without_scheme[i..]);
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+
+    let url_segments = split_path(path);
+    if url_segments.len() != path_segments.len() {
+        return None;
+    }
+
+    let mut wildcards = 0;
+    for (url_segment, path_segment) in url_segments.iter().zip(path_segments) {
+        if url_segment.contains("{{") {
+            wildcards += 1;
+        } else if url_segment != path_segment {
+            return None;
+        }
+    }
+    Some(wildcards)
+}
+
+fn split_path(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+/// Build a reply from the last recorded response of a request
+fn replay_last_result(
+    info: &WorkspaceInfo,
+    request_id: &str,
+    delay: Option<u64>,
+) -> Option<MockReply> {
+    let detail = info
+        .result_details
+        .get(request_id)?
+        .iter()
+        .rev()
+        .filter_map(|d| serde_json::to_value(d).ok())
+        .find(|d| d.get("entityType").and_then(|t| t.as_str()) == Some("request"))?;
+    let response = detail.pointer("/testContext/response")?;
+
+    let headers = response
+        .get("headers")
+        .and_then(|h| h.as_object())
+        .map(|h| {
+            h.iter()
+                // Body is re-sent uncompressed and may have a different length
+                .filter(|(name, _)| {
+                    !name.eq_ignore_ascii_case("content-length")
+                        && !name.eq_ignore_ascii_case("content-encoding")
+                        && !name.eq_ignore_ascii_case("transfer-encoding")
+                })
+                .map(|(name, value)| {
+                    (
+                        name.clone(),
+                        value
+                            .as_str()
+                            .map(|v| v.to_string())
+                            .unwrap_or_else(|| value.to_string()),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let body = match response.get("body") {
+        Some(body) => match (body.get("text"), body.get("data")) {
+            (Some(Value::String(text)), _) => text.clone().into_bytes(),
+            (_, Some(Value::Array(bytes))) => bytes
+                .iter()
+                .filter_map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                .collect(),
+            _ => vec![],
+        },
+        None => vec![],
+    };
+
+    Some(MockReply {
+        status: response
+            .get("status")
+            .and_then(|s| s.as_u64())
+            .and_then(|s| u16::try_from(s).ok())
+            .unwrap_or(200),
+        headers,
+        body,
+        delay,
+    })
+}
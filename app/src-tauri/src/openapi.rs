@@ -0,0 +1,486 @@
+//! OpenAPI import submodule
+//!
+//! This submodule converts OpenAPI 3.x documents into workspaces, with a group
+//! for each tag and a request for each operation
+
+use apicize_lib::{
+    editing::indexed_entities::IndexedEntityPosition, Request, RequestBody, RequestEntry,
+    RequestGroup, RequestMethod, Workspace,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    error::ApicizeAppError,
+    import::{self, ImportResult},
+};
+
+/// Operations (in the order they are added to groups)
+const METHODS: [&str; 7] = ["get", "post", "put", "patch", "delete", "head", "options"];
+
+/// Maximum depth when following references or generating example data from schemas
+const MAX_DEPTH: usize = 10;
+
+/// Convert an OpenAPI 3.x document into a workspace
+pub fn import(document: &Value) -> Result<ImportResult, ApicizeAppError> {
+    let version = document
+        .get("openapi")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    if !version.starts_with("3.") {
+        return Err(ApicizeAppError::ImportError(format!(
+            "OpenAPI version \"{version}\" is not supported, only 3.x documents can be imported"
+        )));
+    }
+
+    let mut importer = OpenApiImporter {
+        document,
+        warnings: vec![],
+    };
+    let workspace = importer.build_workspace()?;
+    Ok(ImportResult {
+        workspace,
+        warnings: importer.warnings,
+    })
+}
+
+struct OpenApiImporter<'a> {
+    document: &'a Value,
+    warnings: Vec<String>,
+}
+
+impl<'a> OpenApiImporter<'a> {
+    fn build_workspace(&mut self) -> Result<Workspace, ApicizeAppError> {
+        let document = self.document;
+        let base_url = self.get_base_url();
+
+        // Requests grouped by their first tag, in order of appearance
+        let mut tagged: Vec<(String, Vec<Request>)> = vec![];
+        let mut untagged: Vec<Request> = vec![];
+
+        if let Some(paths) = document.get("paths").and_then(|p| p.as_object()) {
+            for (path, path_item) in paths {
+                let path_item = self.resolve(path_item);
+                if path_item.get("trace").is_some() {
+                    self.warnings
+                        .push(format!("TRACE operation on {path} is not supported"));
+                }
+
+                for method in METHODS {
+                    let Some(operation) = path_item.get(method) else {
+                        continue;
+                    };
+                    let request = self.build_request(&base_url, path, method, path_item, operation);
+                    match operation
+                        .get("tags")
+                        .and_then(|t| t.as_array())
+                        .and_then(|t| t.first())
+                        .and_then(|t| t.as_str())
+                    {
+                        Some(tag) => match tagged.iter_mut().find(|(name, _)| name == tag) {
+                            Some((_, requests)) => requests.push(request),
+                            None => tagged.push((tag.to_string(), vec![request])),
+                        },
+                        None => untagged.push(request),
+                    }
+                }
+            }
+        }
+
+        // Order groups per the document's tag list, followed by any undeclared tags
+        let declared_tags = document
+            .get("tags")
+            .and_then(|t| t.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.get("name").and_then(|n| n.as_str()))
+                    .collect::<Vec<&str>>()
+            })
+            .unwrap_or_default();
+        tagged.sort_by_key(|(name, _)| {
+            declared_tags
+                .iter()
+                .position(|t| t == name)
+                .unwrap_or(usize::MAX)
+        });
+
+        if self
+            .document
+            .pointer("/components/securitySchemes")
+            .and_then(|s| s.as_object())
+            .is_some_and(|s| !s.is_empty())
+        {
+            self.warnings.push(
+                "Security schemes are not imported, configure authorizations for imported requests"
+                    .to_string(),
+            );
+        }
+
+        let mut workspace = Workspace::new()?;
+        for (tag, requests) in tagged {
+            let mut group = RequestGroup::default();
+            group.name = tag;
+            let group_id = group.id.clone();
+            workspace
+                .requests
+                .add_entity(RequestEntry::Group(group), None, None)?;
+            for request in requests {
+                workspace.requests.add_entity(
+                    RequestEntry::Request(request),
+                    Some(&group_id),
+                    Some(IndexedEntityPosition::Under),
+                )?;
+            }
+        }
+        for request in untagged {
+            workspace
+                .requests
+                .add_entity(RequestEntry::Request(request), None, None)?;
+        }
+
+        Ok(workspace)
+    }
+
+    /// Follow local references (i.e. "#/components/schemas/Pet")
+    fn resolve(&self, value: &'a Value) -> &'a Value {
+        let mut current = value;
+        for _ in 0..MAX_DEPTH {
+            match current
+                .get("$ref")
+                .and_then(|r| r.as_str())
+                .and_then(|r| r.strip_prefix('#'))
+                .and_then(|pointer| self.document.pointer(pointer))
+            {
+                Some(target) => current = target,
+                None => break,
+            }
+        }
+        current
+    }
+
+    /// Return the first server URL, with server variables replaced by their defaults
+    fn get_base_url(&self) -> String {
+        let Some(server) = self
+            .document
+            .get("servers")
+            .and_then(|s| s.as_array())
+            .and_then(|s| s.first())
+        else {
+            return String::default();
+        };
+
+        let mut url = server
+            .get("url")
+            .and_then(|u| u.as_str())
+            .unwrap_or_default()
+            .to_string();
+        if let Some(variables) = server.get("variables").and_then(|v| v.as_object()) {
+            for (name, variable) in variables {
+                if let Some(default) = variable.get("default").and_then(|d| d.as_str()) {
+                    url = url.replace(&format!("{{{name}}}"), default);
+                }
+            }
+        }
+        url.trim_end_matches('/').to_string()
+    }
+
+    fn build_request(
+        &mut self,
+        base_url: &str,
+        path: &str,
+        method: &str,
+        path_item: &'a Value,
+        operation: &'a Value,
+    ) -> Request {
+        let operation_id = operation.get("operationId").and_then(|o| o.as_str());
+        let description = format!("{} {}", method.to_uppercase(), path);
+
+        let mut request = Request::default();
+        request.name = operation
+            .get("summary")
+            .and_then(|s| s.as_str())
+            .filter(|s| !s.is_empty())
+            .or(operation_id)
+            .unwrap_or(&description)
+            .to_string();
+        request.key = operation_id.map(|o| o.to_string());
+        // Path parameters use Apicize variable substitution
+        request.url = format!("{}{}", base_url, path.replace('{', "{{").replace('}', "}}"));
+        request.method = serde_json::from_value::<RequestMethod>(json!(method.to_uppercase())).ok();
+
+        let mut headers: Vec<Value> = vec![];
+        let mut query_string_params: Vec<Value> = vec![];
+
+        for parameter in self.get_parameters(path_item, operation) {
+            let name = parameter
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or_default();
+            let required = parameter
+                .get("required")
+                .and_then(|r| r.as_bool())
+                .unwrap_or(false);
+            let mut pair = json!({
+                "name": name,
+                "value": self.get_parameter_value(parameter),
+            });
+            if !required {
+                pair["disabled"] = json!(true);
+            }
+
+            match parameter.get("in").and_then(|i| i.as_str()) {
+                Some("query") => query_string_params.push(pair),
+                Some("header") => headers.push(pair),
+                Some("path") => {}
+                Some(location) => self.warnings.push(format!(
+                    "{location} parameter \"{name}\" on {description} was not imported"
+                )),
+                None => {}
+            }
+        }
+
+        if let Some(request_body) = operation.get("requestBody") {
+            if let Some((content_type, body)) = self.build_body(request_body, &description) {
+                if !headers.iter().any(|h| {
+                    h.get("name")
+                        .and_then(|n| n.as_str())
+                        .is_some_and(|n| n.eq_ignore_ascii_case("content-type"))
+                }) {
+                    headers.push(json!({ "name": "Content-Type", "value": content_type }));
+                }
+                match serde_json::from_value::<RequestBody>(body) {
+                    Ok(body) => request.body = Some(body),
+                    Err(err) => self
+                        .warnings
+                        .push(format!("Unable to import body for {description}: {err}")),
+                }
+            }
+        }
+
+        request.headers = import::to_name_value_pairs(headers);
+        request.query_string_params = import::to_name_value_pairs(query_string_params);
+        request.test = Some(Self::build_status_test(operation));
+        request
+    }
+
+    /// Return path-level parameters, overridden by operation-level parameters of the same name and location
+    fn get_parameters(&self, path_item: &'a Value, operation: &'a Value) -> Vec<&'a Value> {
+        let mut parameters: Vec<&'a Value> = vec![];
+        for source in [path_item, operation] {
+            let Some(list) = source.get("parameters").and_then(|p| p.as_array()) else {
+                continue;
+            };
+            for parameter in list {
+                let parameter = self.resolve(parameter);
+                let key = (parameter.get("name"), parameter.get("in"));
+                parameters.retain(|p| (p.get("name"), p.get("in")) != key);
+                parameters.push(parameter);
+            }
+        }
+        parameters
+    }
+
+    fn get_parameter_value(&self, parameter: &'a Value) -> String {
+        let example = parameter
+            .get("example")
+            .or_else(|| {
+                parameter
+                    .get("examples")
+                    .and_then(|e| e.as_object())
+                    .and_then(|e| e.values().next())
+                    .and_then(|e| self.resolve(e).get("value"))
+            })
+            .cloned()
+            .or_else(|| {
+                parameter
+                    .get("schema")
+                    .map(|schema| self.sample_from_schema(schema, 0))
+            });
+
+        match example {
+            Some(Value::String(s)) => s,
+            Some(Value::Null) | None => String::default(),
+            Some(other) => other.to_string(),
+        }
+    }
+
+    /// Build body from the preferred media type, returning its content type and body
+    fn build_body(
+        &mut self,
+        request_body: &'a Value,
+        description: &str,
+    ) -> Option<(String, Value)> {
+        let content = self.resolve(request_body).get("content")?.as_object()?;
+
+        let (content_type, media) = content
+            .iter()
+            .find(|(t, _)| t.contains("json"))
+            .or_else(|| {
+                content
+                    .iter()
+                    .find(|(t, _)| *t == "application/x-www-form-urlencoded")
+            })
+            .or_else(|| content.iter().find(|(t, _)| t.contains("xml")))
+            .or_else(|| content.iter().find(|(t, _)| t.starts_with("text/")))
+            .or_else(|| {
+                self.warnings.push(format!(
+                    "Body media type(s) for {} are not supported: {}",
+                    description,
+                    content.keys().cloned().collect::<Vec<String>>().join(", ")
+                ));
+                None
+            })?;
+
+        let example = media
+            .get("example")
+            .cloned()
+            .or_else(|| {
+                media
+                    .get("examples")
+                    .and_then(|e| e.as_object())
+                    .and_then(|e| e.values().next())
+                    .and_then(|e| self.resolve(e).get("value"))
+                    .cloned()
+            })
+            .or_else(|| {
+                media
+                    .get("schema")
+                    .map(|schema| self.sample_from_schema(schema, 0))
+            })
+            .unwrap_or(Value::Null);
+
+        let body = if content_type.contains("json") {
+            json!({ "type": "JSON", "data": example })
+        } else if content_type == "application/x-www-form-urlencoded" {
+            let data = match &example {
+                Value::Object(fields) => fields
+                    .iter()
+                    .map(|(name, value)| {
+                        json!({
+                            "name": name,
+                            "value": match value {
+                                Value::String(s) => s.clone(),
+                                other => other.to_string(),
+                            }
+                        })
+                    })
+                    .collect::<Vec<Value>>(),
+                _ => vec![],
+            };
+            json!({ "type": "Form", "data": data })
+        } else {
+            let data = match example {
+                Value::String(s) => s,
+                Value::Null => String::default(),
+                _ => {
+                    self.warnings.push(format!(
+                        "Example body for {description} could not be converted to {content_type}"
+                    ));
+                    String::default()
+                }
+            };
+            let body_type = if content_type.contains("xml") {
+                "XML"
+            } else {
+                "Text"
+            };
+            json!({ "type": body_type, "data": data })
+        };
+
+        Some((content_type.clone(), body))
+    }
+
+    /// Generate example data from a schema
+    fn sample_from_schema(&self, schema: &'a Value, depth: usize) -> Value {
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+
+        let schema = self.resolve(schema);
+        for key in ["example", "default"] {
+            if let Some(value) = schema.get(key) {
+                return value.clone();
+            }
+        }
+        if let Some(value) = schema
+            .get("enum")
+            .and_then(|e| e.as_array())
+            .and_then(|e| e.first())
+        {
+            return value.clone();
+        }
+
+        if let Some(all_of) = schema.get("allOf").and_then(|a| a.as_array()) {
+            let mut merged = Map::new();
+            for part in all_of {
+                if let Value::Object(fields) = self.sample_from_schema(part, depth + 1) {
+                    merged.extend(fields);
+                }
+            }
+            return Value::Object(merged);
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(first) = schema
+                .get(key)
+                .and_then(|o| o.as_array())
+                .and_then(|o| o.first())
+            {
+                return self.sample_from_schema(first, depth + 1);
+            }
+        }
+
+        // OpenAPI 3.1 allows a list of types (i.e. ["string", "null"])
+        let schema_type = match schema.get("type") {
+            Some(Value::String(t)) => Some(t.as_str()),
+            Some(Value::Array(types)) => types
+                .iter()
+                .filter_map(|t| t.as_str())
+                .find(|t| *t != "null"),
+            _ => None,
+        };
+
+        match schema_type {
+            Some("object") | None if schema.get("properties").is_some() => {
+                let mut fields = Map::new();
+                if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+                    for (name, property) in properties {
+                        fields.insert(name.clone(), self.sample_from_schema(property, depth + 1));
+                    }
+                }
+                Value::Object(fields)
+            }
+            Some("object") => json!({}),
+            Some("array") => match schema.get("items") {
+                Some(items) => json!([self.sample_from_schema(items, depth + 1)]),
+                None => json!([]),
+            },
+            Some("string") => match schema.get("format").and_then(|f| f.as_str()) {
+                Some("date-time") => json!("2000-01-01T00:00:00Z"),
+                Some("date") => json!("2000-01-01"),
+                Some("uuid") => json!("00000000-0000-0000-0000-000000000000"),
+                Some("email") => json!("user@example.com"),
+                Some("uri") | Some("url") => json!("https://example.com"),
+                _ => json!("string"),
+            },
+            Some("integer") | Some("number") => json!(0),
+            Some("boolean") => json!(false),
+            _ => Value::Null,
+        }
+    }
+
+    /// Build a test for the first documented success status (or 200)
+    fn build_status_test(operation: &Value) -> String {
+        let status = operation
+            .get("responses")
+            .and_then(|r| r.as_object())
+            .and_then(|responses| {
+                responses
+                    .keys()
+                    .filter(|code| code.starts_with('2'))
+                    .filter_map(|code| code.parse::<u16>().ok())
+                    .min()
+            })
+            .unwrap_or(200);
+
+        import::status_test(status)
+    }
+}
@@ -237,7 +237,7 @@ async fn process_pkce_response(
 }
 
 #[derive(Default)]
-struct StopHandle {
+pub(crate) struct StopHandle {
     inner: parking_lot::Mutex<Option<ServerHandle>>,
 }
 
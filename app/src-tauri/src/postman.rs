@@ -0,0 +1,676 @@
+//! Postman import submodule
+//!
+//! This submodule converts Postman v2.1 collections into workspaces, with a group
+//! for each folder and a request for each item.  Postman environment exports are
+//! converted into a workspace with a single scenario
+
+use apicize_lib::{
+    editing::indexed_entities::IndexedEntityPosition, indexed_entities::NO_SELECTION_ID,
+    Authorization, Request, RequestBody, RequestEntry, RequestGroup, RequestMethod, Scenario,
+    Selection, Workspace,
+};
+use regex::Regex;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    error::ApicizeAppError,
+    import::{self, ImportResult},
+};
+
+/// Returns true if the document is a Postman collection or environment export
+pub fn is_postman_document(document: &Value) -> bool {
+    document
+        .pointer("/info/schema")
+        .and_then(|s| s.as_str())
+        .is_some_and(|s| s.contains("schema.getpostman.com"))
+        || is_environment(document)
+}
+
+fn is_environment(document: &Value) -> bool {
+    document
+        .get("_postman_variable_scope")
+        .and_then(|s| s.as_str())
+        == Some("environment")
+}
+
+/// Convert a Postman collection or environment into a workspace
+pub fn import(document: &Value) -> Result<ImportResult, ApicizeAppError> {
+    let mut importer = PostmanImporter::new();
+    let workspace = if is_environment(document) {
+        importer.build_environment_workspace(document)?
+    } else {
+        let schema = document
+            .pointer("/info/schema")
+            .and_then(|s| s.as_str())
+            .unwrap_or_default();
+        if !schema.contains("/v2.1") {
+            return Err(ApicizeAppError::ImportError(format!(
+                "Postman schema \"{schema}\" is not supported, only v2.1 collections can be imported"
+            )));
+        }
+        importer.build_workspace(document)?
+    };
+
+    Ok(ImportResult {
+        workspace,
+        warnings: importer.warnings,
+    })
+}
+
+struct PostmanImporter {
+    warnings: Vec<String>,
+    /// Authorizations to add, along with the Postman auth block they were converted from
+    authorizations: Vec<(Value, Selection, Authorization)>,
+    regex_path_variable: Regex,
+    regex_status: Regex,
+    regex_variable_get: Regex,
+    regex_variable_set: Regex,
+}
+
+impl PostmanImporter {
+    fn new() -> Self {
+        PostmanImporter {
+            warnings: vec![],
+            authorizations: vec![],
+            regex_path_variable: Regex::new(r#"/:([A-Za-z_][\w-]*)"#).unwrap(),
+            regex_status: Regex::new(r#"pm\.response\.to\.have\.status\((\d+)\)"#).unwrap(),
+            regex_variable_get: Regex::new(
+                r#"pm\.(?:environment|collectionVariables|globals|variables|iterationData)\.get\(([^()]*)\)"#,
+            )
+            .unwrap(),
+            regex_variable_set: Regex::new(
+                r#"pm\.(?:environment|collectionVariables|globals|variables)\.set\("#,
+            )
+            .unwrap(),
+        }
+    }
+
+    fn build_workspace(&mut self, document: &Value) -> Result<Workspace, ApicizeAppError> {
+        let collection_name = document
+            .pointer("/info/name")
+            .and_then(|n| n.as_str())
+            .unwrap_or("Postman Collection");
+
+        let mut workspace = Workspace::new()?;
+
+        // Collection variables become the default scenario
+        if let Some(variables) = document
+            .get("variable")
+            .and_then(|v| v.as_array())
+            .filter(|v| !v.is_empty())
+        {
+            let scenario = Self::build_scenario(collection_name, variables);
+            workspace.defaults.selected_scenario = Some(Selection {
+                id: scenario.id.clone(),
+                name: scenario.name.clone(),
+            });
+            workspace.scenarios.add_entity(scenario, None, None)?;
+        }
+
+        if let Some(auth) = document.get("auth") {
+            workspace.defaults.selected_authorization =
+                self.get_authorization(auth, collection_name);
+        }
+
+        self.check_prerequest_script(document, collection_name);
+        let tests = self
+            .get_test_script(document, collection_name)
+            .into_iter()
+            .collect::<Vec<String>>();
+
+        if let Some(items) = document.get("item").and_then(|i| i.as_array()) {
+            self.add_items(&mut workspace, items, None, &tests)?;
+        }
+
+        for (_, _, authorization) in self.authorizations.drain(..) {
+            workspace
+                .authorizations
+                .add_entity(authorization, None, None)?;
+        }
+
+        Ok(workspace)
+    }
+
+    fn build_environment_workspace(
+        &mut self,
+        document: &Value,
+    ) -> Result<Workspace, ApicizeAppError> {
+        let name = document
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or("Postman Environment");
+        let variables = document
+            .get("values")
+            .and_then(|v| v.as_array())
+            .map(|v| v.as_slice())
+            .unwrap_or_default();
+
+        let mut workspace = Workspace::new()?;
+        let scenario = Self::build_scenario(name, variables);
+        workspace.defaults.selected_scenario = Some(Selection {
+            id: scenario.id.clone(),
+            name: scenario.name.clone(),
+        });
+        workspace.scenarios.add_entity(scenario, None, None)?;
+        Ok(workspace)
+    }
+
+    /// Add folders (as groups) and requests, recursively
+    fn add_items(
+        &mut self,
+        workspace: &mut Workspace,
+        items: &[Value],
+        parent_id: Option<&str>,
+        inherited_tests: &[String],
+    ) -> Result<(), ApicizeAppError> {
+        for item in items {
+            let position = parent_id.map(|_| IndexedEntityPosition::Under);
+            let name = item
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or("(Unnamed)");
+
+            if let Some(children) = item.get("item").and_then(|i| i.as_array()) {
+                let mut group = RequestGroup::default();
+                group.name = name.to_string();
+                if let Some(auth) = item.get("auth") {
+                    group.selected_authorization = self.get_authorization(auth, name);
+                }
+                self.check_prerequest_script(item, name);
+
+                let mut tests = inherited_tests.to_vec();
+                tests.extend(self.get_test_script(item, name));
+
+                let group_id = group.id.clone();
+                workspace
+                    .requests
+                    .add_entity(RequestEntry::Group(group), parent_id, position)?;
+                self.add_items(workspace, children, Some(&group_id), &tests)?;
+            } else if let Some(definition) = item.get("request") {
+                let request = self.build_request(name, item, definition, inherited_tests);
+                workspace.requests.add_entity(
+                    RequestEntry::Request(request),
+                    parent_id,
+                    position,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn build_request(
+        &mut self,
+        name: &str,
+        item: &Value,
+        definition: &Value,
+        inherited_tests: &[String],
+    ) -> Request {
+        let mut request = Request::default();
+        request.name = name.to_string();
+
+        // A request may be specified as only a URL
+        let url_only: Value;
+        let definition = match definition {
+            Value::String(url) => {
+                url_only = json!({ "method": "GET", "url": url });
+                &url_only
+            }
+            _ => definition,
+        };
+
+        let method = definition
+            .get("method")
+            .and_then(|m| m.as_str())
+            .unwrap_or("GET")
+            .to_uppercase();
+        request.method = serde_json::from_value::<RequestMethod>(json!(method)).ok();
+        if request.method.is_none() {
+            self.warnings
+                .push(format!("{method} method on \"{name}\" is not supported"));
+        }
+
+        let (url, query_string_params) = self.build_url(definition.get("url"));
+        request.url = url;
+
+        let mut headers = definition
+            .get("header")
+            .and_then(|h| h.as_array())
+            .map(|h| h.iter().filter_map(Self::to_pair).collect::<Vec<Value>>())
+            .unwrap_or_default();
+
+        if let Some((content_type, body)) = definition
+            .get("body")
+            .and_then(|body| self.build_body(body, name))
+        {
+            if !headers.iter().any(|h| {
+                h.get("name")
+                    .and_then(|n| n.as_str())
+                    .is_some_and(|n| n.eq_ignore_ascii_case("content-type"))
+            }) {
+                headers.push(json!({ "name": "Content-Type", "value": content_type }));
+            }
+            match serde_json::from_value::<RequestBody>(body) {
+                Ok(body) => request.body = Some(body),
+                Err(err) => self
+                    .warnings
+                    .push(format!("Unable to import body for \"{name}\": {err}")),
+            }
+        }
+
+        request.headers = import::to_name_value_pairs(headers);
+        request.query_string_params = import::to_name_value_pairs(query_string_params);
+
+        if let Some(auth) = definition.get("auth") {
+            request.selected_authorization = self.get_authorization(auth, name);
+        }
+
+        if definition.to_string().contains("{{$") {
+            self.warnings.push(format!(
+                "\"{name}\" uses Postman dynamic variables (i.e. {{{{$guid}}}}), which are not supported"
+            ));
+        }
+
+        self.check_prerequest_script(item, name);
+        let mut tests = inherited_tests.to_vec();
+        tests.extend(self.get_test_script(item, name));
+        request.test = Some(if tests.is_empty() {
+            import::status_test(200)
+        } else {
+            tests.join("\n\n")
+        });
+
+        request
+    }
+
+    /// Return the URL (without query string) and query string parameters
+    fn build_url(&self, url: Option<&Value>) -> (String, Vec<Value>) {
+        let (raw, query, variables) = match url {
+            Some(Value::String(raw)) => (raw.as_str(), None, None),
+            Some(url) => (
+                url.get("raw").and_then(|r| r.as_str()).unwrap_or_default(),
+                url.get("query").and_then(|q| q.as_array()),
+                url.get("variable").and_then(|v| v.as_array()),
+            ),
+            None => ("", None, None),
+        };
+
+        let (path, query_string) = raw.split_once('?').unwrap_or((raw, ""));
+
+        let query_string_params = match query {
+            Some(query) => query.iter().filter_map(Self::to_pair).collect(),
+            None => query_string
+                .split('&')
+                .filter(|p| !p.is_empty())
+                .map(|p| {
+                    let (name, value) = p.split_once('=').unwrap_or((p, ""));
+                    json!({ "name": name, "value": value })
+                })
+                .collect(),
+        };
+
+        // Path variables (i.e. "/users/:id") use their value if set, otherwise Apicize variable substitution
+        let path = self
+            .regex_path_variable
+            .replace_all(path, |captures: &regex::Captures| {
+                let variable_name = &captures[1];
+                let value = variables
+                    .and_then(|variables| {
+                        variables
+                            .iter()
+                            .find(|v| v.get("key").and_then(|k| k.as_str()) == Some(variable_name))
+                    })
+                    .map(|v| import::get_text(v.get("value")))
+                    .filter(|v| !v.is_empty());
+                match value {
+                    Some(value) => format!("/{value}"),
+                    None => format!("/{{{{{variable_name}}}}}"),
+                }
+            })
+            .to_string();
+
+        (path, query_string_params)
+    }
+
+    /// Build body, returning its content type and body
+    fn build_body(&mut self, body: &Value, name: &str) -> Option<(String, Value)> {
+        if body.get("disabled").and_then(|d| d.as_bool()) == Some(true) {
+            return None;
+        }
+
+        match body.get("mode").and_then(|m| m.as_str()) {
+            Some("raw") => {
+                let raw = body.get("raw").and_then(|r| r.as_str()).unwrap_or_default();
+                if raw.is_empty() {
+                    return None;
+                }
+                let language = body
+                    .pointer("/options/raw/language")
+                    .and_then(|l| l.as_str())
+                    .unwrap_or("text");
+                match language {
+                    "json" => match serde_json::from_str::<Value>(raw) {
+                        Ok(data) => Some((
+                            "application/json".to_string(),
+                            json!({ "type": "JSON", "data": data }),
+                        )),
+                        Err(_) => {
+                            // Postman allows unquoted variables in JSON (i.e. "id": {{id}})
+                            self.warnings.push(format!(
+                                "JSON body for \"{name}\" could not be parsed and was imported as text"
+                            ));
+                            Some((
+                                "application/json".to_string(),
+                                json!({ "type": "Text", "data": raw }),
+                            ))
+                        }
+                    },
+                    "xml" => Some((
+                        "application/xml".to_string(),
+                        json!({ "type": "XML", "data": raw }),
+                    )),
+                    "html" => Some((
+                        "text/html".to_string(),
+                        json!({ "type": "Text", "data": raw }),
+                    )),
+                    "javascript" => Some((
+                        "application/javascript".to_string(),
+                        json!({ "type": "Text", "data": raw }),
+                    )),
+                    _ => Some((
+                        "text/plain".to_string(),
+                        json!({ "type": "Text", "data": raw }),
+                    )),
+                }
+            }
+            Some("urlencoded") => {
+                let data = body
+                    .get("urlencoded")
+                    .and_then(|u| u.as_array())
+                    .map(|u| u.iter().filter_map(Self::to_pair).collect::<Vec<Value>>())
+                    .unwrap_or_default();
+                Some((
+                    "application/x-www-form-urlencoded".to_string(),
+                    json!({ "type": "Form", "data": data }),
+                ))
+            }
+            Some("formdata") => {
+                let fields = body
+                    .get("formdata")
+                    .and_then(|f| f.as_array())
+                    .map(|f| f.as_slice())
+                    .unwrap_or_default();
+                let (files, fields): (Vec<&Value>, Vec<&Value>) = fields
+                    .iter()
+                    .partition(|f| f.get("type").and_then(|t| t.as_str()) == Some("file"));
+                self.warnings.push(format!(
+                    "Multipart form data for \"{name}\" was imported as a URL encoded form{}",
+                    if files.is_empty() {
+                        ""
+                    } else {
+                        ", file fields were not imported"
+                    }
+                ));
+                let data = fields
+                    .into_iter()
+                    .filter_map(Self::to_pair)
+                    .collect::<Vec<Value>>();
+                Some((
+                    "application/x-www-form-urlencoded".to_string(),
+                    json!({ "type": "Form", "data": data }),
+                ))
+            }
+            Some("graphql") => {
+                let query = body
+                    .pointer("/graphql/query")
+                    .and_then(|q| q.as_str())
+                    .unwrap_or_default();
+                let variables = body
+                    .pointer("/graphql/variables")
+                    .and_then(|v| v.as_str())
+                    .filter(|v| !v.trim().is_empty())
+                    .and_then(|v| serde_json::from_str::<Value>(v).ok())
+                    .unwrap_or(json!({}));
+                Some((
+                    "application/json".to_string(),
+                    json!({ "type": "JSON", "data": { "query": query, "variables": variables } }),
+                ))
+            }
+            Some(mode) => {
+                self.warnings
+                    .push(format!("{mode} body for \"{name}\" is not supported"));
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Return a selection for the authorization converted from a Postman auth block,
+    /// reusing a previously converted authorization if the block is identical
+    fn get_authorization(&mut self, auth: &Value, owner: &str) -> Option<Selection> {
+        let auth_type = auth
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or("noauth");
+        if auth_type == "noauth" {
+            return Some(Selection {
+                id: NO_SELECTION_ID.to_string(),
+                name: "Off".to_string(),
+            });
+        }
+
+        if let Some((_, selection, _)) = self.authorizations.iter().find(|(a, _, _)| a == auth) {
+            return Some(selection.clone());
+        }
+
+        let parameter = |key: &str| {
+            import::get_text(
+                auth.get(auth_type)
+                    .and_then(|p| p.as_array())
+                    .and_then(|p| {
+                        p.iter()
+                            .find(|p| p.get("key").and_then(|k| k.as_str()) == Some(key))
+                    })
+                    .and_then(|p| p.get("value")),
+            )
+        };
+
+        let definition = match auth_type {
+            "basic" => json!({
+                "type": "Basic",
+                "username": parameter("username"),
+                "password": parameter("password"),
+            }),
+            "bearer" => json!({
+                "type": "ApiKey",
+                "header": "Authorization",
+                "value": format!("Bearer {}", parameter("token")),
+            }),
+            "apikey" if parameter("in") == "query" => {
+                self.warnings.push(format!(
+                    "API key authorization passed via query string on \"{owner}\" is not supported"
+                ));
+                return None;
+            }
+            "apikey" => json!({
+                "type": "ApiKey",
+                "header": parameter("key"),
+                "value": parameter("value"),
+            }),
+            "oauth2" => match parameter("grant_type").as_str() {
+                "client_credentials" => json!({
+                    "type": "OAuth2Client",
+                    "accessTokenUrl": parameter("accessTokenUrl"),
+                    "clientId": parameter("clientId"),
+                    "clientSecret": parameter("clientSecret"),
+                    "scope": parameter("scope"),
+                    "audience": "",
+                    "sendCredentialsInBody": parameter("client_authentication") == "body",
+                }),
+                "authorization_code_with_pkce" => json!({
+                    "type": "OAuth2Pkce",
+                    "authorizeUrl": parameter("authUrl"),
+                    "accessTokenUrl": parameter("accessTokenUrl"),
+                    "clientId": parameter("clientId"),
+                    "scope": parameter("scope"),
+                    "audience": "",
+                    "sendCredentialsInBody": parameter("client_authentication") == "body",
+                }),
+                grant_type => {
+                    self.warnings.push(format!(
+                        "OAuth2 grant type \"{}\" on \"{owner}\" is not supported",
+                        if grant_type.is_empty() {
+                            "authorization_code"
+                        } else {
+                            grant_type
+                        }
+                    ));
+                    return None;
+                }
+            },
+            other => {
+                self.warnings.push(format!(
+                    "{other} authorization on \"{owner}\" is not supported"
+                ));
+                return None;
+            }
+        };
+
+        let selection = Selection {
+            id: Uuid::new_v4().to_string(),
+            name: owner.to_string(),
+        };
+        let mut definition = definition;
+        definition["id"] = json!(selection.id);
+        definition["name"] = json!(selection.name);
+
+        match serde_json::from_value::<Authorization>(definition) {
+            Ok(authorization) => {
+                self.authorizations
+                    .push((auth.clone(), selection.clone(), authorization));
+                Some(selection)
+            }
+            Err(err) => {
+                self.warnings.push(format!(
+                    "Unable to import authorization on \"{owner}\": {err}"
+                ));
+                None
+            }
+        }
+    }
+
+    fn build_scenario(name: &str, variables: &[Value]) -> Scenario {
+        let variables = variables
+            .iter()
+            .filter_map(|v| {
+                let mut variable = json!({
+                    "name": v.get("key").and_then(|k| k.as_str())?,
+                    "type": "TEXT",
+                    "value": import::get_text(v.get("value")),
+                });
+                if v.get("disabled").and_then(|d| d.as_bool()) == Some(true)
+                    || v.get("enabled").and_then(|e| e.as_bool()) == Some(false)
+                {
+                    variable["disabled"] = json!(true);
+                }
+                Some(variable)
+            })
+            .collect::<Vec<Value>>();
+
+        let mut scenario = Scenario::default();
+        scenario.name = name.to_string();
+        if !variables.is_empty() {
+            scenario.variables = serde_json::from_value(Value::Array(variables)).ok();
+        }
+        scenario
+    }
+
+    /// Return the script lines for the specified event (i.e. "test", "prerequest")
+    fn get_script(entity: &Value, listen: &str) -> Option<String> {
+        let script = entity
+            .get("event")
+            .and_then(|e| e.as_array())?
+            .iter()
+            .filter(|e| e.get("listen").and_then(|l| l.as_str()) == Some(listen))
+            .filter_map(|e| e.pointer("/script/exec"))
+            .flat_map(|exec| match exec {
+                Value::Array(lines) => lines
+                    .iter()
+                    .filter_map(|l| l.as_str())
+                    .map(|l| l.to_string())
+                    .collect::<Vec<String>>(),
+                Value::String(text) => vec![text.clone()],
+                _ => vec![],
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        if script.trim().is_empty() {
+            None
+        } else {
+            Some(script)
+        }
+    }
+
+    fn check_prerequest_script(&mut self, entity: &Value, owner: &str) {
+        if Self::get_script(entity, "prerequest").is_some() {
+            self.warnings.push(format!(
+                "Pre-request script on \"{owner}\" was not imported, pre-request scripts are not supported"
+            ));
+        }
+    }
+
+    /// Translate a Postman test script into an Apicize test
+    fn get_test_script(&mut self, entity: &Value, owner: &str) -> Option<String> {
+        let script = Self::get_script(entity, "test")?;
+
+        let script = self
+            .regex_status
+            .replace_all(&script, "expect(response.status).to.equal($1)");
+        let script = self.regex_variable_get.replace_all(&script, "$$[$1]");
+        let script = self
+            .regex_variable_set
+            .replace_all(&script, "output(")
+            .replace("pm.test(", "it(")
+            .replace("pm.expect(", "expect(")
+            .replace("pm.response.json()", "response.body.data")
+            .replace("pm.response.text()", "response.body.text")
+            .replace("pm.response.code", "response.status")
+            .replace("pm.response.status", "response.statusText");
+
+        if script.contains("pm.") || script.contains("postman.") {
+            self.warnings.push(format!(
+                "Test script on \"{owner}\" includes Postman functions that could not be translated"
+            ));
+        }
+
+        let indented = script
+            .lines()
+            .map(|line| {
+                if line.is_empty() {
+                    String::default()
+                } else {
+                    format!("   {line}")
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        Some(format!(
+            "describe('{}', () => {{\n{}\n}})",
+            owner.replace('\\', "\\\\").replace('\'', "\\'"),
+            indented
+        ))
+    }
+
+    /// Convert a Postman key/value entry to a name/value pair
+    fn to_pair(entry: &Value) -> Option<Value> {
+        let mut pair = json!({
+            "name": entry.get("key").and_then(|k| k.as_str())?,
+            "value": import::get_text(entry.get("value")),
+        });
+        if entry.get("disabled").and_then(|d| d.as_bool()) == Some(true) {
+            pair["disabled"] = json!(true);
+        }
+        Some(pair)
+    }
+}
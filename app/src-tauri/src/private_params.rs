@@ -0,0 +1,78 @@
+//! Private parameters submodule
+//!
+//! This submodule stores a workbook's private parameters in a passphrase-encrypted file
+//! alongside the workbook, in place of the plaintext private parameter file
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use apicize_lib::Workspace;
+
+use crate::{
+    error::ApicizeAppError,
+    secrets::{decrypt, derive_key, encrypt, generate_salt, get_salt, StoredParameters},
+};
+
+/// Parameter section for private parameters
+pub const PRIVATE_SECTION: &str = "P";
+
+/// Return the name of the plaintext private parameter file for a workbook
+pub fn get_private_filename(workbook_file_name: &Path) -> PathBuf {
+    workbook_file_name.with_extension("apicize-priv")
+}
+
+/// Return the name of the encrypted private parameter file for a workbook
+pub fn get_encrypted_private_filename(workbook_file_name: &Path) -> PathBuf {
+    workbook_file_name.with_extension("apicize-priv-enc")
+}
+
+/// Return true if the workbook has an encrypted private parameter file
+pub fn is_encrypted(workbook_file_name: &Path) -> bool {
+    get_encrypted_private_filename(workbook_file_name).is_file()
+}
+
+/// Decrypt private parameters for the workbook, returning None if it has no encrypted file
+pub fn open(
+    workbook_file_name: &Path,
+    passphrase: &str,
+) -> Result<Option<StoredParameters>, ApicizeAppError> {
+    let file_name = get_encrypted_private_filename(workbook_file_name);
+    if !file_name.is_file() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(&file_name)?;
+    let Some(salt) = get_salt(&contents)? else {
+        return Err(ApicizeAppError::SecretStoreError(format!(
+            "{} is missing its salt",
+            file_name.to_string_lossy()
+        )));
+    };
+    let key = derive_key(passphrase, &salt)?;
+    Ok(Some(serde_json::from_slice(&decrypt(&key, &contents)?)?))
+}
+
+/// Write the workspace's private parameters to the workbook's encrypted file, returning a
+/// copy of the workspace without them, suitable for saving to disk
+pub fn save(
+    workbook_file_name: &Path,
+    passphrase: &str,
+    workspace: &Workspace,
+) -> Result<Workspace, ApicizeAppError> {
+    let mut stripped = workspace.clone();
+    let parameters = StoredParameters::take(&mut stripped, PRIVATE_SECTION)?;
+    let salt = generate_salt();
+    let key = derive_key(passphrase, &salt)?;
+    let contents = encrypt(&key, Some(&salt), &serde_json::to_vec(&parameters)?)?;
+    fs::write(get_encrypted_private_filename(workbook_file_name), contents)?;
+    Ok(stripped)
+}
+
+/// Remove a file, if it exists
+pub fn remove_file(file_name: &Path) -> Result<(), ApicizeAppError> {
+    if file_name.is_file() {
+        fs::remove_file(file_name)?;
+    }
+    Ok(())
+}
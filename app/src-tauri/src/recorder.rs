@@ -0,0 +1,441 @@
+//! Recording proxy submodule
+//!
+//! This submodule runs a local HTTP forward proxy that records each exchange passing
+//! through it, so captured calls can be converted into workbook requests
+
+use std::{
+    collections::VecDeque,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    thread::JoinHandle,
+    time::Instant,
+};
+
+use actix_web::{
+    http::{Method, StatusCode},
+    web::{self, Bytes, Data},
+    App, HttpRequest, HttpResponse, HttpResponseBuilder, HttpServer,
+};
+use chrono::Utc;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+use crate::{error::ApicizeAppError, pkce::StopHandle, SessionsState, WorkspacesState};
+
+/// Maximum number of exchanges retained, oldest are discarded first
+const MAX_EXCHANGES: usize = 1000;
+
+/// Maximum number of body bytes retained for each request and response
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Headers that apply to a single connection and are not forwarded
+const HOP_BY_HOP_HEADERS: [&str; 9] = [
+    "connection",
+    "proxy-connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RecordedHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// Request and response captured by the recording proxy
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedExchange {
+    pub id: String,
+    /// Time request was received (milliseconds since Unix epoch)
+    pub timestamp: i64,
+    pub method: String,
+    pub url: String,
+    pub request_headers: Vec<RecordedHeader>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    /// Set if the request body exceeded the retained size and was cut short
+    #[serde(default)]
+    pub request_body_truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    pub response_headers: Vec<RecordedHeader>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_body: Option<String>,
+    /// Set if the response body exceeded the retained size and was cut short
+    #[serde(default)]
+    pub response_body_truncated: bool,
+    /// Time taken to receive the response, in milliseconds
+    pub duration: u64,
+    /// Error forwarding the request, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Running recording proxy information
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecorderStatus {
+    pub port: u16,
+    /// Address the proxy listens on
+    pub address: String,
+    pub url: String,
+}
+
+pub struct ProxyRecorder {
+    tauri: AppHandle,
+    status: Option<RecorderStatus>,
+    exchanges: Arc<Mutex<VecDeque<RecordedExchange>>>,
+    stop: Option<web::Data<StopHandle>>,
+    server: Option<JoinHandle<Result<(), std::io::Error>>>,
+}
+
+#[derive(Clone)]
+struct ProxyRecorderData {
+    tauri: AppHandle,
+    client: reqwest::Client,
+    exchanges: Arc<Mutex<VecDeque<RecordedExchange>>>,
+}
+
+impl ProxyRecorder {
+    pub fn new(app_handle: AppHandle) -> Self {
+        ProxyRecorder {
+            tauri: app_handle,
+            status: None,
+            exchanges: Arc::new(Mutex::new(VecDeque::new())),
+            stop: None,
+            server: None,
+        }
+    }
+
+    pub fn get_status(&self) -> Option<RecorderStatus> {
+        self.status.clone()
+    }
+
+    /// Start recording on the specified port, replacing any running proxy.  The proxy listens
+    /// on the loopback interface unless another address (e.g. 0.0.0.0 for all interfaces)
+    /// is specified
+    pub fn start(
+        &mut self,
+        port: u16,
+        bind_address: Option<&str>,
+    ) -> Result<RecorderStatus, ApicizeAppError> {
+        if port == 0 {
+            return Err(ApicizeAppError::InvalidOperation(
+                "a recording proxy port is required".to_string(),
+            ));
+        }
+
+        let address = match bind_address.map(|a| a.trim()).filter(|a| !a.is_empty()) {
+            Some(address) => address.parse::<IpAddr>().map_err(|_| {
+                ApicizeAppError::InvalidOperation(format!(
+                    "\"{address}\" is not a valid recording proxy address"
+                ))
+            })?,
+            None => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        };
+        let socket_address = SocketAddr::new(address, port);
+
+        self.stop();
+
+        // Redirects are passed back to the client so that each hop is recorded
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy()
+            .build()
+            .map_err(|err| ApicizeAppError::InvalidOperation(err.to_string()))?;
+
+        let data = ProxyRecorderData {
+            tauri: self.tauri.clone(),
+            client,
+            exchanges: self.exchanges.clone(),
+        };
+        let stop_handle = web::Data::new(StopHandle::default());
+        let cloned_stop_handle = stop_handle.clone();
+
+        self.server = Some(std::thread::spawn(move || {
+            init_recorder_server(data, socket_address, cloned_stop_handle)
+        }));
+        self.stop = Some(stop_handle);
+
+        let status = RecorderStatus {
+            port,
+            address: address.to_string(),
+            url: format!("http://{socket_address}"),
+        };
+        self.status = Some(status.clone());
+        Ok(status)
+    }
+
+    /// Stop recording, if running; captured exchanges are retained
+    pub fn stop(&mut self) {
+        self.status = None;
+
+        if let Some(h) = self.stop.take() {
+            h.stop(false);
+        }
+
+        if let Some(s) = self.server.take() {
+            if let Err(err) = s.join() {
+                self.tauri
+                    .emit("recorder-error", format!("{err:?}"))
+                    .unwrap();
+            }
+        }
+    }
+
+    pub fn list_exchanges(&self) -> Vec<RecordedExchange> {
+        self.exchanges.lock().iter().cloned().collect()
+    }
+
+    pub fn clear_exchanges(&self) {
+        self.exchanges.lock().clear();
+    }
+
+    /// Build an HTTP Archive document from the specified exchanges, in the order recorded;
+    /// exchanges whose request body was truncated cannot be converted
+    pub fn to_har(&self, exchange_ids: &[String]) -> Result<Value, ApicizeAppError> {
+        let exchanges = self.exchanges.lock();
+        for id in exchange_ids {
+            match exchanges.iter().find(|e| e.id == *id) {
+                Some(exchange) if exchange.request_body_truncated => {
+                    return Err(ApicizeAppError::InvalidOperation(format!(
+                        "request body of {} {} exceeded {MAX_BODY_SIZE} bytes and was not fully recorded",
+                        exchange.method, exchange.url
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    return Err(ApicizeAppError::InvalidOperation(format!(
+                        "recorded exchange '{id}' is not available"
+                    )));
+                }
+            }
+        }
+
+        let entries = exchanges
+            .iter()
+            .filter(|e| exchange_ids.contains(&e.id))
+            .map(|exchange| {
+                let mut request = json!({
+                    "method": exchange.method,
+                    "url": exchange.url,
+                    "headers": exchange.request_headers,
+                });
+                if let Some(body) = &exchange.request_body {
+                    let mime_type = exchange
+                        .request_headers
+                        .iter()
+                        .find(|h| h.name.eq_ignore_ascii_case("content-type"))
+                        .map(|h| h.value.as_str())
+                        .unwrap_or("text/plain");
+                    request["postData"] = json!({ "mimeType": mime_type, "text": body });
+                }
+                json!({
+                    "request": request,
+                    "response": { "status": exchange.status.unwrap_or_default() },
+                })
+            })
+            .collect::<Vec<Value>>();
+
+        Ok(json!({ "log": { "entries": entries } }))
+    }
+}
+
+#[actix_web::main]
+async fn init_recorder_server(
+    data: ProxyRecorderData,
+    address: SocketAddr,
+    stop_handle: Data<StopHandle>,
+) -> Result<(), std::io::Error> {
+    let tauri = data.tauri.clone();
+    let app_data = web::Data::new(data);
+
+    let http_server = HttpServer::new(move || {
+        App::new()
+            .app_data(app_data.clone())
+            .default_service(web::to(forward))
+    })
+    .bind(address);
+
+    match http_server {
+        Ok(server) => {
+            println!("*** Started recording proxy at {address} ***");
+            let running_server = server.run();
+            stop_handle.register(running_server.handle());
+            running_server.await
+        }
+        Err(err) => {
+            tauri
+                .emit(
+                    "recorder-error",
+                    format!("Unable to start recording proxy at http://{address}, {err}"),
+                )
+                .unwrap();
+            eprintln!("Unable to start recording proxy at http://{address}, {err}");
+            Err(err)
+        }
+    }
+}
+
+fn is_hop_by_hop(name: &str) -> bool {
+    HOP_BY_HOP_HEADERS.contains(&name.to_ascii_lowercase().as_str())
+}
+
+/// Return body text, limited to the retained size, and whether it was truncated
+fn body_text(body: &[u8]) -> (Option<String>, bool) {
+    if body.is_empty() {
+        (None, false)
+    } else {
+        (
+            Some(String::from_utf8_lossy(&body[..body.len().min(MAX_BODY_SIZE)]).to_string()),
+            body.len() > MAX_BODY_SIZE,
+        )
+    }
+}
+
+/// Forward a proxied request to its destination, recording the exchange
+async fn forward(request: HttpRequest, body: Bytes, data: Data<ProxyRecorderData>) -> HttpResponse {
+    // Encrypted traffic cannot be inspected without intercepting TLS
+    if request.method() == Method::CONNECT {
+        return HttpResponse::MethodNotAllowed()
+            .body("HTTPS tunneling cannot be recorded, send requests using http:// URLs");
+    }
+
+    let uri = request.uri();
+    if uri.scheme().is_none() || uri.authority().is_none() {
+        return HttpResponse::BadRequest()
+            .body("Configure the recorder as an HTTP proxy to send requests through it");
+    }
+
+    let started = Instant::now();
+    let (request_body, request_body_truncated) = body_text(&body);
+    let mut exchange = RecordedExchange {
+        id: Uuid::new_v4().to_string(),
+        timestamp: Utc::now().timestamp_millis(),
+        method: request.method().to_string(),
+        url: uri.to_string(),
+        request_headers: request
+            .headers()
+            .iter()
+            .filter(|(name, _)| !is_hop_by_hop(name.as_str()))
+            .map(|(name, value)| RecordedHeader {
+                name: name.to_string(),
+                value: String::from_utf8_lossy(value.as_bytes()).to_string(),
+            })
+            .collect(),
+        request_body,
+        request_body_truncated,
+        status: None,
+        response_headers: vec![],
+        response_body: None,
+        response_body_truncated: false,
+        duration: 0,
+        error: None,
+    };
+
+    let result = match reqwest::Method::from_bytes(request.method().as_str().as_bytes()) {
+        Ok(method) => {
+            let mut outgoing = data.client.request(method, &exchange.url);
+            for (name, value) in request.headers() {
+                if name.as_str() != "host" && !is_hop_by_hop(name.as_str()) {
+                    outgoing = outgoing.header(name.as_str(), value.as_bytes());
+                }
+            }
+            if !body.is_empty() {
+                outgoing = outgoing.body(body.to_vec());
+            }
+            match outgoing.send().await {
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    let headers = response
+                        .headers()
+                        .iter()
+                        .filter(|(name, _)| !is_hop_by_hop(name.as_str()))
+                        .map(|(name, value)| (name.to_string(), value.as_bytes().to_vec()))
+                        .collect::<Vec<(String, Vec<u8>)>>();
+                    response
+                        .bytes()
+                        .await
+                        .map(|bytes| (status, headers, bytes))
+                        .map_err(|err| err.to_string())
+                }
+                Err(err) => Err(err.to_string()),
+            }
+        }
+        Err(err) => Err(err.to_string()),
+    };
+    exchange.duration = started.elapsed().as_millis() as u64;
+
+    let response = match result {
+        Ok((status, headers, bytes)) => {
+            exchange.status = Some(status);
+            exchange.response_headers = headers
+                .iter()
+                .map(|(name, value)| RecordedHeader {
+                    name: name.clone(),
+                    value: String::from_utf8_lossy(value).to_string(),
+                })
+                .collect();
+            (exchange.response_body, exchange.response_body_truncated) = body_text(&bytes);
+
+            let mut response = HttpResponseBuilder::new(
+                StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY),
+            );
+            for (name, value) in headers {
+                // Length is recalculated for the returned body
+                if name != "content-length" {
+                    response.append_header((name, value));
+                }
+            }
+            response.body(bytes.to_vec())
+        }
+        Err(err) => {
+            exchange.error = Some(err.clone());
+            HttpResponse::BadGateway().body(err)
+        }
+    };
+
+    emit_exchange(&data.tauri, &exchange).await;
+    let mut exchanges = data.exchanges.lock();
+    if exchanges.len() >= MAX_EXCHANGES {
+        exchanges.pop_front();
+    }
+    exchanges.push_back(exchange);
+
+    response
+}
+
+/// Send a recorded exchange to each session, masking secrets unless the session has
+/// revealed them
+async fn emit_exchange(app: &AppHandle, exchange: &RecordedExchange) {
+    let (Some(sessions_state), Some(workspaces_state)) = (
+        app.try_state::<SessionsState>(),
+        app.try_state::<WorkspacesState>(),
+    ) else {
+        return;
+    };
+
+    let sessions = sessions_state.sessions.read().await;
+    let mut redacted: Option<RecordedExchange> = None;
+    for (session_id, session) in &sessions.sessions {
+        let session_exchange = if session.reveal_secrets {
+            exchange
+        } else {
+            if redacted.is_none() {
+                let redactor = workspaces_state.workspaces.read().await.get_all_redactor();
+                redacted = Some(redactor.redact_exchange(exchange));
+            }
+            redacted.as_ref().unwrap_or(exchange)
+        };
+        let _ = app.emit_to(session_id, "recorded-exchange", session_exchange);
+    }
+}
@@ -0,0 +1,329 @@
+//! Recovery submodule
+//!
+//! This submodule periodically saves snapshots of workspaces with unsaved changes to the
+//! settings directory, so that work can be restored after a crash.  Private and vault
+//! parameters are not included in snapshots, so that secrets are not copied there
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, UNIX_EPOCH},
+};
+
+use apicize_lib::{IndexedEntities, Workspace};
+use chrono::Utc;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    error::ApicizeAppError, mock::MockResponse, settings::ApicizeSettings,
+    workspaces::WorkspaceInfo,
+};
+
+/// How often workspaces with unsaved changes are saved for recovery
+pub const RECOVERY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Parameter sections holding secrets, which are not saved for recovery
+const EXCLUDED_SECTIONS: [&str; 2] = ["P", "V"];
+
+/// Entity collection saved for recovery
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct RecoveredEntities {
+    top_level_ids: Vec<String>,
+    child_ids: BTreeMap<String, Vec<String>>,
+    entities: BTreeMap<String, Value>,
+}
+
+/// Workspace contents saved for recovery
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecoverySnapshot {
+    /// Workbook file name, empty if the workspace has never been saved
+    file_name: String,
+    display_name: String,
+    /// Time of the snapshot (milliseconds since Unix epoch)
+    timestamp: i64,
+    requests: RecoveredEntities,
+    scenarios: RecoveredEntities,
+    authorizations: RecoveredEntities,
+    certificates: RecoveredEntities,
+    proxies: RecoveredEntities,
+    data: Vec<Value>,
+    defaults: Value,
+    /// Mock responses, keyed by request ID (not present in snapshots from earlier versions)
+    #[serde(default)]
+    mocks: Option<BTreeMap<String, MockResponse>>,
+}
+
+/// Unsaved work that can be restored
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryInfo {
+    pub recovery_id: String,
+    /// Workbook file name, empty if the workspace had never been saved
+    pub file_name: String,
+    pub display_name: String,
+    /// Time of the snapshot (milliseconds since Unix epoch)
+    pub timestamp: i64,
+}
+
+/// Tracks recovery snapshots written during this app session
+#[derive(Default)]
+pub struct WorkspaceRecovery {
+    /// Serialized snapshot last written for each workspace ID
+    written: FxHashMap<String, String>,
+}
+
+impl WorkspaceRecovery {
+    /// Return the directory recovery snapshots are written to
+    pub fn get_recovery_directory() -> PathBuf {
+        ApicizeSettings::get_settings_directory().join("recovery")
+    }
+
+    /// Return the snapshot file name for a recovery ID, which must be a workspace ID
+    fn get_snapshot_filename(recovery_id: &str) -> Result<PathBuf, ApicizeAppError> {
+        if recovery_id.is_empty()
+            || !recovery_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(ApicizeAppError::InvalidOperation(format!(
+                "invalid recovery ID '{recovery_id}'"
+            )));
+        }
+        Ok(Self::get_recovery_directory().join(format!("{recovery_id}.json")))
+    }
+
+    /// Write snapshots of workspaces with unsaved changes, and remove snapshots of
+    /// workspaces that have since been saved or closed
+    pub fn update(
+        &mut self,
+        workspaces: &FxHashMap<String, WorkspaceInfo>,
+    ) -> Result<(), ApicizeAppError> {
+        let removed = self
+            .written
+            .keys()
+            .filter(|id| workspaces.get(*id).is_none_or(|info| !info.dirty))
+            .cloned()
+            .collect::<Vec<String>>();
+        for workspace_id in removed {
+            remove_file(&Self::get_snapshot_filename(&workspace_id)?)?;
+            self.written.remove(&workspace_id);
+        }
+
+        for (workspace_id, info) in workspaces.iter().filter(|(_, info)| info.dirty) {
+            let mut snapshot = serde_json::to_value(RecoverySnapshot {
+                file_name: info.file_name.clone(),
+                display_name: info.display_name.clone(),
+                timestamp: 0,
+                requests: save_entities(&info.workspace.requests)?,
+                scenarios: save_entities(&info.workspace.scenarios)?,
+                authorizations: save_entities(&info.workspace.authorizations)?,
+                certificates: save_entities(&info.workspace.certificates)?,
+                proxies: save_entities(&info.workspace.proxies)?,
+                data: info
+                    .workspace
+                    .data
+                    .iter()
+                    .map(serde_json::to_value)
+                    .collect::<Result<Vec<Value>, serde_json::Error>>()?,
+                defaults: serde_json::to_value(&info.workspace.defaults)?,
+                mocks: Some(
+                    info.mocks
+                        .iter()
+                        .map(|(id, mock)| (id.clone(), mock.clone()))
+                        .collect(),
+                ),
+            })?;
+
+            // Snapshots are only rewritten when the workspace has changed
+            let contents = serde_json::to_string(&snapshot)?;
+            if self.written.get(workspace_id) == Some(&contents) {
+                continue;
+            }
+            snapshot["timestamp"] = Value::from(Utc::now().timestamp_millis());
+
+            fs::create_dir_all(Self::get_recovery_directory())?;
+            fs::write(
+                Self::get_snapshot_filename(workspace_id)?,
+                serde_json::to_string(&snapshot)?,
+            )?;
+            self.written.insert(workspace_id.clone(), contents);
+        }
+        Ok(())
+    }
+
+    /// Return snapshots left by previous app sessions that are newer than their workbooks,
+    /// removing snapshots that are out of date or superseded by a newer one
+    pub fn find_recoveries() -> Vec<RecoveryInfo> {
+        let Ok(entries) = fs::read_dir(Self::get_recovery_directory()) else {
+            return vec![];
+        };
+
+        let mut newest = FxHashMap::<String, RecoveryInfo>::default();
+        let mut unnamed = Vec::new();
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            let (Some(recovery_id), Ok(snapshot)) = (
+                path.file_stem().map(|s| s.to_string_lossy().to_string()),
+                read_snapshot(&path),
+            ) else {
+                continue;
+            };
+            let info = RecoveryInfo {
+                recovery_id,
+                file_name: snapshot.file_name,
+                display_name: snapshot.display_name,
+                timestamp: snapshot.timestamp,
+            };
+
+            if info.file_name.is_empty() {
+                unnamed.push(info);
+                continue;
+            }
+
+            let workbook_modified = fs::metadata(&info.file_name)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as i64);
+            if workbook_modified.is_some_and(|m| m >= info.timestamp) {
+                let _ = remove_file(&path);
+                continue;
+            }
+
+            match newest.get(&info.file_name) {
+                Some(existing) if existing.timestamp >= info.timestamp => {
+                    let _ = remove_file(&path);
+                }
+                _ => {
+                    if let Some(existing) = newest.insert(info.file_name.clone(), info) {
+                        if let Ok(file_name) = Self::get_snapshot_filename(&existing.recovery_id) {
+                            let _ = remove_file(&file_name);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut recoveries = newest
+            .into_values()
+            .chain(unnamed)
+            .collect::<Vec<RecoveryInfo>>();
+        recoveries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        recoveries
+    }
+
+    /// Replace the workspace's workbook contents and mock responses with those of a snapshot,
+    /// keeping its private and vault parameters
+    pub fn restore(
+        recovery_id: &str,
+        workspace: &mut Workspace,
+        mocks: &mut FxHashMap<String, MockResponse>,
+    ) -> Result<(), ApicizeAppError> {
+        let snapshot = read_snapshot(&Self::get_snapshot_filename(recovery_id)?)?;
+        restore_entities(&mut workspace.requests, snapshot.requests, false)?;
+        restore_entities(&mut workspace.scenarios, snapshot.scenarios, true)?;
+        restore_entities(&mut workspace.authorizations, snapshot.authorizations, true)?;
+        restore_entities(&mut workspace.certificates, snapshot.certificates, true)?;
+        restore_entities(&mut workspace.proxies, snapshot.proxies, true)?;
+        workspace.data = snapshot
+            .data
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<Result<_, serde_json::Error>>()?;
+        workspace.defaults = serde_json::from_value(snapshot.defaults)?;
+        if let Some(recovered) = snapshot.mocks {
+            *mocks = recovered.into_iter().collect();
+        }
+        Ok(())
+    }
+
+    /// Return the workbook file name a snapshot was taken from, empty if never saved
+    pub fn get_file_name(recovery_id: &str) -> Result<String, ApicizeAppError> {
+        Ok(read_snapshot(&Self::get_snapshot_filename(recovery_id)?)?.file_name)
+    }
+
+    /// Delete a snapshot
+    pub fn discard(recovery_id: &str) -> Result<(), ApicizeAppError> {
+        remove_file(&Self::get_snapshot_filename(recovery_id)?)
+    }
+}
+
+fn read_snapshot(file_name: &Path) -> Result<RecoverySnapshot, ApicizeAppError> {
+    Ok(serde_json::from_str(&fs::read_to_string(file_name)?)?)
+}
+
+fn remove_file(file_name: &Path) -> Result<(), ApicizeAppError> {
+    if file_name.is_file() {
+        fs::remove_file(file_name)?;
+    }
+    Ok(())
+}
+
+fn save_entities<T: Serialize>(
+    entities: &IndexedEntities<T>,
+) -> Result<RecoveredEntities, ApicizeAppError> {
+    let mut excluded = FxHashSet::default();
+    for section in EXCLUDED_SECTIONS {
+        excluded.extend(entities.child_ids.get(section).into_iter().flatten());
+    }
+
+    let mut recovered = RecoveredEntities {
+        top_level_ids: entities.top_level_ids.clone(),
+        ..Default::default()
+    };
+    for (parent_id, child_ids) in entities.child_ids.iter() {
+        if !EXCLUDED_SECTIONS.contains(&parent_id.as_str()) {
+            recovered
+                .child_ids
+                .insert(parent_id.clone(), child_ids.clone());
+        }
+    }
+    for (id, entity) in entities.entities.iter() {
+        if !excluded.contains(id) {
+            recovered
+                .entities
+                .insert(id.clone(), serde_json::to_value(entity)?);
+        }
+    }
+    Ok(recovered)
+}
+
+/// Replace entities with recovered ones; for parameters, those in excluded sections are kept
+fn restore_entities<T: DeserializeOwned>(
+    entities: &mut IndexedEntities<T>,
+    recovered: RecoveredEntities,
+    keep_excluded: bool,
+) -> Result<(), ApicizeAppError> {
+    let mut kept = FxHashSet::default();
+    if keep_excluded {
+        for section in EXCLUDED_SECTIONS {
+            kept.extend(
+                entities
+                    .child_ids
+                    .get(section)
+                    .into_iter()
+                    .flatten()
+                    .cloned(),
+            );
+        }
+    }
+
+    entities.entities.retain(|id, _| kept.contains(id));
+    entities
+        .child_ids
+        .retain(|parent_id, _| keep_excluded && EXCLUDED_SECTIONS.contains(&parent_id.as_str()));
+    entities.top_level_ids = recovered.top_level_ids;
+    for (parent_id, child_ids) in recovered.child_ids {
+        entities.child_ids.insert(parent_id, child_ids);
+    }
+    for (id, entity) in recovered.entities {
+        entities
+            .entities
+            .insert(id, serde_json::from_value(entity)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,268 @@
+//! Redaction submodule
+//!
+//! This submodule masks workspace secrets (authorization credentials, certificate keys and
+//! private or vault parameter values) and sensitive HTTP header values in text emitted by the
+//! backend
+
+use apicize_lib::Workspace;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{
+    error::ApicizeAppError,
+    recorder::{RecordedExchange, RecordedHeader},
+    trace::ReqwestEvent,
+};
+
+/// Value substituted for redacted text
+pub const REDACTED: &str = "********";
+
+/// Secrets shorter than this are not masked, to avoid obliterating unrelated text
+const MIN_SECRET_LENGTH: usize = 4;
+
+/// Scenario sections whose variable values are treated as secrets
+const SECRET_PARAMETER_SECTIONS: [&str; 2] = ["P", "V"];
+
+/// Names of headers whose values are redacted by default (case-insensitive, "*" matches any text)
+pub fn default_redacted_headers() -> Vec<String> {
+    [
+        "authorization",
+        "proxy-authorization",
+        "cookie",
+        "set-cookie",
+        "*api-key*",
+        "*api_key*",
+        "*apikey*",
+    ]
+    .iter()
+    .map(|h| h.to_string())
+    .collect()
+}
+
+/// Masks known secret values and sensitive header values
+#[derive(Clone)]
+pub struct Redactor {
+    /// Secret values, longest first so that overlapping secrets are fully masked
+    secrets: Vec<String>,
+    headers: Vec<String>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Redactor {
+            secrets: vec![],
+            headers: default_redacted_headers(),
+        }
+    }
+}
+
+impl Redactor {
+    /// Return a redactor for the secrets in a workspace
+    pub fn for_workspace(workspace: &Workspace) -> Self {
+        let mut redactor = Redactor::default();
+        redactor.add_workspace(workspace);
+        redactor
+    }
+
+    /// Add secrets stored in a workspace
+    pub fn add_workspace(&mut self, workspace: &Workspace) {
+        for authorization in workspace.authorizations.entities.values() {
+            let Ok(authorization) = serde_json::to_value(authorization) else {
+                continue;
+            };
+            let field = match authorization.get("type").and_then(|t| t.as_str()) {
+                Some("Basic") => "password",
+                Some("OAuth2Client") => "clientSecret",
+                Some("ApiKey") => "value",
+                _ => continue,
+            };
+            self.add_field(&authorization, field);
+        }
+
+        for certificate in workspace.certificates.entities.values() {
+            if let Ok(certificate) = serde_json::to_value(certificate) {
+                self.add_field(&certificate, "password");
+                self.add_field(&certificate, "key");
+            }
+        }
+
+        for section in SECRET_PARAMETER_SECTIONS {
+            for id in workspace
+                .scenarios
+                .child_ids
+                .get(section)
+                .into_iter()
+                .flatten()
+            {
+                let Some(variables) = workspace
+                    .scenarios
+                    .entities
+                    .get(id)
+                    .and_then(|s| serde_json::to_value(s).ok())
+                    .and_then(|mut s| s.get_mut("variables").map(|v| v.take()))
+                else {
+                    continue;
+                };
+                for variable in variables.as_array().into_iter().flatten() {
+                    self.add_field(variable, "value");
+                }
+            }
+        }
+    }
+
+    /// Add a value to mask
+    pub fn add_secret(&mut self, secret: &str) {
+        if secret.chars().count() < MIN_SECRET_LENGTH || self.secrets.iter().any(|s| s == secret) {
+            return;
+        }
+        let position = self
+            .secrets
+            .iter()
+            .position(|s| s.len() < secret.len())
+            .unwrap_or(self.secrets.len());
+        self.secrets.insert(position, secret.to_string());
+    }
+
+    fn add_field(&mut self, value: &Value, field: &str) {
+        if let Some(secret) = value.get(field).and_then(|s| s.as_str()) {
+            self.add_secret(secret);
+        }
+    }
+
+    /// Return text with secrets and sensitive header values masked
+    pub fn redact(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        for secret in &self.secrets {
+            if result.contains(secret.as_str()) {
+                result = result.replace(secret.as_str(), REDACTED);
+            }
+        }
+        redact_headers(&result, &self.headers)
+    }
+
+    /// Mask secrets in every string of a JSON value
+    pub fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.redact(text),
+            Value::Array(items) => items.iter_mut().for_each(|v| self.redact_value(v)),
+            Value::Object(properties) => properties.iter_mut().for_each(|(name, v)| {
+                // Header maps are keyed by name, so values are checked against header rules
+                match v {
+                    Value::String(text) if matches_any(name, &self.headers) => {
+                        *text = REDACTED.to_string()
+                    }
+                    _ => self.redact_value(v),
+                }
+            }),
+            _ => {}
+        }
+    }
+
+    /// Return a copy of a serializable value with secrets masked
+    pub fn redact_serialized<T: Serialize + DeserializeOwned>(
+        &self,
+        data: &T,
+    ) -> Result<T, ApicizeAppError> {
+        let mut value = serde_json::to_value(data)?;
+        self.redact_value(&mut value);
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Return a copy of a trace event with secrets masked in its data
+    pub fn redact_event(&self, event: &ReqwestEvent) -> ReqwestEvent {
+        let mut event = event.clone();
+        match &mut event {
+            ReqwestEvent::Read(e) => e.data = self.redact(&e.data),
+            ReqwestEvent::Write(e) => e.data = self.redact(&e.data),
+            ReqwestEvent::Connect(_) | ReqwestEvent::Clear => {}
+        }
+        event
+    }
+
+    /// Return a copy of a recorded exchange with secrets and sensitive header values masked
+    pub fn redact_exchange(&self, exchange: &RecordedExchange) -> RecordedExchange {
+        let redact_headers = |headers: &[RecordedHeader]| {
+            headers
+                .iter()
+                .map(|header| RecordedHeader {
+                    name: header.name.clone(),
+                    value: if matches_any(&header.name, &self.headers) {
+                        REDACTED.to_string()
+                    } else {
+                        self.redact(&header.value)
+                    },
+                })
+                .collect()
+        };
+
+        let mut exchange = exchange.clone();
+        exchange.url = self.redact(&exchange.url);
+        exchange.request_headers = redact_headers(&exchange.request_headers);
+        exchange.request_body = exchange.request_body.map(|body| self.redact(&body));
+        exchange.response_headers = redact_headers(&exchange.response_headers);
+        exchange.response_body = exchange.response_body.map(|body| self.redact(&body));
+        exchange.error = exchange.error.map(|error| self.redact(&error));
+        exchange
+    }
+}
+
+/// Replace values of header lines whose names match any of the patterns
+pub fn redact_headers(data: &str, patterns: &[String]) -> String {
+    if patterns.is_empty() {
+        return data.to_string();
+    }
+
+    let mut result = String::with_capacity(data.len());
+    for line in data.split_inclusive('\n') {
+        match line.split_once(':') {
+            Some((name, _)) if is_header_name(name) && matches_any(name, patterns) => {
+                result.push_str(name);
+                result.push_str(": ");
+                result.push_str(REDACTED);
+                if line.ends_with("\r\n") {
+                    result.push_str("\r\n");
+                } else if line.ends_with('\n') {
+                    result.push('\n');
+                }
+            }
+            _ => result.push_str(line),
+        }
+    }
+    result
+}
+
+/// True if text is a valid HTTP header field name, so that body content is left alone
+fn is_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+fn matches_any(name: &str, patterns: &[String]) -> bool {
+    let name = name.to_ascii_lowercase();
+    patterns
+        .iter()
+        .any(|pattern| matches_wildcard(&name, &pattern.to_ascii_lowercase()))
+}
+
+/// Match text against a pattern where "*" matches any sequence of characters
+fn matches_wildcard(text: &str, pattern: &str) -> bool {
+    let parts = pattern.split('*').collect::<Vec<&str>>();
+    if parts.len() == 1 {
+        return text == pattern;
+    }
+
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if text.len() < first.len() + last.len() || !text.starts_with(first) || !text.ends_with(last) {
+        return false;
+    }
+    let mut remaining = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match remaining.find(part) {
+            Some(i) => remaining = &remaining[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
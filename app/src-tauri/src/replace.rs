@@ -0,0 +1,290 @@
+//! Replace submodule
+//!
+//! This submodule rewrites URL hosts, header values and body text across requests
+
+use std::collections::HashSet;
+
+use apicize_lib::{Identifiable, NameValuePair, Request, RequestBody, RequestEntry, Workspace};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::ApicizeAppError, search::Matcher, workspaces::EntityType};
+
+/// Part of a request to apply replacements to
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReplaceTarget {
+    UrlHost,
+    HeaderValue,
+    BodyText,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceOptions {
+    /// Text to locate
+    pub find: String,
+    /// Text to substitute
+    pub replace: String,
+    /// Parts of requests to update
+    pub targets: Vec<ReplaceTarget>,
+    /// If true, match case (default is case-insensitive)
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Requests and groups to update (all requests if empty)
+    #[serde(default)]
+    pub request_ids: Vec<String>,
+}
+
+/// Description of a single change, used for previews
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacePreview {
+    /// Type of entity (or request section) being changed
+    pub entity_type: EntityType,
+    pub id: String,
+    /// Title of the request being changed
+    pub name: String,
+    pub target: ReplaceTarget,
+    pub before: String,
+    pub after: String,
+}
+
+/// Changes to make, along with updated requests
+pub struct ReplacePlan {
+    pub previews: Vec<ReplacePreview>,
+    pub requests: Vec<Request>,
+}
+
+/// Determine what requests would be updated by a replace operation
+pub fn plan(
+    workspace: &Workspace,
+    options: &ReplaceOptions,
+) -> Result<ReplacePlan, ApicizeAppError> {
+    if options.find.is_empty() {
+        return Err(ApicizeAppError::InvalidOperation(
+            "Text to replace is required".to_string(),
+        ));
+    }
+
+    let mut request_ids = Vec::<String>::new();
+    let mut visited = HashSet::<String>::new();
+    if options.request_ids.is_empty() {
+        collect_request_ids(
+            workspace,
+            &workspace.requests.top_level_ids,
+            &mut visited,
+            &mut request_ids,
+        );
+    } else {
+        for id in &options.request_ids {
+            if !workspace.requests.entities.contains_key(id) {
+                return Err(ApicizeAppError::InvalidRequest(id.clone()));
+            }
+            collect_request_ids(
+                workspace,
+                std::slice::from_ref(id),
+                &mut visited,
+                &mut request_ids,
+            );
+        }
+    }
+
+    let matcher = Matcher::new(&options.find, options.case_sensitive);
+    let mut plan = ReplacePlan {
+        previews: vec![],
+        requests: vec![],
+    };
+
+    for id in request_ids {
+        let Some(RequestEntry::Request(request)) = workspace.requests.entities.get(&id) else {
+            continue;
+        };
+        let name = request.get_title();
+        let mut updated = request.clone();
+        let mut changed = false;
+        let mut add =
+            |entity_type: EntityType, target: ReplaceTarget, before: &str, after: &str| {
+                plan.previews.push(ReplacePreview {
+                    entity_type,
+                    id: id.clone(),
+                    name: name.clone(),
+                    target,
+                    before: before.to_string(),
+                    after: after.to_string(),
+                })
+            };
+
+        if options.targets.contains(&ReplaceTarget::UrlHost) {
+            if let Some(url) = replace_host(&request.url, &matcher, &options.replace) {
+                add(
+                    EntityType::Request,
+                    ReplaceTarget::UrlHost,
+                    &request.url,
+                    &url,
+                );
+                updated.url = url;
+                changed = true;
+            }
+        }
+
+        if options.targets.contains(&ReplaceTarget::HeaderValue) {
+            if let Some(headers) = updated.headers.as_mut() {
+                for header in headers.iter_mut() {
+                    if let Some(value) = matcher.replace_all(&header.value, &options.replace) {
+                        add(
+                            EntityType::Headers,
+                            ReplaceTarget::HeaderValue,
+                            &format!("{}: {}", header.name, header.value),
+                            &format!("{}: {}", header.name, value),
+                        );
+                        header.value = value;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        if options.targets.contains(&ReplaceTarget::BodyText) {
+            if let Some(body) = &request.body {
+                if let Some((before, after, body)) = replace_body(body, &matcher, &options.replace)?
+                {
+                    add(EntityType::Body, ReplaceTarget::BodyText, &before, &after);
+                    updated.body = Some(body);
+                    changed = true;
+                }
+            }
+        }
+
+        if changed {
+            plan.requests.push(updated);
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Append request IDs (including those of group descendants) in navigation order
+fn collect_request_ids(
+    workspace: &Workspace,
+    ids: &[String],
+    visited: &mut HashSet<String>,
+    request_ids: &mut Vec<String>,
+) {
+    for id in ids {
+        if !visited.insert(id.clone()) {
+            continue;
+        }
+        match workspace.requests.entities.get(id) {
+            Some(RequestEntry::Request(_)) => request_ids.push(id.clone()),
+            Some(RequestEntry::Group(_)) => {
+                if let Some(child_ids) = workspace.requests.child_ids.get(id) {
+                    collect_request_ids(workspace, child_ids, visited, request_ids);
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+/// Replace text in the host (and port) portion of a URL, leaving scheme, path and query alone
+fn replace_host(url: &str, matcher: &Matcher, replacement: &str) -> Option<String> {
+    let host_start = url.find("://").map_or(0, |i| i + 3);
+    let host_end = url[host_start..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |i| host_start + i);
+    let host = matcher.replace_all(&url[host_start..host_end], replacement)?;
+    Some(format!(
+        "{}{}{}",
+        &url[..host_start],
+        host,
+        &url[host_end..]
+    ))
+}
+
+/// Replace text in body data, returning the before and after text along with the updated body
+fn replace_body(
+    body: &RequestBody,
+    matcher: &Matcher,
+    replacement: &str,
+) -> Result<Option<(String, String, RequestBody)>, ApicizeAppError> {
+    let mut value = serde_json::to_value(body)?;
+    let body_type = value
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let Some(data) = value.get_mut("data") else {
+        return Ok(None);
+    };
+
+    let (before, after) = match (body_type.as_str(), &mut *data) {
+        ("Text" | "XML", Value::String(text)) => match matcher.replace_all(text, replacement) {
+            Some(updated) => (std::mem::replace(text, updated.clone()), updated),
+            None => return Ok(None),
+        },
+        ("JSON", _) => {
+            let before = data.to_string();
+            if !replace_strings(data, matcher, replacement) {
+                return Ok(None);
+            }
+            let after = data.to_string();
+
+            // Formatted JSON text is regenerated so that it matches the updated data
+            let formatted = serde_json::to_string_pretty(data)?;
+            if let Some(Value::String(text)) = value.get_mut("formatted") {
+                *text = formatted;
+            }
+            (before, after)
+        }
+        // Form bodies only have their values updated
+        ("Form", Value::Array(_)) => {
+            let mut pairs = serde_json::from_value::<Vec<NameValuePair>>(data.clone())?;
+            let before = format_pairs(&pairs);
+            let mut found = false;
+            for pair in pairs.iter_mut() {
+                if let Some(updated) = matcher.replace_all(&pair.value, replacement) {
+                    pair.value = updated;
+                    found = true;
+                }
+            }
+            if !found {
+                return Ok(None);
+            }
+            *data = serde_json::to_value(&pairs)?;
+            (before, format_pairs(&pairs))
+        }
+        // Raw bodies are not updated
+        _ => return Ok(None),
+    };
+
+    Ok(Some((before, after, serde_json::from_value(value)?)))
+}
+
+fn format_pairs(pairs: &[NameValuePair]) -> String {
+    pairs
+        .iter()
+        .map(|p| format!("{}={}", p.name, p.value))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Replace text in all string values of a JSON value, returning true if anything changed
+fn replace_strings(value: &mut Value, matcher: &Matcher, replacement: &str) -> bool {
+    match value {
+        Value::String(text) => match matcher.replace_all(text, replacement) {
+            Some(updated) => {
+                *text = updated;
+                true
+            }
+            None => false,
+        },
+        Value::Array(items) => items.iter_mut().fold(false, |found, v| {
+            replace_strings(v, matcher, replacement) || found
+        }),
+        Value::Object(properties) => properties.values_mut().fold(false, |found, v| {
+            replace_strings(v, matcher, replacement) || found
+        }),
+        _ => false,
+    }
+}
@@ -0,0 +1,421 @@
+//! Execution result comparison submodule
+//!
+//! This submodule compares two execution results for a request and reports
+//! differences in status, headers, body and test outcomes
+
+use apicize_lib::ExecutionResultDetail;
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::ApicizeAppError;
+
+/// Bodies with more lines than this are compared as a whole rather than line by line
+const MAX_LINE_DIFF: usize = 2000;
+
+fn default_ignore_headers() -> Vec<String> {
+    vec!["date".to_string()]
+}
+
+/// Identifies an execution result to compare
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultReference {
+    /// Request or group the result belongs to
+    pub request_or_group_id: String,
+    /// Index of result (i.e. for multi-run executions)
+    pub index: usize,
+    /// Stored run to retrieve the result from, if not the current result
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+}
+
+/// Options controlling which values are compared
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultDiffOptions {
+    /// JSON body fields to skip, either by property name (i.e. "timestamp")
+    /// or by JSON pointer (i.e. "/data/0/id")
+    #[serde(default)]
+    pub ignore_fields: Vec<String>,
+    /// Response headers to skip (case-insensitive)
+    #[serde(default = "default_ignore_headers")]
+    pub ignore_headers: Vec<String>,
+}
+
+impl Default for ResultDiffOptions {
+    fn default() -> Self {
+        ResultDiffOptions {
+            ignore_fields: vec![],
+            ignore_headers: default_ignore_headers(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A single difference between the left and right results
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffEntry {
+    /// Location of the difference (header name, JSON pointer, line number or test name)
+    pub path: String,
+    pub kind: DiffKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right: Option<Value>,
+}
+
+/// Structured differences between two execution results
+#[derive(Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultDiff {
+    /// True if no differences were found
+    pub identical: bool,
+    /// Differences in success and pass/fail counts
+    pub summary: Vec<DiffEntry>,
+    /// Differences in response status
+    pub status: Vec<DiffEntry>,
+    /// Differences in response headers
+    pub headers: Vec<DiffEntry>,
+    /// Differences in response body
+    pub body: Vec<DiffEntry>,
+    /// Differences in test outcomes
+    pub tests: Vec<DiffEntry>,
+}
+
+const SUMMARY_FIELDS: [&str; 6] = [
+    "success",
+    "requestSuccessCount",
+    "requestFailureCount",
+    "requestErrorCount",
+    "testPassCount",
+    "testFailCount",
+];
+
+impl ResultDiff {
+    /// Compare two execution result details
+    pub fn compare(
+        left: &ExecutionResultDetail,
+        right: &ExecutionResultDetail,
+        options: &ResultDiffOptions,
+    ) -> Result<ResultDiff, ApicizeAppError> {
+        let left = serde_json::to_value(left)?;
+        let right = serde_json::to_value(right)?;
+
+        let mut diff = ResultDiff::default();
+
+        for field in SUMMARY_FIELDS {
+            push_if_changed(&mut diff.summary, field, left.get(field), right.get(field));
+        }
+
+        let left_response = left.pointer("/testContext/response");
+        let right_response = right.pointer("/testContext/response");
+
+        for field in ["status", "statusText"] {
+            push_if_changed(
+                &mut diff.status,
+                field,
+                left_response.and_then(|r| r.get(field)),
+                right_response.and_then(|r| r.get(field)),
+            );
+        }
+
+        diff.headers = compare_headers(
+            left_response.and_then(|r| r.get("headers")),
+            right_response.and_then(|r| r.get("headers")),
+            &options.ignore_headers,
+        );
+
+        diff.body = compare_bodies(
+            left_response.and_then(|r| r.get("body")),
+            right_response.and_then(|r| r.get("body")),
+            &options.ignore_fields,
+        );
+
+        diff.tests = compare_tests(left.get("tests"), right.get("tests"));
+
+        diff.identical = diff.summary.is_empty()
+            && diff.status.is_empty()
+            && diff.headers.is_empty()
+            && diff.body.is_empty()
+            && diff.tests.is_empty();
+
+        Ok(diff)
+    }
+}
+
+fn push_if_changed(
+    results: &mut Vec<DiffEntry>,
+    path: &str,
+    left: Option<&Value>,
+    right: Option<&Value>,
+) {
+    let left = left.filter(|v| !v.is_null());
+    let right = right.filter(|v| !v.is_null());
+    let kind = match (left, right) {
+        (Some(l), Some(r)) if l == r => return,
+        (Some(_), Some(_)) => DiffKind::Changed,
+        (Some(_), None) => DiffKind::Removed,
+        (None, Some(_)) => DiffKind::Added,
+        (None, None) => return,
+    };
+    results.push(DiffEntry {
+        path: path.to_string(),
+        kind,
+        left: left.cloned(),
+        right: right.cloned(),
+    });
+}
+
+fn compare_headers(
+    left: Option<&Value>,
+    right: Option<&Value>,
+    ignore_headers: &[String],
+) -> Vec<DiffEntry> {
+    let ignore = ignore_headers
+        .iter()
+        .map(|h| h.to_ascii_lowercase())
+        .collect::<FxHashSet<String>>();
+
+    // Header names are case-insensitive, so compare on lower case names
+    let normalize = |headers: Option<&Value>| -> Map<String, Value> {
+        headers
+            .and_then(|h| h.as_object())
+            .map(|h| {
+                h.iter()
+                    .map(|(name, value)| (name.to_ascii_lowercase(), value.clone()))
+                    .filter(|(name, _)| !ignore.contains(name))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let left = normalize(left);
+    let right = normalize(right);
+
+    let mut names = left.keys().chain(right.keys()).collect::<Vec<&String>>();
+    names.sort();
+    names.dedup();
+
+    let mut results = vec![];
+    for name in names {
+        push_if_changed(&mut results, name, left.get(name), right.get(name));
+    }
+    results
+}
+
+fn compare_bodies(
+    left: Option<&Value>,
+    right: Option<&Value>,
+    ignore_fields: &[String],
+) -> Vec<DiffEntry> {
+    let mut results = vec![];
+
+    let body_type = |body: Option<&Value>| {
+        body.and_then(|b| b.get("type"))
+            .and_then(|t| t.as_str())
+            .map(|t| t.to_string())
+    };
+    let left_type = body_type(left);
+    let right_type = body_type(right);
+
+    if left_type.as_deref() == Some("JSON") && right_type.as_deref() == Some("JSON") {
+        if let (Some(l), Some(r)) = (
+            left.and_then(|b| b.get("data")),
+            right.and_then(|b| b.get("data")),
+        ) {
+            compare_json("", l, r, ignore_fields, &mut results);
+            return results;
+        }
+    }
+
+    push_if_changed(
+        &mut results,
+        "type",
+        left_type.map(Value::String).as_ref(),
+        right_type.map(Value::String).as_ref(),
+    );
+
+    let body_text = |body: Option<&Value>| {
+        body.and_then(|b| b.get("text").or_else(|| b.get("data")))
+            .map(|t| match t {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .unwrap_or_default()
+    };
+    compare_lines(&body_text(left), &body_text(right), &mut results);
+    results
+}
+
+fn is_ignored(path: &str, key: &str, ignore_fields: &[String]) -> bool {
+    ignore_fields.iter().any(|f| {
+        if f.starts_with('/') {
+            f == path
+        } else {
+            f == key
+        }
+    })
+}
+
+/// Recursively compare JSON values, recording differences by JSON pointer
+fn compare_json(
+    path: &str,
+    left: &Value,
+    right: &Value,
+    ignore_fields: &[String],
+    results: &mut Vec<DiffEntry>,
+) {
+    match (left, right) {
+        (Value::Object(l), Value::Object(r)) => {
+            let mut keys = l.keys().chain(r.keys()).collect::<Vec<&String>>();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child_path = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+                if is_ignored(&child_path, key, ignore_fields) {
+                    continue;
+                }
+                match (l.get(key), r.get(key)) {
+                    (Some(lv), Some(rv)) => {
+                        compare_json(&child_path, lv, rv, ignore_fields, results)
+                    }
+                    (lv, rv) => push_if_changed(results, &child_path, lv, rv),
+                }
+            }
+        }
+        (Value::Array(l), Value::Array(r)) => {
+            for index in 0..l.len().max(r.len()) {
+                let child_path = format!("{path}/{index}");
+                if is_ignored(&child_path, "", ignore_fields) {
+                    continue;
+                }
+                match (l.get(index), r.get(index)) {
+                    (Some(lv), Some(rv)) => {
+                        compare_json(&child_path, lv, rv, ignore_fields, results)
+                    }
+                    (lv, rv) => push_if_changed(results, &child_path, lv, rv),
+                }
+            }
+        }
+        _ => {
+            if left != right {
+                results.push(DiffEntry {
+                    path: if path.is_empty() {
+                        "/".to_string()
+                    } else {
+                        path.to_string()
+                    },
+                    kind: DiffKind::Changed,
+                    left: Some(left.clone()),
+                    right: Some(right.clone()),
+                });
+            }
+        }
+    }
+}
+
+/// Compare text line by line using longest common subsequence
+fn compare_lines(left: &str, right: &str, results: &mut Vec<DiffEntry>) {
+    if left == right {
+        return;
+    }
+
+    let l = left.lines().collect::<Vec<&str>>();
+    let r = right.lines().collect::<Vec<&str>>();
+
+    if l.len() > MAX_LINE_DIFF || r.len() > MAX_LINE_DIFF {
+        results.push(DiffEntry {
+            path: "text".to_string(),
+            kind: DiffKind::Changed,
+            left: Some(Value::String(left.to_string())),
+            right: Some(Value::String(right.to_string())),
+        });
+        return;
+    }
+
+    let mut lcs = vec![vec![0usize; r.len() + 1]; l.len() + 1];
+    for i in (0..l.len()).rev() {
+        for j in (0..r.len()).rev() {
+            lcs[i][j] = if l[i] == r[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < l.len() || j < r.len() {
+        if i < l.len() && j < r.len() && l[i] == r[j] {
+            i += 1;
+            j += 1;
+        } else if j < r.len() && (i == l.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            results.push(DiffEntry {
+                path: format!("line {}", j + 1),
+                kind: DiffKind::Added,
+                left: None,
+                right: Some(Value::String(r[j].to_string())),
+            });
+            j += 1;
+        } else {
+            results.push(DiffEntry {
+                path: format!("line {}", i + 1),
+                kind: DiffKind::Removed,
+                left: Some(Value::String(l[i].to_string())),
+                right: None,
+            });
+            i += 1;
+        }
+    }
+}
+
+/// Flatten nested test scenarios into "Scenario > Behavior" names with their outcome
+fn flatten_tests(tests: Option<&Value>, prefix: &str, results: &mut Map<String, Value>) {
+    if let Some(Value::Array(items)) = tests {
+        for item in items {
+            let name = item
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or_default();
+            let full_name = if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{prefix} > {name}")
+            };
+            if item.get("children").is_some() {
+                flatten_tests(item.get("children"), &full_name, results);
+            } else {
+                results.insert(
+                    full_name,
+                    item.get("success").cloned().unwrap_or(Value::Null),
+                );
+            }
+        }
+    }
+}
+
+fn compare_tests(left: Option<&Value>, right: Option<&Value>) -> Vec<DiffEntry> {
+    let mut l = Map::new();
+    let mut r = Map::new();
+    flatten_tests(left, "", &mut l);
+    flatten_tests(right, "", &mut r);
+
+    let mut names = l.keys().chain(r.keys()).collect::<Vec<&String>>();
+    names.sort();
+    names.dedup();
+
+    let mut results = vec![];
+    for name in names {
+        push_if_changed(&mut results, name, l.get(name), r.get(name));
+    }
+    results
+}
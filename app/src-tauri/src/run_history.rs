@@ -0,0 +1,277 @@
+//! Execution history submodule
+//!
+//! This submodule persists the results of each request execution so that
+//! past runs can be listed, reloaded and pruned after the workspace is closed
+
+use std::{
+    fs::{self, OpenOptions},
+    hash::Hasher,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use apicize_lib::{
+    open_data_file, save_data_file, ExecutionResultDetail, ExecutionResultSuccess,
+    ExecutionResultSummary,
+};
+use chrono::Utc;
+use rustc_hash::{FxHashMap, FxHasher};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ApicizeAppError, redact::Redactor, settings::ApicizeSettings};
+
+/// Serializes access to history index files
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+const INDEX_FILE_NAME: &str = "index.jsonl";
+
+/// Index entry describing a stored execution
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunHistoryEntry {
+    /// Unique identifier of the stored run
+    pub run_id: String,
+    /// Workbook the request belongs to
+    pub workbook_file_name: String,
+    /// Request or group that was executed
+    pub request_or_group_id: String,
+    /// Name of request or group at time of execution
+    pub name: String,
+    /// Execution time (milliseconds since Unix epoch)
+    pub timestamp: i64,
+    /// Overall success of the run, if available
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<ExecutionResultSuccess>,
+}
+
+/// Stored execution, including all summaries and details
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunHistoryRecord {
+    pub entry: RunHistoryEntry,
+    pub summaries: Vec<ExecutionResultSummary>,
+    pub details: Vec<ExecutionResultDetail>,
+}
+
+/// Criteria for removing stored runs
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunHistoryPruneOptions {
+    /// Remove runs older than this many days
+    pub older_than_days: Option<u32>,
+    /// Keep at most this many runs per request or group
+    pub keep_per_request: Option<usize>,
+}
+
+/// Execution history for a single workbook
+pub struct RunHistory {
+    directory: PathBuf,
+    workbook_file_name: String,
+}
+
+impl RunHistory {
+    /// Return the root directory for execution history
+    pub fn get_history_directory() -> PathBuf {
+        ApicizeSettings::get_settings_directory().join("history")
+    }
+
+    /// Return history for the specified workbook file name
+    pub fn for_workbook(workbook_file_name: &str) -> Result<RunHistory, ApicizeAppError> {
+        if workbook_file_name.is_empty() {
+            return Err(ApicizeAppError::FileNameRequired());
+        }
+
+        let path = std::path::absolute(workbook_file_name)?;
+        let stem = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        // Use a stable hash of the full path so identically named workbooks in
+        // different directories do not share history
+        let mut hasher = FxHasher::default();
+        hasher.write(path.to_string_lossy().as_bytes());
+
+        Ok(RunHistory {
+            directory: Self::get_history_directory().join(format!(
+                "{}-{:016x}",
+                stem,
+                hasher.finish()
+            )),
+            workbook_file_name: path.to_string_lossy().to_string(),
+        })
+    }
+
+    fn index_file_name(&self) -> PathBuf {
+        self.directory.join(INDEX_FILE_NAME)
+    }
+
+    /// Return the file name for a run; run IDs are UUIDs, so anything other than letters,
+    /// digits and dashes is rejected to keep the file within the history directory
+    fn run_file_name(&self, run_id: &str) -> Result<PathBuf, ApicizeAppError> {
+        if run_id.is_empty()
+            || !run_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(ApicizeAppError::InvalidRunHistory(run_id.to_string()));
+        }
+        Ok(self.directory.join(format!("{run_id}.json")))
+    }
+
+    /// Store execution results under the specified run ID with secrets masked, returning
+    /// the new index entry
+    pub fn store(
+        &self,
+        run_id: &str,
+        request_or_group_id: &str,
+        summaries: &[ExecutionResultSummary],
+        details: &[ExecutionResultDetail],
+        redactor: &Redactor,
+    ) -> Result<RunHistoryEntry, ApicizeAppError> {
+        let entry = RunHistoryEntry {
+            run_id: run_id.to_string(),
+            workbook_file_name: self.workbook_file_name.clone(),
+            request_or_group_id: request_or_group_id.to_string(),
+            name: summaries
+                .first()
+                .map(|s| s.name.clone())
+                .unwrap_or_default(),
+            timestamp: Utc::now().timestamp_millis(),
+            success: summaries.first().map(|s| s.success.clone()),
+        };
+
+        let _lock = HISTORY_LOCK.lock().unwrap();
+        fs::create_dir_all(&self.directory)?;
+
+        save_data_file(
+            &self.run_file_name(&entry.run_id)?,
+            &RunHistoryRecord {
+                entry: entry.clone(),
+                summaries: redactor.redact_serialized(&summaries.to_vec())?,
+                details: redactor.redact_serialized(&details.to_vec())?,
+            },
+        )?;
+
+        let mut index = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.index_file_name())?;
+        writeln!(index, "{}", serde_json::to_string(&entry)?)?;
+
+        Ok(entry)
+    }
+
+    /// List stored runs, newest first, optionally filtering on request or group ID
+    pub fn list(
+        &self,
+        request_or_group_id: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<RunHistoryEntry>, ApicizeAppError> {
+        let _lock = HISTORY_LOCK.lock().unwrap();
+        let mut entries = Self::read_index(&self.index_file_name())?
+            .into_iter()
+            .filter(|e| request_or_group_id.is_none_or(|id| e.request_or_group_id == id))
+            .collect::<Vec<RunHistoryEntry>>();
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        if let Some(max) = limit {
+            entries.truncate(max);
+        }
+        Ok(entries)
+    }
+
+    /// Load a stored run
+    pub fn load(&self, run_id: &str) -> Result<RunHistoryRecord, ApicizeAppError> {
+        let file_name = self.run_file_name(run_id)?;
+        let _lock = HISTORY_LOCK.lock().unwrap();
+        if !file_name.is_file() {
+            return Err(ApicizeAppError::InvalidRunHistory(run_id.to_string()));
+        }
+        Ok(open_data_file::<RunHistoryRecord>(&file_name)?.data)
+    }
+
+    /// Load a single execution detail from a stored run
+    pub fn load_detail(
+        &self,
+        run_id: &str,
+        index: usize,
+    ) -> Result<ExecutionResultDetail, ApicizeAppError> {
+        let mut record = self.load(run_id)?;
+        if index < record.details.len() {
+            Ok(record.details.swap_remove(index))
+        } else {
+            Err(ApicizeAppError::InvalidResult(run_id.to_string(), index))
+        }
+    }
+
+    /// Remove stored runs matching the prune criteria, returning the number removed
+    pub fn prune(&self, options: &RunHistoryPruneOptions) -> Result<usize, ApicizeAppError> {
+        let _lock = HISTORY_LOCK.lock().unwrap();
+        let index_file_name = self.index_file_name();
+        let mut entries = Self::read_index(&index_file_name)?;
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+        let cutoff = options
+            .older_than_days
+            .map(|days| Utc::now().timestamp_millis() - i64::from(days) * 86_400_000);
+
+        let mut kept_per_request = FxHashMap::<String, usize>::default();
+        let mut keep = Vec::with_capacity(entries.len());
+        let mut removed = 0;
+
+        for entry in entries {
+            let too_old = cutoff.is_some_and(|c| entry.timestamp < c);
+            let kept = kept_per_request
+                .entry(entry.request_or_group_id.clone())
+                .or_default();
+            let too_many = options.keep_per_request.is_some_and(|max| *kept >= max);
+
+            if too_old || too_many {
+                if let Ok(run_file_name) = self.run_file_name(&entry.run_id) {
+                    if run_file_name.is_file() {
+                        fs::remove_file(run_file_name)?;
+                    }
+                }
+                removed += 1;
+            } else {
+                *kept += 1;
+                keep.push(entry);
+            }
+        }
+
+        if removed > 0 {
+            // Rewrite index oldest first so that appends stay in chronological order
+            keep.reverse();
+            let mut contents = String::new();
+            for entry in &keep {
+                contents.push_str(&serde_json::to_string(entry)?);
+                contents.push('\n');
+            }
+            fs::write(&index_file_name, contents)?;
+        }
+
+        Ok(removed)
+    }
+
+    fn read_index(index_file_name: &Path) -> Result<Vec<RunHistoryEntry>, ApicizeAppError> {
+        if !index_file_name.is_file() {
+            return Ok(vec![]);
+        }
+
+        let reader = BufReader::new(fs::File::open(index_file_name)?);
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Skip entries that cannot be parsed (i.e. a partial write) rather than failing
+            if let Ok(entry) = serde_json::from_str::<RunHistoryEntry>(&line) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
@@ -0,0 +1,315 @@
+//! Search submodule
+//!
+//! This submodule locates text in workspace requests, groups, scenarios and authorizations
+
+use std::borrow::Cow;
+
+use apicize_lib::{Identifiable, IndexedEntities, NameValuePair, RequestEntry, Workspace};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{import::get_text, redact::Redactor, workspaces::EntityType};
+
+/// Number of characters to include on either side of a match in snippets
+const SNIPPET_CONTEXT: usize = 30;
+
+/// Parameter sections, in navigation order
+const PARAMETER_SECTIONS: [&str; 3] = ["W", "P", "V"];
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchOptions {
+    /// Text to locate
+    pub text: String,
+    /// If true, match case (default is case-insensitive)
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Maximum number of results to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Part of an entity where text was found
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchField {
+    Name,
+    Url,
+    QueryString,
+    Header,
+    Body,
+    Test,
+    Variable,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    /// Type of entity (or request section) containing the match
+    pub entity_type: EntityType,
+    pub id: String,
+    /// Title of the entity containing the match
+    pub name: String,
+    pub field: SearchField,
+    /// Matched text with surrounding context
+    pub snippet: String,
+}
+
+/// Search the workspace, returning results in navigation order; if a redactor is specified,
+/// text is masked before matching so that secrets are neither returned nor probed
+pub fn search(
+    workspace: &Workspace,
+    options: &SearchOptions,
+    redactor: Option<Redactor>,
+) -> Vec<SearchResult> {
+    let mut results: Vec<SearchResult> = vec![];
+    if options.text.is_empty() {
+        return results;
+    }
+
+    let mut matcher = Matcher::new(&options.text, options.case_sensitive);
+    matcher.redactor = redactor;
+    search_requests(
+        &workspace.requests,
+        &workspace.requests.top_level_ids,
+        &matcher,
+        &mut results,
+    );
+
+    for id in ordered_ids(&workspace.scenarios) {
+        let Some(scenario) = workspace.scenarios.entities.get(id) else {
+            continue;
+        };
+        let name = scenario.get_title();
+        let mut add = |field: SearchField, snippet: String| {
+            results.push(SearchResult {
+                entity_type: EntityType::Scenario,
+                id: id.clone(),
+                name: name.clone(),
+                field,
+                snippet,
+            })
+        };
+        if let Some(snippet) = matcher.find(&scenario.name) {
+            add(SearchField::Name, snippet);
+        }
+        if let Some(variables) = serde_json::to_value(scenario)
+            .ok()
+            .as_ref()
+            .and_then(|s| s.get("variables"))
+            .and_then(|v| v.as_array())
+        {
+            for variable in variables {
+                let text = format!(
+                    "{} = {}",
+                    get_text(variable.get("name")),
+                    get_text(variable.get("value"))
+                );
+                if let Some(snippet) = matcher.find(&text) {
+                    add(SearchField::Variable, snippet);
+                }
+            }
+        }
+    }
+
+    for id in ordered_ids(&workspace.authorizations) {
+        let Some(authorization) = workspace.authorizations.entities.get(id) else {
+            continue;
+        };
+        if let Some(snippet) = matcher.find(authorization.get_name()) {
+            results.push(SearchResult {
+                entity_type: EntityType::Authorization,
+                id: id.clone(),
+                name: authorization.get_title(),
+                field: SearchField::Name,
+                snippet,
+            });
+        }
+    }
+
+    if let Some(limit) = options.limit {
+        results.truncate(limit);
+    }
+    results
+}
+
+fn search_requests(
+    requests: &IndexedEntities<RequestEntry>,
+    ids: &[String],
+    matcher: &Matcher,
+    results: &mut Vec<SearchResult>,
+) {
+    for id in ids {
+        let Some(entry) = requests.entities.get(id) else {
+            continue;
+        };
+        let name = entry.get_title();
+        let mut add = |entity_type: EntityType, field: SearchField, snippet: String| {
+            results.push(SearchResult {
+                entity_type,
+                id: id.clone(),
+                name: name.clone(),
+                field,
+                snippet,
+            })
+        };
+
+        match entry {
+            RequestEntry::Request(request) => {
+                if let Some(snippet) = matcher.find(&request.name) {
+                    add(EntityType::Request, SearchField::Name, snippet);
+                }
+                if let Some(snippet) = matcher.find(&request.url) {
+                    add(EntityType::Request, SearchField::Url, snippet);
+                }
+                for snippet in matcher.find_in_pairs(&request.query_string_params) {
+                    add(EntityType::Request, SearchField::QueryString, snippet);
+                }
+                for snippet in matcher.find_in_pairs(&request.headers) {
+                    add(EntityType::Headers, SearchField::Header, snippet);
+                }
+                if let Some(snippet) = request
+                    .body
+                    .as_ref()
+                    .and_then(|b| serde_json::to_value(b).ok())
+                    .and_then(|b| matcher.find(&get_body_text(b.get("data"))))
+                {
+                    add(EntityType::Body, SearchField::Body, snippet);
+                }
+                if let Some(snippet) = request.test.as_ref().and_then(|t| matcher.find(t)) {
+                    add(EntityType::Request, SearchField::Test, snippet);
+                }
+            }
+            RequestEntry::Group(group) => {
+                if let Some(snippet) = matcher.find(&group.name) {
+                    add(EntityType::Group, SearchField::Name, snippet);
+                }
+            }
+        }
+
+        if let Some(child_ids) = requests.child_ids.get(id) {
+            search_requests(requests, child_ids, matcher, results);
+        }
+    }
+}
+
+/// Return parameter IDs in navigation order
+fn ordered_ids<T>(entities: &IndexedEntities<T>) -> Vec<&String> {
+    PARAMETER_SECTIONS
+        .iter()
+        .filter_map(|section| entities.child_ids.get(*section))
+        .flatten()
+        .collect()
+}
+
+/// Return body data as searchable text
+fn get_body_text(data: Option<&Value>) -> String {
+    match data {
+        Some(Value::Array(pairs)) if pairs.iter().all(|p| p.get("name").is_some()) => pairs
+            .iter()
+            .map(|p| format!("{}={}", get_text(p.get("name")), get_text(p.get("value"))))
+            .collect::<Vec<String>>()
+            .join("\n"),
+        // Raw bodies are serialized as byte arrays
+        Some(Value::Array(bytes)) if bytes.iter().all(|b| b.is_u64()) => String::from_utf8_lossy(
+            &bytes
+                .iter()
+                .filter_map(|b| b.as_u64().map(|b| b as u8))
+                .collect::<Vec<u8>>(),
+        )
+        .to_string(),
+        other => get_text(other),
+    }
+}
+
+pub(crate) struct Matcher {
+    needle: Vec<char>,
+    case_sensitive: bool,
+    /// Masks secrets in searched text
+    redactor: Option<Redactor>,
+}
+
+impl Matcher {
+    pub(crate) fn new(text: &str, case_sensitive: bool) -> Self {
+        Matcher {
+            needle: Self::normalize(text, case_sensitive),
+            case_sensitive,
+            redactor: None,
+        }
+    }
+
+    /// Characters are lower cased individually so that match positions line up with the source text
+    fn normalize(text: &str, case_sensitive: bool) -> Vec<char> {
+        if case_sensitive {
+            text.chars().collect()
+        } else {
+            text.chars()
+                .map(|c| c.to_lowercase().next().unwrap_or(c))
+                .collect()
+        }
+    }
+
+    /// Return a snippet around the first match, if any
+    fn find(&self, text: &str) -> Option<String> {
+        let text = match &self.redactor {
+            Some(redactor) => Cow::Owned(redactor.redact(text)),
+            None => Cow::Borrowed(text),
+        };
+        let haystack = Self::normalize(&text, self.case_sensitive);
+        let position = haystack
+            .windows(self.needle.len())
+            .position(|w| w == self.needle.as_slice())?;
+
+        let chars = text.chars().collect::<Vec<char>>();
+        let start = position.saturating_sub(SNIPPET_CONTEXT);
+        let end = (position + self.needle.len() + SNIPPET_CONTEXT).min(chars.len());
+        let mut snippet = String::default();
+        if start > 0 {
+            snippet.push('…');
+        }
+        snippet.extend(
+            chars[start..end]
+                .iter()
+                .map(|c| if c.is_whitespace() { ' ' } else { *c }),
+        );
+        if end < chars.len() {
+            snippet.push('…');
+        }
+        Some(snippet)
+    }
+
+    /// Return text with every match substituted, or None if there are no matches
+    pub(crate) fn replace_all(&self, text: &str, replacement: &str) -> Option<String> {
+        if self.needle.is_empty() {
+            return None;
+        }
+        let haystack = Self::normalize(text, self.case_sensitive);
+        let chars = text.chars().collect::<Vec<char>>();
+        let mut result = String::with_capacity(text.len());
+        let mut found = false;
+        let mut i = 0;
+        while i < chars.len() {
+            if haystack[i..].starts_with(&self.needle) {
+                result.push_str(replacement);
+                i += self.needle.len();
+                found = true;
+            } else {
+                result.push(chars[i]);
+                i += 1;
+            }
+        }
+        if found {
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    fn find_in_pairs(&self, pairs: &Option<Vec<NameValuePair>>) -> Vec<String> {
+        pairs
+            .iter()
+            .flatten()
+            .filter_map(|p| self.find(&format!("{}: {}", p.name, p.value)))
+            .collect()
+    }
+}
@@ -0,0 +1,442 @@
+//! Secrets submodule
+//!
+//! This submodule keeps vault parameters and cached OAuth2 tokens in a secret store, so that
+//! they are not written to disk as plaintext JSON.  Secrets are kept in an encrypted file in
+//! the settings directory, whose key is either held in the operating system keyring or
+//! derived from a passphrase
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use apicize_lib::{CachedTokenInfo, Identifiable, IndexedEntities, Workspace};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::ApicizeAppError, settings::ApicizeSettings};
+
+/// Keyring service name secrets are stored under
+const KEYRING_SERVICE: &str = "apicize";
+
+/// Keyring entry holding the secret file key
+const KEYRING_KEY_ENTRY: &str = "secret-store-key";
+
+/// Parameter section for vault (global) parameters
+const VAULT_SECTION: &str = "V";
+
+/// Secret store entry holding vault parameters
+const VAULT_ENTRY: &str = "vault";
+
+/// Secret store entry holding cached OAuth2 tokens
+const TOKENS_ENTRY: &str = "oauth2-tokens";
+
+/// Where vault parameters and cached OAuth2 tokens are stored
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SecretStorage {
+    /// Vault parameters are saved to the global parameters file, tokens are not persisted
+    #[default]
+    File,
+    /// Secrets are encrypted with a key held in the operating system keyring
+    Keyring,
+    /// Secrets are encrypted with a key derived from a passphrase
+    EncryptedFile,
+}
+
+/// Secret storage configuration and whether secrets are available
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretStoreStatus {
+    pub storage: SecretStorage,
+    /// False if the store requires a passphrase that has not been entered
+    pub unlocked: bool,
+}
+
+/// Storage for named secret values
+pub trait SecretStore: Send + Sync {
+    fn get(&self, name: &str) -> Result<Option<String>, ApicizeAppError>;
+    fn set(&self, name: &str, value: &str) -> Result<(), ApicizeAppError>;
+    fn delete(&self, name: &str) -> Result<(), ApicizeAppError>;
+}
+
+/// Encrypted file contents
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptedFile {
+    version: u32,
+    /// Salt used to derive the key from a passphrase, if applicable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    nonce: String,
+    data: String,
+}
+
+/// Derive a 256 bit key from a passphrase
+pub(crate) fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], ApicizeAppError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| ApicizeAppError::SecretStoreError(err.to_string()))?;
+    Ok(key)
+}
+
+/// Return a new random salt for key derivation
+pub(crate) fn generate_salt() -> [u8; 16] {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+/// Encrypt data, returning file contents that include the nonce and optional salt
+pub(crate) fn encrypt(
+    key: &[u8; 32],
+    salt: Option<&[u8]>,
+    data: &[u8],
+) -> Result<String, ApicizeAppError> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let encrypted = cipher
+        .encrypt(&nonce, data)
+        .map_err(|err| ApicizeAppError::SecretStoreError(err.to_string()))?;
+    Ok(serde_json::to_string_pretty(&EncryptedFile {
+        version: 1,
+        salt: salt.map(|s| STANDARD.encode(s)),
+        nonce: STANDARD.encode(nonce),
+        data: STANDARD.encode(encrypted),
+    })?)
+}
+
+/// Return the salt stored with encrypted file contents, if any
+pub(crate) fn get_salt(contents: &str) -> Result<Option<Vec<u8>>, ApicizeAppError> {
+    let file = serde_json::from_str::<EncryptedFile>(contents)?;
+    match file.salt {
+        Some(salt) => Ok(Some(decode(&salt)?)),
+        None => Ok(None),
+    }
+}
+
+/// Decrypt file contents created by `encrypt`
+pub(crate) fn decrypt(key: &[u8; 32], contents: &str) -> Result<Vec<u8>, ApicizeAppError> {
+    let file = serde_json::from_str::<EncryptedFile>(contents)?;
+    let nonce = decode(&file.nonce)?;
+    if nonce.len() != 12 {
+        return Err(ApicizeAppError::SecretStoreError(
+            "invalid nonce".to_string(),
+        ));
+    }
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), decode(&file.data)?.as_ref())
+        .map_err(|_| {
+            ApicizeAppError::SecretStoreError(
+                "unable to decrypt, the passphrase may be incorrect".to_string(),
+            )
+        })
+}
+
+fn decode(text: &str) -> Result<Vec<u8>, ApicizeAppError> {
+    STANDARD
+        .decode(text)
+        .map_err(|err| ApicizeAppError::SecretStoreError(err.to_string()))
+}
+
+/// Secrets held in memory and written to an encrypted file on each change
+pub struct EncryptedFileSecretStore {
+    file_name: PathBuf,
+    key: [u8; 32],
+    salt: Option<Vec<u8>>,
+    values: Mutex<BTreeMap<String, String>>,
+}
+
+impl EncryptedFileSecretStore {
+    /// Return the name of the file used by the passphrase secret store
+    pub fn get_passphrase_filename() -> PathBuf {
+        ApicizeSettings::get_settings_directory().join("secrets.apicize-vault")
+    }
+
+    /// Open the file secret store, using a key derived from the passphrase; if the store
+    /// does not exist yet, the passphrase must be confirmed before it is created
+    pub fn open_with_passphrase(
+        passphrase: &str,
+        confirmation: Option<&str>,
+    ) -> Result<Self, ApicizeAppError> {
+        if passphrase.is_empty() {
+            return Err(ApicizeAppError::SecretStoreError(
+                "a passphrase is required".to_string(),
+            ));
+        }
+        let file_name = Self::get_passphrase_filename();
+        if !file_name.is_file() && confirmation != Some(passphrase) {
+            return Err(ApicizeAppError::SecretStoreError(
+                "the passphrase confirmation does not match".to_string(),
+            ));
+        }
+        let salt = match fs::read_to_string(&file_name) {
+            Ok(contents) => get_salt(&contents)?.unwrap_or_else(|| generate_salt().to_vec()),
+            Err(_) => generate_salt().to_vec(),
+        };
+        let key = derive_key(passphrase, &salt)?;
+        Self::open(file_name, key, Some(salt))
+    }
+
+    /// Open the file secret store, using a key held in the operating system keyring
+    pub fn open_with_keyring() -> Result<Self, ApicizeAppError> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_KEY_ENTRY)
+            .map_err(|err| ApicizeAppError::SecretStoreError(err.to_string()))?;
+        let key = match entry.get_password() {
+            Ok(encoded) => decode(&encoded)?.try_into().map_err(|_| {
+                ApicizeAppError::SecretStoreError("invalid keyring key".to_string())
+            })?,
+            Err(keyring::Error::NoEntry) => {
+                let key = Aes256Gcm::generate_key(&mut OsRng);
+                entry
+                    .set_password(&STANDARD.encode(key))
+                    .map_err(|err| ApicizeAppError::SecretStoreError(err.to_string()))?;
+                key.into()
+            }
+            Err(err) => return Err(ApicizeAppError::SecretStoreError(err.to_string())),
+        };
+        let file_name = ApicizeSettings::get_settings_directory().join("secrets.apicize-keyring");
+        Self::open(file_name, key, None)
+    }
+
+    fn open(
+        file_name: PathBuf,
+        key: [u8; 32],
+        salt: Option<Vec<u8>>,
+    ) -> Result<Self, ApicizeAppError> {
+        let values = if Path::new(&file_name).is_file() {
+            serde_json::from_slice(&decrypt(&key, &fs::read_to_string(&file_name)?)?)?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(EncryptedFileSecretStore {
+            file_name,
+            key,
+            salt,
+            values: Mutex::new(values),
+        })
+    }
+
+    fn write(&self, values: &BTreeMap<String, String>) -> Result<(), ApicizeAppError> {
+        if let Some(directory) = self.file_name.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let contents = encrypt(
+            &self.key,
+            self.salt.as_deref(),
+            &serde_json::to_vec(values)?,
+        )?;
+
+        // Written to a temporary file first, so that a failed write cannot corrupt the store
+        let mut temp_file_name = self.file_name.clone().into_os_string();
+        temp_file_name.push(".tmp");
+        fs::write(&temp_file_name, contents)?;
+        fs::rename(&temp_file_name, &self.file_name)?;
+        Ok(())
+    }
+}
+
+impl SecretStore for EncryptedFileSecretStore {
+    fn get(&self, name: &str) -> Result<Option<String>, ApicizeAppError> {
+        match self.values.lock() {
+            Ok(values) => Ok(values.get(name).cloned()),
+            Err(err) => Err(ApicizeAppError::ConcurrencyError(err.to_string())),
+        }
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), ApicizeAppError> {
+        match self.values.lock() {
+            Ok(mut values) => {
+                if values.get(name).is_some_and(|v| v == value) {
+                    return Ok(());
+                }
+                values.insert(name.to_string(), value.to_string());
+                self.write(&values)
+            }
+            Err(err) => Err(ApicizeAppError::ConcurrencyError(err.to_string())),
+        }
+    }
+
+    fn delete(&self, name: &str) -> Result<(), ApicizeAppError> {
+        match self.values.lock() {
+            Ok(mut values) => {
+                if values.remove(name).is_some() {
+                    self.write(&values)
+                } else {
+                    Ok(())
+                }
+            }
+            Err(err) => Err(ApicizeAppError::ConcurrencyError(err.to_string())),
+        }
+    }
+}
+
+/// Parameters removed from one section of a workspace, so they can be stored separately
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StoredParameters {
+    scenarios: Vec<Value>,
+    authorizations: Vec<Value>,
+    certificates: Vec<Value>,
+    proxies: Vec<Value>,
+}
+
+impl StoredParameters {
+    /// Remove the parameters in a section of the workspace
+    pub(crate) fn take(workspace: &mut Workspace, section: &str) -> Result<Self, ApicizeAppError> {
+        Ok(StoredParameters {
+            scenarios: take_section(&mut workspace.scenarios, section)?,
+            authorizations: take_section(&mut workspace.authorizations, section)?,
+            certificates: take_section(&mut workspace.certificates, section)?,
+            proxies: take_section(&mut workspace.proxies, section)?,
+        })
+    }
+
+    /// Add parameters to a section of the workspace, returning true if any were added;
+    /// parameters already in the workspace are left alone
+    pub(crate) fn add_to(
+        self,
+        workspace: &mut Workspace,
+        section: &str,
+    ) -> Result<bool, ApicizeAppError> {
+        let scenarios = add_section(&mut workspace.scenarios, section, self.scenarios)?;
+        let authorizations =
+            add_section(&mut workspace.authorizations, section, self.authorizations)?;
+        let certificates = add_section(&mut workspace.certificates, section, self.certificates)?;
+        let proxies = add_section(&mut workspace.proxies, section, self.proxies)?;
+        Ok(scenarios || authorizations || certificates || proxies)
+    }
+}
+
+/// Return true if the workspace has any parameters in the section
+pub(crate) fn has_parameters(workspace: &Workspace, section: &str) -> bool {
+    fn any<T>(entities: &IndexedEntities<T>, section: &str) -> bool {
+        entities
+            .child_ids
+            .get(section)
+            .is_some_and(|ids| !ids.is_empty())
+    }
+    any(&workspace.scenarios, section)
+        || any(&workspace.authorizations, section)
+        || any(&workspace.certificates, section)
+        || any(&workspace.proxies, section)
+}
+
+/// Return true if the workspace has any vault parameters
+pub fn has_vault(workspace: &Workspace) -> bool {
+    has_parameters(workspace, VAULT_SECTION)
+}
+
+/// Save the workspace's vault parameters to the store, returning a copy of the workspace
+/// without them, suitable for saving to disk
+pub fn store_vault(
+    store: &dyn SecretStore,
+    workspace: &Workspace,
+) -> Result<Workspace, ApicizeAppError> {
+    let mut stripped = workspace.clone();
+    let vault = StoredParameters::take(&mut stripped, VAULT_SECTION)?;
+    store.set(VAULT_ENTRY, &serde_json::to_string(&vault)?)?;
+    Ok(stripped)
+}
+
+/// Add vault parameters from the store to the workspace, returning true if any were added;
+/// parameters already in the workspace are left alone
+pub fn restore_vault(
+    store: &dyn SecretStore,
+    workspace: &mut Workspace,
+) -> Result<bool, ApicizeAppError> {
+    let Some(stored) = store.get(VAULT_ENTRY)? else {
+        return Ok(false);
+    };
+    serde_json::from_str::<StoredParameters>(&stored)?.add_to(workspace, VAULT_SECTION)
+}
+
+fn take_section<T: Serialize>(
+    entities: &mut IndexedEntities<T>,
+    section: &str,
+) -> Result<Vec<Value>, ApicizeAppError> {
+    let ids = std::mem::take(entities.child_ids.entry(section.to_string()).or_default());
+    let mut removed = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(entity) = entities.entities.remove(&id) {
+            removed.push(serde_json::to_value(entity)?);
+        }
+    }
+    Ok(removed)
+}
+
+fn add_section<T: DeserializeOwned + Identifiable>(
+    entities: &mut IndexedEntities<T>,
+    section: &str,
+    stored: Vec<Value>,
+) -> Result<bool, ApicizeAppError> {
+    let mut added = false;
+    for value in stored {
+        let entity = serde_json::from_value::<T>(value)?;
+        let id = entity.get_id().to_string();
+        if entities.entities.contains_key(&id) {
+            continue;
+        }
+        entities.entities.insert(id.clone(), entity);
+        entities
+            .child_ids
+            .entry(section.to_string())
+            .or_default()
+            .push(id);
+        added = true;
+    }
+    Ok(added)
+}
+
+/// Return cached OAuth2 tokens saved in the store, keyed by authorization ID
+pub fn load_tokens(
+    store: &dyn SecretStore,
+) -> Result<BTreeMap<String, CachedTokenInfo>, ApicizeAppError> {
+    let mut tokens = BTreeMap::new();
+    for (authorization_id, token_info) in load_token_values(store)? {
+        tokens.insert(authorization_id, serde_json::from_value(token_info)?);
+    }
+    Ok(tokens)
+}
+
+/// Save a cached OAuth2 token, as sent by the UI, or remove it if no token is specified
+pub fn store_token(
+    store: &dyn SecretStore,
+    authorization_id: &str,
+    token_info: Option<Value>,
+) -> Result<(), ApicizeAppError> {
+    let mut tokens = load_token_values(store)?;
+    match token_info {
+        Some(token_info) => {
+            tokens.insert(authorization_id.to_string(), token_info);
+        }
+        None => {
+            if tokens.remove(authorization_id).is_none() {
+                return Ok(());
+            }
+        }
+    }
+    store.set(TOKENS_ENTRY, &serde_json::to_string(&tokens)?)
+}
+
+/// Remove all cached OAuth2 tokens
+pub fn clear_tokens(store: &dyn SecretStore) -> Result<(), ApicizeAppError> {
+    store.delete(TOKENS_ENTRY)
+}
+
+fn load_token_values(store: &dyn SecretStore) -> Result<BTreeMap<String, Value>, ApicizeAppError> {
+    match store.get(TOKENS_ENTRY)? {
+        Some(tokens) => Ok(serde_json::from_str(&tokens)?),
+        None => Ok(BTreeMap::new()),
+    }
+}
@@ -25,6 +25,9 @@ pub struct SessionStartupState {
 pub struct Session {
     pub workspace_id: String,
     pub startup_state: Option<SessionStartupState>,
+    /// If true, secrets are not masked in text sent to the session
+    #[serde(default)]
+    pub reveal_secrets: bool,
 }
 
 #[derive(Default)]
@@ -84,6 +87,13 @@ impl Sessions {
         }
     }
 
+    pub fn get_session_mut(&mut self, session_id: &str) -> Result<&mut Session, ApicizeAppError> {
+        match self.sessions.get_mut(session_id) {
+            Some(session) => Ok(session),
+            None => Err(ApicizeAppError::InvaliedSession(session_id.into())),
+        }
+    }
+
     pub fn get_workspace_session_ids(&self, workspace_id: &str) -> Vec<String> {
         self.sessions
             .iter()
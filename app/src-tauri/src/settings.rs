@@ -14,6 +14,11 @@ use apicize_lib::{
 use dirs::{config_dir, document_dir, home_dir};
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    secrets::SecretStorage,
+    trace::{DEFAULT_TRACE_RETENTION, DEFAULT_TRACE_SPOOL_DAYS},
+};
+
 fn default_font_size() -> i32 {
     12
 }
@@ -34,6 +39,14 @@ fn default_true() -> bool {
     true
 }
 
+fn default_trace_retention() -> usize {
+    DEFAULT_TRACE_RETENTION
+}
+
+fn default_trace_spool_days() -> u32 {
+    DEFAULT_TRACE_SPOOL_DAYS
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
 /// Color scheme for UI app
@@ -105,6 +118,37 @@ pub struct ApicizeSettings {
     /// Tab indent
     #[serde(default = "default_true")]
     pub editor_check_js_syntax: bool,
+
+    /// Number of network trace events retained in memory
+    #[serde(default = "default_trace_retention")]
+    pub trace_retention: usize,
+
+    /// Append network trace events to files in the settings directory
+    #[serde(default)]
+    pub trace_spool: bool,
+
+    /// Number of days network trace files are kept
+    #[serde(default = "default_trace_spool_days")]
+    pub trace_spool_days: u32,
+
+    /// Where vault parameters and cached OAuth2 tokens are stored
+    #[serde(default)]
+    pub secret_storage: SecretStorage,
+
+    /// Encrypt workbook private parameter files with a passphrase
+    #[serde(default)]
+    pub encrypt_private_parameters: bool,
+
+    /// Save workbooks in a deterministic, diff-friendly format
+    #[serde(default)]
+    pub canonical_workbook_format: bool,
+
+    /// Split multi-line bodies and test scripts into lines when saving in canonical format;
+    /// split text is saved as "" with the lines in a `…Lines` array (i.e. `test` and
+    /// `testLines`, `body/data` and `body/dataLines`), so earlier versions of Apicize and the
+    /// standalone Apicize CLI see those properties as empty
+    #[serde(default)]
+    pub split_multiline_strings: bool,
 }
 
 impl ApicizeSettings {
@@ -193,6 +237,13 @@ impl ApicizeSettings {
                 editor_indent_size: 3,
                 editor_check_js_syntax: true,
                 editor_detect_existing_indent: true,
+                trace_retention: DEFAULT_TRACE_RETENTION,
+                trace_spool: false,
+                trace_spool_days: DEFAULT_TRACE_SPOOL_DAYS,
+                secret_storage: SecretStorage::default(),
+                encrypt_private_parameters: false,
+                canonical_workbook_format: false,
+                split_multiline_strings: false,
             };
             Ok(SerializationOpenSuccess {
                 file_name: String::from(""),
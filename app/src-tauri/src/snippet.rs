@@ -0,0 +1,532 @@
+//! Snippet submodule
+//!
+//! This submodule renders a resolved request as a command line or code snippet
+//! (curl, HTTPie, Rust reqwest or Python requests)
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    error::ApicizeAppError,
+    import::{encode_query_component, get_text},
+    workspaces::ResolvedRequest,
+};
+
+/// Supported snippet formats
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum SnippetFormat {
+    Curl,
+    Httpie,
+    Reqwest,
+    PythonRequests,
+}
+
+/// Rendered snippet, along with anything that could not be fully represented
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestSnippet {
+    pub snippet: String,
+    pub warnings: Vec<String>,
+}
+
+/// Placeholder for OAuth2 tokens, which are not retrieved when rendering snippets
+const TOKEN_PLACEHOLDER: &str = "<access token>";
+
+enum SnippetBody {
+    Json(Value),
+    Text(String),
+    Form(Vec<(String, String)>),
+}
+
+/// Request with scenario variables substituted and parameters flattened
+struct SnippetRequest {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    basic_auth: Option<(String, String)>,
+    body: Option<SnippetBody>,
+    proxy: Option<String>,
+    /// Certificate and (optional) key file names
+    certificate: Option<(String, Option<String>)>,
+    accept_invalid_certs: bool,
+    timeout: Option<u64>,
+}
+
+impl RequestSnippet {
+    /// Render the resolved request in the specified format
+    pub fn render(
+        resolved: &ResolvedRequest,
+        format: SnippetFormat,
+    ) -> Result<RequestSnippet, ApicizeAppError> {
+        let mut warnings: Vec<String> = vec![];
+        let request = SnippetRequest::build(resolved, &mut warnings)?;
+        let snippet = match format {
+            SnippetFormat::Curl => request.to_curl(),
+            SnippetFormat::Httpie => request.to_httpie(),
+            SnippetFormat::Reqwest => request.to_reqwest(),
+            SnippetFormat::PythonRequests => request.to_python_requests(),
+        };
+        Ok(RequestSnippet { snippet, warnings })
+    }
+}
+
+impl SnippetRequest {
+    fn build(
+        resolved: &ResolvedRequest,
+        warnings: &mut Vec<String>,
+    ) -> Result<SnippetRequest, ApicizeAppError> {
+        let request = serde_json::to_value(&resolved.request)?;
+
+        // Only text and JSON scenario variables can be substituted without running the request
+        let mut variables: Vec<(String, String)> = vec![];
+        if let Some(scenario) = &resolved.scenario {
+            let scenario = serde_json::to_value(scenario)?;
+            for variable in scenario
+                .get("variables")
+                .and_then(|v| v.as_array())
+                .map(|v| v.as_slice())
+                .unwrap_or_default()
+            {
+                if variable.get("disabled").and_then(|d| d.as_bool()) == Some(true) {
+                    continue;
+                }
+                let name = get_text(variable.get("name"));
+                match variable.get("type").and_then(|t| t.as_str()) {
+                    Some("TEXT") | Some("JSON") | None => {
+                        variables.push((name, get_text(variable.get("value"))))
+                    }
+                    Some(_) => warnings.push(format!(
+                        "Scenario variable \"{name}\" is loaded from a file and was not substituted"
+                    )),
+                }
+            }
+        }
+        let substitute = |text: &str| {
+            variables
+                .iter()
+                .fold(text.to_string(), |text, (name, value)| {
+                    text.replace(&format!("{{{{{name}}}}}"), value)
+                })
+        };
+
+        let mut url = substitute(&get_text(request.get("url")));
+        let query = get_pairs(request.get("queryStringParams"))
+            .into_iter()
+            .map(|(name, value)| {
+                format!(
+                    "{}={}",
+                    encode_query_component(&substitute(&name)),
+                    encode_query_component(&substitute(&value))
+                )
+            })
+            .collect::<Vec<String>>();
+        if !query.is_empty() {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&query.join("&"));
+        }
+
+        let mut headers = get_pairs(request.get("headers"))
+            .into_iter()
+            .map(|(name, value)| (substitute(&name), substitute(&value)))
+            .collect::<Vec<(String, String)>>();
+
+        let mut basic_auth: Option<(String, String)> = None;
+        if let Some(authorization) = &resolved.authorization {
+            let authorization = serde_json::to_value(authorization)?;
+            match authorization.get("type").and_then(|t| t.as_str()) {
+                Some("Basic") => {
+                    basic_auth = Some((
+                        substitute(&get_text(authorization.get("username"))),
+                        substitute(&get_text(authorization.get("password"))),
+                    ))
+                }
+                Some("ApiKey") => headers.push((
+                    substitute(&get_text(authorization.get("header"))),
+                    substitute(&get_text(authorization.get("value"))),
+                )),
+                Some(_) => {
+                    headers.push((
+                        "Authorization".to_string(),
+                        format!("Bearer {TOKEN_PLACEHOLDER}"),
+                    ));
+                    warnings.push(format!(
+                        "OAuth2 token is not retrieved, replace \"{TOKEN_PLACEHOLDER}\" with a valid token"
+                    ));
+                }
+                None => {}
+            }
+        }
+
+        let body = match request.get("body") {
+            Some(body) => match body.get("type").and_then(|t| t.as_str()) {
+                Some("JSON") => match body.get("data") {
+                    // Substitute within the serialized JSON so variables inside values are replaced
+                    Some(Value::String(text)) => Some(SnippetBody::Text(substitute(text))),
+                    Some(data) => {
+                        let text = substitute(&data.to_string());
+                        Some(match serde_json::from_str::<Value>(&text) {
+                            Ok(data) => SnippetBody::Json(data),
+                            Err(_) => SnippetBody::Text(text),
+                        })
+                    }
+                    None => None,
+                },
+                Some("Text") | Some("XML") => {
+                    Some(SnippetBody::Text(substitute(&get_text(body.get("data")))))
+                }
+                Some("Form") => Some(SnippetBody::Form(
+                    get_pairs(body.get("data"))
+                        .into_iter()
+                        .map(|(name, value)| (substitute(&name), substitute(&value)))
+                        .collect(),
+                )),
+                Some("Raw") => {
+                    warnings.push("Raw (binary) body is not included in snippets".to_string());
+                    None
+                }
+                _ => None,
+            },
+            None => None,
+        };
+
+        let proxy = match &resolved.proxy {
+            Some(proxy) => Some(get_text(serde_json::to_value(proxy)?.get("url"))),
+            None => None,
+        };
+
+        let certificate = match &resolved.certificate {
+            Some(certificate) => {
+                let certificate = serde_json::to_value(certificate)?;
+                let name = get_text(certificate.get("name"));
+                let file_name = name
+                    .chars()
+                    .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                    .collect::<String>();
+                warnings.push(format!(
+                    "Certificate \"{name}\" must be saved to file(s) referenced by the snippet"
+                ));
+                match certificate.get("type").and_then(|t| t.as_str()) {
+                    Some("PKCS12") => Some((format!("{file_name}.pfx"), None)),
+                    Some("PKCS8_PEM") => {
+                        Some((format!("{file_name}.pem"), Some(format!("{file_name}.key"))))
+                    }
+                    _ => Some((format!("{file_name}.pem"), None)),
+                }
+            }
+            None => None,
+        };
+
+        Ok(SnippetRequest {
+            method: request
+                .get("method")
+                .and_then(|m| m.as_str())
+                .unwrap_or("GET")
+                .to_string(),
+            url,
+            headers,
+            basic_auth,
+            body,
+            proxy,
+            certificate,
+            accept_invalid_certs: request
+                .get("acceptInvalidCerts")
+                .and_then(|a| a.as_bool())
+                .unwrap_or(false),
+            timeout: request
+                .get("timeout")
+                .and_then(|t| t.as_u64())
+                .filter(|t| *t > 0),
+        })
+    }
+
+    fn to_curl(&self) -> String {
+        let mut args = vec![format!(
+            "curl -X {} {}",
+            self.method,
+            quote_shell(&self.url)
+        )];
+        for (name, value) in &self.headers {
+            args.push(format!("-H {}", quote_shell(&format!("{name}: {value}"))));
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            args.push(format!(
+                "-u {}",
+                quote_shell(&format!("{username}:{password}"))
+            ));
+        }
+        match &self.body {
+            Some(SnippetBody::Json(data)) => {
+                args.push(format!("--data-raw {}", quote_shell(&data.to_string())))
+            }
+            Some(SnippetBody::Text(text)) => args.push(format!("--data-raw {}", quote_shell(text))),
+            Some(SnippetBody::Form(fields)) => {
+                for (name, value) in fields {
+                    args.push(format!(
+                        "--data-urlencode {}",
+                        quote_shell(&format!("{name}={value}"))
+                    ));
+                }
+            }
+            None => {}
+        }
+        if let Some(proxy) = &self.proxy {
+            args.push(format!("-x {}", quote_shell(proxy)));
+        }
+        if let Some((certificate, key)) = &self.certificate {
+            if certificate.ends_with(".pfx") {
+                args.push("--cert-type P12".to_string());
+            }
+            args.push(format!("--cert {}", quote_shell(certificate)));
+            if let Some(key) = key {
+                args.push(format!("--key {}", quote_shell(key)));
+            }
+        }
+        if self.accept_invalid_certs {
+            args.push("--insecure".to_string());
+        }
+        if let Some(timeout) = self.timeout {
+            args.push(format!("--max-time {}", format_seconds(timeout)));
+        }
+        args.join(" \\\n  ")
+    }
+
+    fn to_httpie(&self) -> String {
+        let mut args = vec![match &self.body {
+            Some(SnippetBody::Form(_)) => "http --form".to_string(),
+            _ => "http".to_string(),
+        }];
+        if let Some((username, password)) = &self.basic_auth {
+            args.push(format!(
+                "-a {}",
+                quote_shell(&format!("{username}:{password}"))
+            ));
+        }
+        if let Some(proxy) = &self.proxy {
+            for scheme in ["http", "https"] {
+                args.push(format!(
+                    "--proxy {}",
+                    quote_shell(&format!("{scheme}:{proxy}"))
+                ));
+            }
+        }
+        if let Some((certificate, key)) = &self.certificate {
+            args.push(format!("--cert {}", quote_shell(certificate)));
+            if let Some(key) = key {
+                args.push(format!("--cert-key {}", quote_shell(key)));
+            }
+        }
+        if self.accept_invalid_certs {
+            args.push("--verify no".to_string());
+        }
+        if let Some(timeout) = self.timeout {
+            args.push(format!("--timeout {}", format_seconds(timeout)));
+        }
+        match &self.body {
+            Some(SnippetBody::Json(data)) => {
+                args.push(format!("--raw {}", quote_shell(&data.to_string())))
+            }
+            Some(SnippetBody::Text(text)) => args.push(format!("--raw {}", quote_shell(text))),
+            _ => {}
+        }
+        args.push(self.method.clone());
+        args.push(quote_shell(&self.url));
+        for (name, value) in &self.headers {
+            args.push(quote_shell(&format!("{name}:{value}")));
+        }
+        if let Some(SnippetBody::Form(fields)) = &self.body {
+            for (name, value) in fields {
+                args.push(quote_shell(&format!("{name}={value}")));
+            }
+        }
+        args.join(" \\\n  ")
+    }
+
+    fn to_reqwest(&self) -> String {
+        let mut lines = vec!["let client = reqwest::blocking::Client::builder()".to_string()];
+        if let Some(proxy) = &self.proxy {
+            lines.push(format!("    .proxy(reqwest::Proxy::all({proxy:?})?)"));
+        }
+        if let Some((certificate, key)) = &self.certificate {
+            if certificate.ends_with(".pfx") {
+                lines.push(format!(
+                    "    .identity(reqwest::Identity::from_pkcs12_der(&std::fs::read({certificate:?})?, \"<password>\")?)"
+                ));
+            } else if let Some(key) = key {
+                lines.push(format!(
+                    "    .identity(reqwest::Identity::from_pkcs8_pem(&std::fs::read({certificate:?})?, &std::fs::read({key:?})?)?)"
+                ));
+            } else {
+                lines.push(format!(
+                    "    .identity(reqwest::Identity::from_pem(&std::fs::read({certificate:?})?)?)"
+                ));
+            }
+        }
+        if self.accept_invalid_certs {
+            lines.push("    .danger_accept_invalid_certs(true)".to_string());
+        }
+        if let Some(timeout) = self.timeout {
+            lines.push(format!(
+                "    .timeout(std::time::Duration::from_millis({timeout}))"
+            ));
+        }
+        lines.push("    .build()?;".to_string());
+        lines.push(String::default());
+        lines.push("let response = client".to_string());
+        lines.push(format!(
+            "    .request(reqwest::Method::{}, {:?})",
+            self.method, self.url
+        ));
+        for (name, value) in &self.headers {
+            lines.push(format!("    .header({name:?}, {value:?})"));
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            lines.push(format!("    .basic_auth({username:?}, Some({password:?}))"));
+        }
+        match &self.body {
+            Some(SnippetBody::Json(data)) => {
+                lines.push(format!("    .body({:?})", data.to_string()))
+            }
+            Some(SnippetBody::Text(text)) => lines.push(format!("    .body({text:?})")),
+            Some(SnippetBody::Form(fields)) => lines.push(format!(
+                "    .form(&[{}])",
+                fields
+                    .iter()
+                    .map(|(name, value)| format!("({name:?}, {value:?})"))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )),
+            None => {}
+        }
+        lines.push("    .send()?;".to_string());
+        lines.push(String::default());
+        lines.push("println!(\"{}\", response.text()?);".to_string());
+        lines.join("\n")
+    }
+
+    fn to_python_requests(&self) -> String {
+        let mut args = vec![quote_python(&self.method), quote_python(&self.url)];
+        if !self.headers.is_empty() {
+            args.push(format!(
+                "headers={{\n{}\n    }}",
+                self.headers
+                    .iter()
+                    .map(|(name, value)| format!(
+                        "        {}: {},",
+                        quote_python(name),
+                        quote_python(value)
+                    ))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ));
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            args.push(format!(
+                "auth=({}, {})",
+                quote_python(username),
+                quote_python(password)
+            ));
+        }
+        match &self.body {
+            Some(SnippetBody::Json(data)) => args.push(format!("json={}", to_python(data))),
+            Some(SnippetBody::Text(text)) => args.push(format!("data={}", quote_python(text))),
+            Some(SnippetBody::Form(fields)) => args.push(format!(
+                "data={{{}}}",
+                fields
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", quote_python(name), quote_python(value)))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )),
+            None => {}
+        }
+        if let Some(proxy) = &self.proxy {
+            let proxy = quote_python(proxy);
+            args.push(format!("proxies={{\"http\": {proxy}, \"https\": {proxy}}}"));
+        }
+        if let Some((certificate, key)) = &self.certificate {
+            // requests does not support PKCS12 certificates directly
+            match key {
+                Some(key) => args.push(format!(
+                    "cert=({}, {})",
+                    quote_python(certificate),
+                    quote_python(key)
+                )),
+                None => args.push(format!("cert={}", quote_python(certificate))),
+            }
+        }
+        if self.accept_invalid_certs {
+            args.push("verify=False".to_string());
+        }
+        if let Some(timeout) = self.timeout {
+            args.push(format!("timeout={}", format_seconds(timeout)));
+        }
+
+        format!(
+            "import requests\n\nresponse = requests.request(\n{}\n)\nprint(response.status_code)\nprint(response.text)",
+            args.iter()
+                .map(|a| format!("    {a},"))
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+}
+
+/// Return enabled name/value pairs
+fn get_pairs(value: Option<&Value>) -> Vec<(String, String)> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|pairs| {
+            pairs
+                .iter()
+                .filter(|p| p.get("disabled").and_then(|d| d.as_bool()) != Some(true))
+                .map(|p| (get_text(p.get("name")), get_text(p.get("value"))))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Single-quote text for POSIX shells
+fn quote_shell(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// JSON string escaping is also valid for Python string literals
+fn quote_python(text: &str) -> String {
+    Value::String(text.to_string()).to_string()
+}
+
+/// Render JSON data as a Python literal
+fn to_python(value: &Value) -> String {
+    match value {
+        Value::Null => "None".to_string(),
+        Value::Bool(true) => "True".to_string(),
+        Value::Bool(false) => "False".to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => quote_python(s),
+        Value::Array(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(to_python)
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        Value::Object(fields) => format!(
+            "{{{}}}",
+            fields
+                .iter()
+                .map(|(name, value)| format!("{}: {}", quote_python(name), to_python(value)))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    }
+}
+
+fn format_seconds(milliseconds: u64) -> String {
+    if milliseconds % 1000 == 0 {
+        (milliseconds / 1000).to_string()
+    } else {
+        format!("{:.3}", milliseconds as f64 / 1000.0)
+    }
+}
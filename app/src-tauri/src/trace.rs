@@ -1,43 +1,151 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    future::Future,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
-use chrono::Local;
+use chrono::{Days, Local, NaiveDate, Utc};
 use log::{Metadata, Record};
+use parking_lot::{Mutex, RwLock};
 use regex::Regex;
-use serde::Serialize;
-use tauri::{async_runtime::Sender, AppHandle, Emitter};
-use tokio::sync::mpsc;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc::{self, error::TrySendError, Sender};
 
-use crate::error::ApicizeAppError;
+use crate::{
+    error::ApicizeAppError, redact::Redactor, settings::ApicizeSettings, SessionsState,
+    WorkspacesState,
+};
+
+/// Default number of trace events retained in memory
+pub const DEFAULT_TRACE_RETENTION: usize = 1000;
+
+/// Default number of days spooled trace files are kept
+pub const DEFAULT_TRACE_SPOOL_DAYS: u32 = 7;
+
+/// Maximum number of connection hosts tracked; connection IDs are reused, so this
+/// only needs to cover connections that are open at the same time
+const MAX_CONNECTION_HOSTS: usize = 1000;
+
+/// Maximum number of new connections awaiting their first read or write
+const MAX_PENDING_CONNECTIONS: usize = 1000;
+
+/// Maximum number of trace events queued for processing, further events are dropped
+const MAX_QUEUED_EVENTS: usize = 10000;
+
+tokio::task_local! {
+    /// Run ID and request or group ID of the run executing in the current task
+    static TRACE_RUN: (String, String);
+}
 
 pub struct ReqwestLogger {
     regex_readwrite: Regex,
     regex_connect: Regex,
     app: AppHandle,
     event_sender: Sender<ReqwestEvent>,
-    stored_log: Arc<RwLock<Vec<ReqwestEvent>>>,
+    /// Number of events dropped because the queue was full
+    dropped: AtomicU64,
+    stored_log: Arc<RwLock<VecDeque<ReqwestEvent>>>,
+    retention: Arc<AtomicUsize>,
+    spool: Arc<AtomicBool>,
+    spool_days: Arc<AtomicU32>,
+    sequence: AtomicU64,
+    runs: Mutex<TraceRuns>,
+    /// Host names, keyed by connection ID
+    hosts: Mutex<FxHashMap<String, String>>,
+}
+
+/// Runs in progress, used to attribute trace events to the request that caused them
+#[derive(Default)]
+struct TraceRuns {
+    /// Request or group ID, keyed by run ID
+    active: FxHashMap<String, String>,
+    /// Run ID and request or group ID, keyed by connection ID
+    connections: FxHashMap<String, (String, String)>,
+    /// Host, run ID and request or group ID of connections started but not yet read or
+    /// written, in the order they were started
+    pending: VecDeque<(String, (String, String))>,
+}
+
+impl TraceRuns {
+    /// Return the run executing in the current task, or the only active run
+    fn current(&self) -> Option<(String, String)> {
+        TRACE_RUN.try_with(|run| run.clone()).ok().or_else(|| {
+            if self.active.len() == 1 {
+                self.active
+                    .iter()
+                    .next()
+                    .map(|(run_id, id)| (run_id.clone(), id.clone()))
+            } else {
+                None
+            }
+        })
+    }
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct ReqwestEventConnect {
+    /// Order event was logged in
+    pub sequence: u64,
     pub timestamp: String,
+    /// Time event was logged (milliseconds since Unix epoch)
+    pub timestamp_ms: i64,
     pub host: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_or_group_id: Option<String>,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct ReqwestEventRead {
+    /// Order event was logged in
+    pub sequence: u64,
     pub timestamp: String,
+    /// Time event was logged (milliseconds since Unix epoch)
+    pub timestamp_ms: i64,
+    /// Connection ID
     pub id: String,
     pub data: String,
+    /// Host the connection was opened to, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_or_group_id: Option<String>,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct ReqwestEventWrite {
+    /// Order event was logged in
+    pub sequence: u64,
     pub timestamp: String,
+    /// Time event was logged (milliseconds since Unix epoch)
+    pub timestamp_ms: i64,
+    /// Connection ID
     pub id: String,
     pub data: String,
+    /// Host the connection was opened to, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_or_group_id: Option<String>,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(tag = "event")]
 pub enum ReqwestEvent {
     Connect(ReqwestEventConnect),
@@ -46,23 +154,171 @@ pub enum ReqwestEvent {
     Clear,
 }
 
+/// Direction of traffic on a connection
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceDirection {
+    Read,
+    Write,
+}
+
+/// Criteria for selecting stored trace events; unset criteria match everything
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceQuery {
+    /// Text contained in the host (case-insensitive)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    /// Earliest time to include (milliseconds since Unix epoch)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<i64>,
+    /// Latest time to include (milliseconds since Unix epoch)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<i64>,
+    /// Only include reads or writes (connect events are excluded)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<TraceDirection>,
+    /// Text contained in the host or data (case-insensitive)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains: Option<String>,
+    /// Number of matching events to skip
+    #[serde(default)]
+    pub offset: usize,
+    /// Maximum number of events to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Page of trace events matching a query
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TracePage {
+    pub events: Vec<ReqwestEvent>,
+    /// Number of events matching the query, before pagination
+    pub total: usize,
+    pub offset: usize,
+    /// Number of events dropped because they were logged faster than they could be processed
+    pub dropped: u64,
+}
+
+impl TraceQuery {
+    fn matches(&self, event: &ReqwestEvent, host: Option<&str>, contains: Option<&str>) -> bool {
+        let (timestamp_ms, event_host, connection_id, data, direction) = match event {
+            ReqwestEvent::Connect(e) => (e.timestamp_ms, Some(&e.host), None, None, None),
+            ReqwestEvent::Read(e) => (
+                e.timestamp_ms,
+                e.host.as_ref(),
+                Some(&e.id),
+                Some(&e.data),
+                Some(TraceDirection::Read),
+            ),
+            ReqwestEvent::Write(e) => (
+                e.timestamp_ms,
+                e.host.as_ref(),
+                Some(&e.id),
+                Some(&e.data),
+                Some(TraceDirection::Write),
+            ),
+            ReqwestEvent::Clear => return false,
+        };
+
+        let contains_lower = |text: Option<&String>, lower: &str| {
+            text.is_some_and(|t| t.to_lowercase().contains(lower))
+        };
+
+        self.from.is_none_or(|from| timestamp_ms >= from)
+            && self.to.is_none_or(|to| timestamp_ms <= to)
+            && self.direction.is_none_or(|d| direction == Some(d))
+            && self
+                .connection_id
+                .as_ref()
+                .is_none_or(|id| connection_id == Some(id))
+            && self
+                .run_id
+                .as_deref()
+                .is_none_or(|id| event.get_run_id() == Some(id))
+            && host.is_none_or(|h| contains_lower(event_host, h))
+            && contains.is_none_or(|c| contains_lower(event_host, c) || contains_lower(data, c))
+    }
+}
+
+impl ReqwestEvent {
+    /// Return the order the event was logged in
+    pub fn get_sequence(&self) -> Option<u64> {
+        match self {
+            ReqwestEvent::Connect(e) => Some(e.sequence),
+            ReqwestEvent::Read(e) => Some(e.sequence),
+            ReqwestEvent::Write(e) => Some(e.sequence),
+            ReqwestEvent::Clear => None,
+        }
+    }
+
+    /// Return the ID of the run the event is attributed to, if any
+    pub fn get_run_id(&self) -> Option<&str> {
+        match self {
+            ReqwestEvent::Connect(e) => e.run_id.as_deref(),
+            ReqwestEvent::Read(e) => e.run_id.as_deref(),
+            ReqwestEvent::Write(e) => e.run_id.as_deref(),
+            ReqwestEvent::Clear => None,
+        }
+    }
+
+    /// Return the ID of the request or group the event is attributed to, if any
+    pub fn get_request_or_group_id(&self) -> Option<&str> {
+        match self {
+            ReqwestEvent::Connect(e) => e.request_or_group_id.as_deref(),
+            ReqwestEvent::Read(e) => e.request_or_group_id.as_deref(),
+            ReqwestEvent::Write(e) => e.request_or_group_id.as_deref(),
+            ReqwestEvent::Clear => None,
+        }
+    }
+}
+
 impl ReqwestLogger {
     pub fn new(app: AppHandle) -> Self {
-        let (event_sender, mut event_receiver) = mpsc::channel::<ReqwestEvent>(50);
+        // Bounded so that a stalled receiver cannot exhaust memory, logging never blocks
+        let (event_sender, mut event_receiver) = mpsc::channel::<ReqwestEvent>(MAX_QUEUED_EVENTS);
 
-        let stored_log = Arc::new(RwLock::new(vec![]));
+        let stored_log = Arc::new(RwLock::new(VecDeque::new()));
+        let retention = Arc::new(AtomicUsize::new(DEFAULT_TRACE_RETENTION));
+        let spool = Arc::new(AtomicBool::new(false));
+        let spool_days = Arc::new(AtomicU32::new(DEFAULT_TRACE_SPOOL_DAYS));
         let cloned_stored_log = stored_log.clone();
+        let cloned_retention = retention.clone();
+        let cloned_spool = spool.clone();
+        let cloned_spool_days = spool_days.clone();
         let cloned_app = app.clone();
 
         tokio::spawn(async move {
-            loop {
-                if let Some(event) = event_receiver.recv().await {
-                    cloned_app.emit("log", &event).unwrap();
-                    let mut log = cloned_stored_log.write().unwrap();
-                    while log.len() > 99 {
-                        log.remove(0);
+            let mut spooler = TraceSpooler::default();
+            while let Some(event) = event_receiver.recv().await {
+                // Drain anything else queued so spooled events are written in batches
+                let mut events = vec![event];
+                while let Ok(event) = event_receiver.try_recv() {
+                    events.push(event);
+                }
+
+                // Secrets are masked before events are written to disk
+                let mut redacted = None;
+                if cloned_spool.load(Ordering::Relaxed) {
+                    let redacted = redacted.insert(redact_events(&cloned_app, &events).await);
+                    let spool_days = cloned_spool_days.load(Ordering::Relaxed);
+                    if let Err(err) = spooler.write(redacted, spool_days) {
+                        eprintln!("Unable to spool trace events: {err}");
                     }
-                    log.push(event);
+                }
+
+                emit_events(&cloned_app, &events, &mut redacted).await;
+
+                let retention = cloned_retention.load(Ordering::Relaxed);
+                let mut log = cloned_stored_log.write();
+                log.extend(events);
+                while log.len() > retention {
+                    log.pop_front();
                 }
             }
         });
@@ -71,26 +327,197 @@ impl ReqwestLogger {
             regex_readwrite: Regex::new(r#"^([0-9a-f]+) (read|write): b"(.*)"$"#).unwrap(),
             regex_connect: Regex::new(r#"starting new connection: (.*)"#).unwrap(),
             event_sender,
+            dropped: AtomicU64::new(0),
             app,
             stored_log,
+            retention,
+            spool,
+            spool_days,
+            sequence: AtomicU64::new(0),
+            runs: Mutex::new(TraceRuns::default()),
+            hosts: Mutex::new(FxHashMap::default()),
+        }
+    }
+
+    /// Return the directory trace events are spooled to
+    pub fn get_spool_directory() -> PathBuf {
+        ApicizeSettings::get_settings_directory().join("trace")
+    }
+
+    /// Update the number of events retained in memory, whether events are spooled to disk
+    /// and how many days spooled events are kept
+    pub fn configure(&self, retention: usize, spool: bool, spool_days: u32) {
+        self.retention.store(retention, Ordering::Relaxed);
+        self.spool.store(spool, Ordering::Relaxed);
+        self.spool_days.store(spool_days, Ordering::Relaxed);
+
+        let mut log = self.stored_log.write();
+        while log.len() > retention {
+            log.pop_front();
+        }
+        drop(log);
+
+        if let Err(err) = prune_spool_files(&Self::get_spool_directory(), spool_days) {
+            eprintln!("Unable to remove expired trace files: {err}");
         }
     }
 
+    /// Track a run so that trace events can be attributed to it
+    pub fn begin_run(&self, run_id: &str, request_or_group_id: &str) {
+        self.runs
+            .lock()
+            .active
+            .insert(run_id.to_string(), request_or_group_id.to_string());
+    }
+
+    /// Stop attributing trace events to the specified run
+    pub fn end_run(&self, run_id: &str) {
+        let mut runs = self.runs.lock();
+        runs.active.remove(run_id);
+        runs.connections.retain(|_, (id, _)| id != run_id);
+        runs.pending.retain(|(_, (id, _))| id != run_id);
+    }
+
+    /// Execute a run, attributing connections started while executing it to the run
+    pub async fn attribute_run<F: Future>(
+        run_id: &str,
+        request_or_group_id: &str,
+        run: F,
+    ) -> F::Output {
+        TRACE_RUN
+            .scope((run_id.to_string(), request_or_group_id.to_string()), run)
+            .await
+    }
+
     pub fn get_logs(&self) -> Result<Vec<ReqwestEvent>, ApicizeAppError> {
-        match self.stored_log.read() {
-            Ok(logs) => Ok(logs.to_vec()),
-            Err(e) => Err(ApicizeAppError::ConcurrencyError(e.to_string())),
+        Ok(self.stored_log.read().iter().cloned().collect())
+    }
+
+    /// Return events attributed to a run in the order they were logged, including
+    /// spooled events that are no longer retained in memory
+    pub fn get_run_events(&self, run_id: &str) -> Vec<ReqwestEvent> {
+        let mut events = FxHashMap::<u64, ReqwestEvent>::default();
+        for event in read_spooled_events(run_id) {
+            if let Some(sequence) = event.get_sequence() {
+                events.insert(sequence, event);
+            }
+        }
+        for event in self.stored_log.read().iter() {
+            if let Some(sequence) = event.get_sequence() {
+                if event.get_run_id() == Some(run_id) {
+                    events.insert(sequence, event.clone());
+                }
+            }
+        }
+
+        let mut events = events.into_values().collect::<Vec<ReqwestEvent>>();
+        events.sort_by_key(|e| e.get_sequence());
+        events
+    }
+
+    /// Return stored events matching the query, in the order they were logged; if a
+    /// redactor is specified, events are masked before matching so secrets cannot be probed
+    pub fn query_logs(&self, query: &TraceQuery, redactor: Option<&Redactor>) -> TracePage {
+        let host = query.host.as_ref().map(|h| h.to_lowercase());
+        let contains = query.contains.as_ref().map(|c| c.to_lowercase());
+
+        let log = self.stored_log.read();
+        let mut total = 0;
+        let mut events = vec![];
+        for event in log.iter() {
+            let event = match redactor {
+                Some(redactor) => redactor.redact_event(event),
+                None => event.clone(),
+            };
+            if !query.matches(&event, host.as_deref(), contains.as_deref()) {
+                continue;
+            }
+            if total >= query.offset && query.limit.is_none_or(|limit| events.len() < limit) {
+                events.push(event);
+            }
+            total += 1;
+        }
+
+        TracePage {
+            events,
+            total,
+            offset: query.offset,
+            dropped: self.dropped.load(Ordering::Relaxed),
         }
     }
 
     pub fn clear_logs(&self) -> Result<(), ApicizeAppError> {
-        match self.stored_log.write() {
-            Ok(mut logs) => {
-                logs.clear();
-                self.app.emit("log", ReqwestEvent::Clear).unwrap();
-                Ok(())
+        self.stored_log.write().clear();
+        let _ = self.app.emit("log", ReqwestEvent::Clear);
+        Ok(())
+    }
+
+    /// Return the run ID and request or group ID starting a connection, which is the run
+    /// executing in the current task (or the only active run); the run is held until the
+    /// connection's ID is known from its first read or write
+    fn attribute_connect(&self, host: &str) -> (Option<String>, Option<String>) {
+        let mut runs = self.runs.lock();
+        let Some(run) = runs.current() else {
+            return (None, None);
+        };
+        if runs.pending.len() >= MAX_PENDING_CONNECTIONS {
+            runs.pending.pop_front();
+        }
+        runs.pending.push_back((host.to_string(), run.clone()));
+        (Some(run.0), Some(run.1))
+    }
+
+    /// Return the run ID and request or group ID responsible for a connection's trace
+    /// event; a new connection is matched to the earliest started connection to its host
+    /// and port
+    fn attribute(
+        &self,
+        connection_id: &str,
+        host: Option<&str>,
+    ) -> (Option<String>, Option<String>) {
+        let mut runs = self.runs.lock();
+        if let Some((run_id, request_or_group_id)) = runs.connections.get(connection_id) {
+            return (Some(run_id.clone()), Some(request_or_group_id.clone()));
+        }
+
+        let started = runs
+            .pending
+            .iter()
+            .position(|(started_host, _)| host.is_none_or(|h| is_same_host(started_host, h)))
+            .and_then(|index| runs.pending.remove(index))
+            .map(|(_, run)| run);
+        match started.or_else(|| runs.current()) {
+            Some(run) => {
+                runs.connections
+                    .insert(connection_id.to_string(), run.clone());
+                (Some(run.0), Some(run.1))
             }
-            Err(e) => Err(ApicizeAppError::ConcurrencyError(e.to_string())),
+            None => (None, None),
+        }
+    }
+
+    /// Return the host for a connection, recording it from the Host header of written requests
+    fn connection_host(&self, connection_id: &str, written: Option<&str>) -> Option<String> {
+        let mut hosts = self.hosts.lock();
+        if let Some(host) = written.and_then(|data| {
+            data.lines().find_map(|line| {
+                line.split_once(':')
+                    .filter(|(name, _)| name.eq_ignore_ascii_case("host"))
+                    .map(|(_, value)| value.trim().to_string())
+            })
+        }) {
+            if hosts.len() >= MAX_CONNECTION_HOSTS && !hosts.contains_key(connection_id) {
+                hosts.clear();
+            }
+            hosts.insert(connection_id.to_string(), host);
+        }
+        hosts.get(connection_id).cloned()
+    }
+
+    fn send(&self, event: ReqwestEvent) {
+        // The receiver only goes away during shutdown, when events can be discarded
+        if let Err(TrySendError::Full(_)) = self.event_sender.try_send(event) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
         }
     }
 }
@@ -106,13 +533,15 @@ impl log::Log for ReqwestLogger {
             let args = record.args().to_string();
             if let Some(result) = self.regex_connect.captures(&args) {
                 if let Some(host) = result.get(1) {
-                    let event = ReqwestEvent::Connect(ReqwestEventConnect {
+                    let (run_id, request_or_group_id) = self.attribute_connect(host.as_str());
+                    self.send(ReqwestEvent::Connect(ReqwestEventConnect {
+                        sequence: self.sequence.fetch_add(1, Ordering::Relaxed),
                         timestamp: Local::now().format("%H:%M:%S%.3f").to_string(),
+                        timestamp_ms: Utc::now().timestamp_millis(),
                         host: host.as_str().to_string(),
-                    });
-                    self.event_sender.try_send(event).unwrap();
-                    // self.app.emit("log", &event).unwrap();
-                    // self.event_sender.blocking_send(event).unwrap();
+                        run_id,
+                        request_or_group_id,
+                    }));
                 }
             }
         } else if target == "reqwest::connect::verbose" {
@@ -121,30 +550,40 @@ impl log::Log for ReqwestLogger {
                 if let Some(request_id) = result.get(1) {
                     if let Some(operation) = result.get(2) {
                         if let Some(data) = result.get(3) {
+                            let id = request_id.as_str().to_string();
+                            let data = String::from(data.as_str())
+                                .replace("\\r\\n", "\r\n")
+                                .replace("\\n", "\n");
                             match operation.as_str() {
                                 "read" => {
-                                    let event = ReqwestEvent::Read(ReqwestEventRead {
+                                    let host = self.connection_host(&id, None);
+                                    let (run_id, request_or_group_id) =
+                                        self.attribute(&id, host.as_deref());
+                                    self.send(ReqwestEvent::Read(ReqwestEventRead {
+                                        sequence: self.sequence.fetch_add(1, Ordering::Relaxed),
                                         timestamp: Local::now().format("%H:%M:%S%.3f").to_string(),
-                                        id: request_id.as_str().to_string(),
-                                        data: String::from(data.as_str())
-                                            .replace("\\r\\n", "\r\n")
-                                            .replace("\\n", "\n"),
-                                    });
-                                    self.event_sender.try_send(event).unwrap();
-                                    // self.app.emit("log", &event).unwrap();
-                                    // self.event_sender.blocking_send(event).unwrap();
+                                        timestamp_ms: Utc::now().timestamp_millis(),
+                                        id,
+                                        data,
+                                        host,
+                                        run_id,
+                                        request_or_group_id,
+                                    }));
                                 }
                                 "write" => {
-                                    let event = ReqwestEvent::Write(ReqwestEventWrite {
+                                    let host = self.connection_host(&id, Some(&data));
+                                    let (run_id, request_or_group_id) =
+                                        self.attribute(&id, host.as_deref());
+                                    self.send(ReqwestEvent::Write(ReqwestEventWrite {
+                                        sequence: self.sequence.fetch_add(1, Ordering::Relaxed),
                                         timestamp: Local::now().format("%H:%M:%S%.3f").to_string(),
-                                        id: request_id.as_str().to_string(),
-                                        data: String::from(data.as_str())
-                                            .replace("\\r\\n", "\r\n")
-                                            .replace("\\n", "\n"),
-                                    });
-                                    self.event_sender.try_send(event).unwrap();
-                                    // self.app.emit("log", &event).unwrap();
-                                    // self.event_sender.blocking_send(event).unwrap();
+                                        timestamp_ms: Utc::now().timestamp_millis(),
+                                        id,
+                                        data,
+                                        host,
+                                        run_id,
+                                        request_or_group_id,
+                                    }));
                                 }
                                 _ => {}
                             }
@@ -157,3 +596,173 @@ impl log::Log for ReqwestLogger {
 
     fn flush(&self) {}
 }
+
+/// Return true if a Host header value refers to the host and port of a connection's URI,
+/// where the port defaults to the URI scheme's
+fn is_same_host(uri: &str, host: &str) -> bool {
+    let (scheme, authority) = uri.split_once("://").unwrap_or(("", uri));
+    let authority = authority.split(['/', '?', '#']).next().unwrap_or_default();
+    let authority = authority.rsplit_once('@').map_or(authority, |(_, a)| a);
+    let default_port = match scheme.to_ascii_lowercase().as_str() {
+        "http" => Some(80),
+        "https" => Some(443),
+        _ => None,
+    };
+
+    match (split_host_port(authority), split_host_port(host)) {
+        (Some((uri_host, uri_port)), Some((host, port))) => {
+            uri_host.eq_ignore_ascii_case(host)
+                && uri_port.or(default_port) == port.or(default_port)
+        }
+        _ => false,
+    }
+}
+
+/// Split a host (or bracketed IPv6 address) and optional port
+fn split_host_port(text: &str) -> Option<(&str, Option<u16>)> {
+    let text = text.trim();
+    let (host, port) = if let Some(rest) = text.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match text.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (text, None),
+        }
+    };
+    let port = match port {
+        Some(port) => Some(port.parse::<u16>().ok()?),
+        None => None,
+    };
+    Some((host, port))
+}
+
+/// Return events with the secrets of all open workspaces masked
+async fn redact_events(app: &AppHandle, events: &[ReqwestEvent]) -> Vec<ReqwestEvent> {
+    match app.try_state::<WorkspacesState>() {
+        Some(workspaces_state) => {
+            let redactor = workspaces_state.workspaces.read().await.get_all_redactor();
+            events.iter().map(|e| redactor.redact_event(e)).collect()
+        }
+        None => events.to_vec(),
+    }
+}
+
+/// Send events to each session, masking secrets unless the session has revealed them;
+/// masked events are reused if already generated
+async fn emit_events(
+    app: &AppHandle,
+    events: &[ReqwestEvent],
+    redacted: &mut Option<Vec<ReqwestEvent>>,
+) {
+    let Some(sessions_state) = app.try_state::<SessionsState>() else {
+        return;
+    };
+
+    let sessions = sessions_state.sessions.read().await;
+    for (session_id, session) in &sessions.sessions {
+        let session_events = if session.reveal_secrets {
+            events
+        } else {
+            if redacted.is_none() {
+                *redacted = Some(redact_events(app, events).await);
+            }
+            redacted.as_deref().unwrap_or_default()
+        };
+        for event in session_events {
+            let _ = app.emit_to(session_id, "log", event);
+        }
+    }
+}
+
+/// Read spooled events attributed to a run
+fn read_spooled_events(run_id: &str) -> Vec<ReqwestEvent> {
+    let Ok(entries) = fs::read_dir(ReqwestLogger::get_spool_directory()) else {
+        return vec![];
+    };
+
+    let mut events = vec![];
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "jsonl") {
+            continue;
+        }
+        let Ok(file) = File::open(&path) else {
+            continue;
+        };
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            // Skip parsing lines that cannot belong to the run
+            if !line.contains(run_id) {
+                continue;
+            }
+            if let Ok(event) = serde_json::from_str::<ReqwestEvent>(&line) {
+                if event.get_run_id() == Some(run_id) {
+                    events.push(event);
+                }
+            }
+        }
+    }
+    events
+}
+
+/// Remove spooled trace files older than the specified number of days
+fn prune_spool_files(directory: &Path, spool_days: u32) -> Result<(), ApicizeAppError> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Ok(());
+    };
+    let Some(earliest) = Local::now()
+        .date_naive()
+        .checked_sub_days(Days::new(spool_days.saturating_sub(1).into()))
+    else {
+        return Ok(());
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let date = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("trace-"))
+            .and_then(|name| name.strip_suffix(".jsonl"))
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+        if date.is_some_and(|date| date < earliest) {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Appends trace events as JSON lines to a file per day in the spool directory, removing
+/// files older than the retention period when a day's file is started
+#[derive(Default)]
+struct TraceSpooler {
+    file: Option<(String, BufWriter<File>)>,
+}
+
+impl TraceSpooler {
+    fn write(&mut self, events: &[ReqwestEvent], spool_days: u32) -> Result<(), ApicizeAppError> {
+        let file_name = format!("trace-{}.jsonl", Local::now().format("%Y-%m-%d"));
+        if self
+            .file
+            .as_ref()
+            .is_none_or(|(name, _)| *name != file_name)
+        {
+            let directory = ReqwestLogger::get_spool_directory();
+            fs::create_dir_all(&directory)?;
+            prune_spool_files(&directory, spool_days)?;
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(directory.join(&file_name))?;
+            self.file = Some((file_name, BufWriter::new(file)));
+        }
+
+        if let Some((_, writer)) = self.file.as_mut() {
+            for event in events {
+                writeln!(writer, "{}", serde_json::to_string(event)?)?;
+            }
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! Trace export submodule
+//!
+//! This submodule writes the network trace captured for a run to an annotated text
+//! transcript or JSON lines, redacting sensitive header values
+
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ApicizeAppError,
+    redact::{default_redacted_headers, redact_headers, Redactor},
+    trace::ReqwestEvent,
+};
+
+/// Supported trace export formats
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceExportFormat {
+    /// Annotated text transcript
+    #[default]
+    Text,
+    /// One JSON event per line
+    JsonLines,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceExportOptions {
+    #[serde(default)]
+    pub format: TraceExportFormat,
+    /// Names of headers whose values are redacted (case-insensitive, "*" matches any text)
+    #[serde(default = "default_redacted_headers")]
+    pub redacted_headers: Vec<String>,
+}
+
+impl Default for TraceExportOptions {
+    fn default() -> Self {
+        TraceExportOptions {
+            format: TraceExportFormat::default(),
+            redacted_headers: default_redacted_headers(),
+        }
+    }
+}
+
+/// Render events captured for a run, in the order they were logged, masking secrets
+/// if a redactor is specified
+pub fn export(
+    run_id: &str,
+    name: &str,
+    events: &[ReqwestEvent],
+    options: &TraceExportOptions,
+    redactor: Option<&Redactor>,
+) -> Result<String, ApicizeAppError> {
+    let redacted = events
+        .iter()
+        .map(|event| {
+            let mut event = match redactor {
+                Some(redactor) => redactor.redact_event(event),
+                None => event.clone(),
+            };
+            match &mut event {
+                ReqwestEvent::Read(e) => {
+                    e.data = redact_headers(&e.data, &options.redacted_headers)
+                }
+                ReqwestEvent::Write(e) => {
+                    e.data = redact_headers(&e.data, &options.redacted_headers)
+                }
+                ReqwestEvent::Connect(_) | ReqwestEvent::Clear => {}
+            }
+            event
+        })
+        .collect::<Vec<ReqwestEvent>>();
+
+    match options.format {
+        TraceExportFormat::JsonLines => {
+            let mut lines = String::new();
+            for event in redacted {
+                lines.push_str(&serde_json::to_string(&event)?);
+                lines.push('\n');
+            }
+            Ok(lines)
+        }
+        TraceExportFormat::Text => Ok(render_transcript(run_id, name, &redacted, options)),
+    }
+}
+
+fn render_transcript(
+    run_id: &str,
+    name: &str,
+    events: &[ReqwestEvent],
+    options: &TraceExportOptions,
+) -> String {
+    let mut text = format!(
+        "# Apicize network trace\n# Name: {name}\n# Run ID: {run_id}\n# Exported: {}\n# Events: {}\n",
+        Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        events.len()
+    );
+    if !options.redacted_headers.is_empty() {
+        text.push_str(&format!(
+            "# Redacted headers: {}\n",
+            options.redacted_headers.join(", ")
+        ));
+    }
+
+    for event in events {
+        text.push('\n');
+        match event {
+            ReqwestEvent::Connect(e) => {
+                text.push_str(&format!(
+                    "[{}] #{} CONNECT {}\n",
+                    e.timestamp, e.sequence, e.host
+                ));
+            }
+            ReqwestEvent::Write(e) => {
+                text.push_str(&format!(
+                    "[{}] #{} WRITE connection {} ({} bytes)\n",
+                    e.timestamp,
+                    e.sequence,
+                    e.id,
+                    e.data.len()
+                ));
+                push_prefixed(&mut text, "> ", &e.data);
+            }
+            ReqwestEvent::Read(e) => {
+                text.push_str(&format!(
+                    "[{}] #{} READ connection {} ({} bytes)\n",
+                    e.timestamp,
+                    e.sequence,
+                    e.id,
+                    e.data.len()
+                ));
+                push_prefixed(&mut text, "< ", &e.data);
+            }
+            ReqwestEvent::Clear => {}
+        }
+    }
+    text
+}
+
+/// Append each line of data with a direction prefix
+fn push_prefixed(text: &mut String, prefix: &str, data: &str) {
+    for line in data.lines() {
+        text.push_str(prefix);
+        text.push_str(line);
+        text.push('\n');
+    }
+}
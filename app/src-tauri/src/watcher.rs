@@ -0,0 +1,139 @@
+//! Workbook watcher submodule
+//!
+//! This submodule detects changes made outside of Apicize to open workbooks, their included
+//! files and private and vault parameter files, by periodically comparing file modification times
+//! and sizes with those recorded when the workbook was last opened or saved
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use apicize_lib::Parameters;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    includes::{self, WorkbookInclude},
+    private_params,
+};
+
+/// How often watched files are checked for changes
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Modification time and size of a file, both empty if the file does not exist
+#[derive(Clone, PartialEq)]
+struct FileSignature {
+    modified: Option<SystemTime>,
+    len: Option<u64>,
+}
+
+impl FileSignature {
+    fn read(file_name: &Path) -> Self {
+        match fs::metadata(file_name) {
+            Ok(metadata) => FileSignature {
+                modified: metadata.modified().ok(),
+                len: Some(metadata.len()),
+            },
+            Err(_) => FileSignature {
+                modified: None,
+                len: None,
+            },
+        }
+    }
+}
+
+/// Files watched for a workbook, with their last recorded signatures
+struct WatchedWorkbook {
+    files: Vec<(PathBuf, FileSignature)>,
+}
+
+/// Workbook whose files were changed outside of Apicize
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkbookChange {
+    pub workspace_id: String,
+    /// Names of the changed files
+    pub file_names: Vec<String>,
+    /// True if the workspace has unsaved changes that would conflict with a reload
+    pub dirty: bool,
+}
+
+#[derive(Default)]
+pub struct WorkbookWatcher {
+    workbooks: FxHashMap<String, WatchedWorkbook>,
+}
+
+impl WorkbookWatcher {
+    /// Record the current state of a workspace's workbook files, so that only later changes
+    /// are reported; files shared with other workbooks are updated for them as well
+    pub fn watch(&mut self, workspace_id: &str, file_name: &str, includes: &[WorkbookInclude]) {
+        if file_name.is_empty() {
+            self.workbooks.remove(workspace_id);
+            return;
+        }
+
+        let files = get_watched_files(Path::new(file_name), includes)
+            .into_iter()
+            .map(|file| {
+                let signature = FileSignature::read(&file);
+                (file, signature)
+            })
+            .collect::<Vec<(PathBuf, FileSignature)>>();
+
+        for workbook in self.workbooks.values_mut() {
+            for (file, signature) in workbook.files.iter_mut() {
+                if let Some((_, updated)) = files.iter().find(|(f, _)| f == file) {
+                    *signature = updated.clone();
+                }
+            }
+        }
+
+        self.workbooks
+            .insert(workspace_id.to_string(), WatchedWorkbook { files });
+    }
+
+    /// Stop watching a workspace's workbook files
+    pub fn unwatch(&mut self, workspace_id: &str) {
+        self.workbooks.remove(workspace_id);
+    }
+
+    /// Return the watched workspace IDs
+    pub fn get_workspace_ids(&self) -> Vec<String> {
+        self.workbooks.keys().cloned().collect()
+    }
+
+    /// Return the names of a workspace's files that changed since they were last recorded,
+    /// recording their new state so that each change is only reported once
+    pub fn check(&mut self, workspace_id: &str) -> Vec<String> {
+        let Some(workbook) = self.workbooks.get_mut(workspace_id) else {
+            return vec![];
+        };
+
+        let mut changed = Vec::new();
+        for (file, signature) in workbook.files.iter_mut() {
+            let current = FileSignature::read(file);
+            if current != *signature {
+                changed.push(file.to_string_lossy().to_string());
+                *signature = current;
+            }
+        }
+        changed
+    }
+}
+
+/// Return the files a workbook is loaded from
+fn get_watched_files(workbook_file_name: &Path, includes: &[WorkbookInclude]) -> Vec<PathBuf> {
+    let mut files = vec![
+        workbook_file_name.to_path_buf(),
+        private_params::get_private_filename(workbook_file_name),
+        private_params::get_encrypted_private_filename(workbook_file_name),
+        Parameters::get_globals_filename(),
+    ];
+    files.extend(includes::get_include_filenames(
+        workbook_file_name,
+        includes,
+    ));
+    files
+}
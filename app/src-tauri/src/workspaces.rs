@@ -7,6 +7,7 @@ use apicize_lib::{
     Warnings, WorkbookDefaultParameters, Workspace,
 };
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::{
     collections::{HashSet, VecDeque},
@@ -16,7 +17,17 @@ use std::{
 use rustc_hash::FxHashMap;
 use uuid::Uuid;
 
-use crate::{error::ApicizeAppError, sessions::SessionStartupState};
+use crate::{
+    curl::ParsedCurl,
+    error::ApicizeAppError,
+    history::{HistoryRestore, WorkspaceHistory},
+    includes::WorkbookInclude,
+    mock::{self, MockResponse},
+    redact::Redactor,
+    replace::{self, ReplaceOptions, ReplacePreview},
+    search::{self, SearchOptions, SearchResult},
+    sessions::SessionStartupState,
+};
 
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -77,6 +88,15 @@ pub struct RequestHeaderInfo {
     pub headers: Option<Vec<NameValuePair>>,
 }
 
+/// Request with the parameters in effect for it
+pub struct ResolvedRequest {
+    pub request: Request,
+    pub scenario: Option<Scenario>,
+    pub authorization: Option<Authorization>,
+    pub certificate: Option<Certificate>,
+    pub proxy: Option<Proxy>,
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct RequestBodyInfo {
@@ -189,6 +209,24 @@ pub struct WorkspaceInfo {
     pub result_summaries: FxHashMap<String, Vec<ExecutionResultSummary>>,
     /// Execution detail results (if any)
     pub result_details: FxHashMap<String, Vec<ExecutionResultDetail>>,
+    /// Undo/redo history of edits
+    pub history: WorkspaceHistory,
+    /// Workspace as last loaded from or saved to disk, the base when merging external changes
+    pub base: Option<Workspace>,
+    /// Mock responses as last loaded from or saved to disk
+    pub base_mocks: FxHashMap<String, MockResponse>,
+    /// Workbook on disk when external changes were last merged, used to resolve conflicts
+    /// until the workspace is saved or reloaded
+    pub merge_theirs: Option<Workspace>,
+    /// Mock responses on disk when external changes were last merged
+    pub merge_theirs_mocks: FxHashMap<String, MockResponse>,
+    /// Request groups saved to their own files
+    pub includes: Vec<WorkbookInclude>,
+    /// Mock server responses, keyed by request ID
+    pub mocks: FxHashMap<String, MockResponse>,
+    /// Passphrase for the workbook's encrypted private parameters, set once they are
+    /// decrypted (or, if there are none yet, once a new passphrase is confirmed)
+    pub private_passphrase: Option<String>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -481,8 +519,20 @@ impl Workspaces {
                 executing_request_ids: HashSet::new(),
                 result_summaries: FxHashMap::default(),
                 result_details: FxHashMap::default(),
+                history: WorkspaceHistory::default(),
+                base: None,
+                base_mocks: FxHashMap::default(),
+                merge_theirs: None,
+                merge_theirs_mocks: FxHashMap::default(),
+                includes: Vec::new(),
+                mocks: if file_name.is_empty() {
+                    FxHashMap::default()
+                } else {
+                    mock::load_mocks(file_name)
+                },
                 file_name: file_name.to_string(),
                 display_name: display_name.clone(),
+                private_passphrase: None,
             },
         );
 
@@ -525,6 +575,15 @@ impl Workspaces {
         }
     }
 
+    /// Add an imported workspace, which is unsaved (dirty) until given a file name
+    pub fn add_imported_workspace(&mut self, workspace: Workspace) -> OpenWorkspaceResult {
+        let result = self.add_workspace(workspace, "", false);
+        if let Some(info) = self.workspaces.get_mut(&result.workspace_id) {
+            info.dirty = true;
+        }
+        result
+    }
+
     pub fn remove_workspace(&mut self, workspace_id: &str) {
         // log::trace!("Removing workspace {}", &workspace_id);
         self.workspaces.remove(workspace_id);
@@ -550,6 +609,23 @@ impl Workspaces {
             .collect()
     }
 
+    /// Return a redactor for secrets in the specified workspace
+    pub fn get_redactor(&self, workspace_id: &str) -> Result<Redactor, ApicizeAppError> {
+        Ok(Redactor::for_workspace(
+            &self.get_workspace_info(workspace_id)?.workspace,
+        ))
+    }
+
+    /// Return a redactor for secrets in every open workspace, used for output such as
+    /// network traces that is not specific to a workspace
+    pub fn get_all_redactor(&self) -> Redactor {
+        let mut redactor = Redactor::default();
+        for info in self.workspaces.values() {
+            redactor.add_workspace(&info.workspace);
+        }
+        redactor
+    }
+
     pub fn get_workspace_info(
         &self,
         workspace_id: &str,
@@ -591,6 +667,28 @@ impl Workspaces {
         }
     }
 
+    /// Undo the last edit, returning the ID of the affected entity (if any)
+    pub fn undo(&mut self, workspace_id: &str) -> Result<Option<String>, ApicizeAppError> {
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        match info.history.undo(&info.workspace, &info.mocks) {
+            Some(restore) => Ok(info.restore_from_history(restore)),
+            None => Err(ApicizeAppError::InvalidOperation(
+                "there is nothing to undo".to_string(),
+            )),
+        }
+    }
+
+    /// Redo the last undone edit, returning the ID of the affected entity (if any)
+    pub fn redo(&mut self, workspace_id: &str) -> Result<Option<String>, ApicizeAppError> {
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        match info.history.redo(&info.workspace, &info.mocks) {
+            Some(restore) => Ok(info.restore_from_history(restore)),
+            None => Err(ApicizeAppError::InvalidOperation(
+                "there is nothing to redo".to_string(),
+            )),
+        }
+    }
+
     pub fn get_workspace_mut(
         &mut self,
         workspace_id: &str,
@@ -665,6 +763,8 @@ impl Workspaces {
         clone_from_id: Option<&str>,
     ) -> Result<String, ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Add Request", None, None);
         info.dirty = true;
         let request = match clone_from_id {
             Some(other_id) => match info.workspace.requests.entities.get(other_id) {
@@ -684,6 +784,148 @@ impl Workspaces {
         Ok(id)
     }
 
+    /// Add a request parsed from a curl command, reusing (or adding) a matching
+    /// basic authorization and proxy
+    pub fn add_request_from_curl(
+        &mut self,
+        workspace_id: &str,
+        relative_to: Option<&str>,
+        relative_position: Option<IndexedEntityPosition>,
+        parsed: ParsedCurl,
+    ) -> Result<String, ApicizeAppError> {
+        let id = self.add_request(workspace_id, relative_to, relative_position, None)?;
+        let info = self.get_workspace_info_mut(workspace_id)?;
+
+        let mut request = parsed.request;
+        request.id = id.clone();
+
+        if let Some((username, password)) = parsed.basic_auth {
+            let existing = info
+                .workspace
+                .authorizations
+                .entities
+                .values()
+                .find(|a| match a {
+                    Authorization::Basic {
+                        username: u,
+                        password: p,
+                        ..
+                    } => *u == username && *p == password,
+                    _ => false,
+                });
+            let selection = match existing {
+                Some(authorization) => Selection {
+                    id: authorization.get_id().to_string(),
+                    name: authorization.get_name().to_string(),
+                },
+                None => {
+                    let selection = Selection {
+                        id: Uuid::new_v4().to_string(),
+                        name: format!("{username} (Basic)"),
+                    };
+                    let authorization = serde_json::from_value::<Authorization>(json!({
+                        "id": selection.id,
+                        "name": selection.name,
+                        "type": "Basic",
+                        "username": username,
+                        "password": password,
+                    }))?;
+                    info.workspace
+                        .authorizations
+                        .add_entity(authorization, None, None)?;
+                    selection
+                }
+            };
+            request.selected_authorization = Some(selection);
+        }
+
+        if let Some(url) = parsed.proxy {
+            let selection = match info
+                .workspace
+                .proxies
+                .entities
+                .values()
+                .find(|p| p.url == url)
+            {
+                Some(proxy) => Selection {
+                    id: proxy.id.clone(),
+                    name: proxy.name.clone(),
+                },
+                None => {
+                    let mut proxy = Proxy::default();
+                    proxy.name = url.clone();
+                    proxy.url = url;
+                    let selection = Selection {
+                        id: proxy.id.clone(),
+                        name: proxy.name.clone(),
+                    };
+                    info.workspace.proxies.add_entity(proxy, None, None)?;
+                    selection
+                }
+            };
+            request.selected_proxy = Some(selection);
+        }
+
+        info.workspace
+            .requests
+            .entities
+            .insert(id.clone(), RequestEntry::Request(request));
+        Ok(id)
+    }
+
+    /// Add groups of requests (i.e. from an imported file) as a single edit, returning
+    /// the IDs of the added groups
+    pub fn add_request_groups(
+        &mut self,
+        workspace_id: &str,
+        relative_to: Option<&str>,
+        mut relative_position: Option<IndexedEntityPosition>,
+        groups: Vec<(RequestGroup, Vec<Request>)>,
+    ) -> Result<Vec<String>, ApicizeAppError> {
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Import Requests", None, None);
+
+        let mut group_ids = Vec::<String>::with_capacity(groups.len());
+        let mut import = || -> Result<(), ApicizeAppError> {
+            for (group, requests) in groups {
+                let group_id = group.id.clone();
+                // Subsequent groups follow the previously added group
+                match group_ids.last() {
+                    Some(previous_id) => info.workspace.requests.add_entity(
+                        RequestEntry::Group(group),
+                        Some(previous_id.as_str()),
+                        Some(IndexedEntityPosition::After),
+                    )?,
+                    None => info.workspace.requests.add_entity(
+                        RequestEntry::Group(group),
+                        relative_to,
+                        relative_position.take(),
+                    )?,
+                };
+                for request in requests {
+                    info.workspace.requests.add_entity(
+                        RequestEntry::Request(request),
+                        Some(&group_id),
+                        Some(IndexedEntityPosition::Under),
+                    )?;
+                }
+                group_ids.push(group_id);
+            }
+            Ok(())
+        };
+
+        // A failed import leaves the workspace as it was
+        if let Err(err) = import() {
+            if let Some(workspace) = info.history.discard_last() {
+                info.workspace = workspace;
+            }
+            return Err(err);
+        }
+        info.dirty = true;
+        Ok(group_ids)
+    }
+
     pub fn add_request_group(
         &mut self,
         workspace_id: &str,
@@ -692,6 +934,8 @@ impl Workspaces {
         clone_from_id: Option<&str>,
     ) -> Result<String, ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Add Group", None, None);
         info.dirty = true;
 
         let group = match clone_from_id {
@@ -712,65 +956,7 @@ impl Workspaces {
         )?;
 
         if let Some(other_id) = clone_from_id {
-            // Pre-calculate approximate size by counting descendant nodes
-            let estimated_size = Self::count_descendant_nodes(&info.workspace.requests, other_id);
-            
-            // Pre-allocate collections with estimated capacity
-            let mut cloned_group_ids = FxHashMap::<String, String>::with_capacity_and_hasher(estimated_size / 2, Default::default());
-            cloned_group_ids.insert(other_id.to_string(), id.to_string());
-
-            let mut new_entries = Vec::<RequestEntry>::with_capacity(estimated_size);
-            let mut new_child_mappings = FxHashMap::<String, Vec<String>>::with_capacity_and_hasher(estimated_size / 2, Default::default());
-
-            // Use a single pass to collect all entries that need cloning
-            let mut to_process = VecDeque::with_capacity(estimated_size);
-            to_process.push_back(other_id.to_string());
-
-            let mut processed = HashSet::<String>::with_capacity(estimated_size);
-
-            while let Some(parent_id) = to_process.pop_front() {
-                if !processed.insert(parent_id.clone()) {
-                    continue; // Already processed
-                }
-
-                if let Some(child_ids) = info.workspace.requests.child_ids.get(&parent_id) {
-                    let new_group_id = cloned_group_ids
-                        .get(&parent_id)
-                        .expect("Parent group ID should exist in cloned_group_ids")
-                        .clone();
-                    
-                    // Pre-allocate child vector with known size
-                    let mut new_group_child_ids = Vec::with_capacity(child_ids.len());
-
-                    for child_id in child_ids {
-                        if let Some(child) = info.workspace.requests.get(child_id) {
-                            let cloned_child = child.clone_as_new(child.get_name().to_owned());
-                            let cloned_child_id = cloned_child.get_id().to_string();
-                            let is_group = matches!(&cloned_child, RequestEntry::Group(_));
-
-                            new_group_child_ids.push(cloned_child_id.clone());
-                            new_entries.push(cloned_child);
-
-                            if is_group {
-                                cloned_group_ids.insert(child_id.to_string(), cloned_child_id);
-                                to_process.push_back(child_id.clone());
-                            }
-                        }
-                    }
-
-                    new_child_mappings.insert(new_group_id, new_group_child_ids);
-                }
-            }
-
-            // Batch insert new entries - more efficient than individual inserts
-            info.workspace.requests.entities.reserve(new_entries.len());
-            for entry in new_entries {
-                info.workspace.requests.entities.insert(entry.get_id().to_string(), entry);
-            }
-
-            // Batch insert child mappings
-            info.workspace.requests.child_ids.reserve(new_child_mappings.len());
-            info.workspace.requests.child_ids.extend(new_child_mappings);
+            Self::clone_group_descendants(&mut info.workspace.requests, other_id, &id);
         }
         Ok(id)
     }
@@ -781,8 +967,17 @@ impl Workspaces {
         request_or_group_id: &str,
     ) -> Result<(), ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history.record(
+            &info.workspace,
+            "Delete Request",
+            Some(request_or_group_id),
+            None,
+        );
+        if let Err(err) = info.workspace.requests.remove_entity(request_or_group_id) {
+            info.history.discard_last();
+            return Err(err.into());
+        }
         info.dirty = true;
-        info.workspace.requests.remove_entity(request_or_group_id)?;
         info.workspace.validate_selections();
         Ok(())
     }
@@ -794,10 +989,231 @@ impl Workspaces {
         relative_to: &str,
         relative_position: IndexedEntityPosition,
     ) -> Result<bool, ApicizeAppError> {
-        let workspace = self.get_workspace_mut(workspace_id)?;
-        Ok(workspace
-            .requests
-            .move_entity(request_or_group_id, relative_to, relative_position)?)
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history.record(
+            &info.workspace,
+            "Move Request",
+            Some(request_or_group_id),
+            None,
+        );
+        let result = info.workspace.requests.move_entity(
+            request_or_group_id,
+            relative_to,
+            relative_position,
+        );
+        let moved = match result {
+            Ok(true) => true,
+            Ok(false) => {
+                info.history.discard_last();
+                false
+            }
+            Err(err) => {
+                info.history.discard_last();
+                return Err(err.into());
+            }
+        };
+        Ok(moved)
+    }
+
+    /// Delete multiple requests and groups as a single undoable change
+    pub fn delete_request_entries(
+        &mut self,
+        workspace_id: &str,
+        request_or_group_ids: &[String],
+    ) -> Result<(), ApicizeAppError> {
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        let ids =
+            Self::get_selected_request_entry_ids(&info.workspace.requests, request_or_group_ids)?;
+        info.history
+            .record(&info.workspace, "Delete Requests", None, None);
+        for id in &ids {
+            if let Err(err) = info.workspace.requests.remove_entity(id) {
+                if let Some(workspace) = info.history.discard_last() {
+                    info.workspace = workspace;
+                }
+                return Err(err.into());
+            }
+        }
+        info.dirty = true;
+        info.workspace.validate_selections();
+        Ok(())
+    }
+
+    /// Move multiple requests and groups, preserving their relative order, as a
+    /// single undoable change.  Returns the IDs of entries that were moved
+    pub fn move_request_entries(
+        &mut self,
+        workspace_id: &str,
+        request_or_group_ids: &[String],
+        relative_to: &str,
+        relative_position: IndexedEntityPosition,
+    ) -> Result<Vec<String>, ApicizeAppError> {
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        let ids =
+            Self::get_selected_request_entry_ids(&info.workspace.requests, request_or_group_ids)?;
+        if ids.iter().any(|id| id == relative_to) {
+            return Err(ApicizeAppError::InvalidOperation(
+                "Unable to move entries relative to themselves".to_string(),
+            ));
+        }
+
+        info.history
+            .record(&info.workspace, "Move Requests", None, None);
+
+        let mut moved_ids = Vec::<String>::with_capacity(ids.len());
+        let mut anchor_id = relative_to.to_string();
+        let mut position = relative_position;
+        for id in ids {
+            let result = info
+                .workspace
+                .requests
+                .move_entity(&id, &anchor_id, position);
+            match result {
+                Ok(true) => moved_ids.push(id.clone()),
+                Ok(false) => {}
+                Err(err) => {
+                    if let Some(workspace) = info.history.discard_last() {
+                        info.workspace = workspace;
+                    }
+                    return Err(err.into());
+                }
+            }
+
+            // Subsequent entries follow this one, whether it was moved or already in place
+            anchor_id = id;
+            position = IndexedEntityPosition::After;
+        }
+
+        if moved_ids.is_empty() {
+            info.history.discard_last();
+        } else {
+            info.dirty = true;
+        }
+        Ok(moved_ids)
+    }
+
+    /// Duplicate multiple requests and groups (including group descendants), placing
+    /// each copy after its original, as a single undoable change.  Returns the new IDs
+    pub fn duplicate_request_entries(
+        &mut self,
+        workspace_id: &str,
+        request_or_group_ids: &[String],
+    ) -> Result<Vec<String>, ApicizeAppError> {
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        let ids =
+            Self::get_selected_request_entry_ids(&info.workspace.requests, request_or_group_ids)?;
+        info.history
+            .record(&info.workspace, "Duplicate Requests", None, None);
+
+        let mut new_ids = Vec::<String>::with_capacity(ids.len());
+        let mut duplicate = || -> Result<(), ApicizeAppError> {
+            for id in ids {
+                let copy = match info.workspace.requests.entities.get(&id) {
+                    Some(entry) => entry.clone_as_new(Self::create_copy_name(entry.get_name())),
+                    None => return Err(ApicizeAppError::InvalidRequest(id)),
+                };
+                let new_id = copy.get_id().to_string();
+                let is_group = matches!(&copy, RequestEntry::Group(_));
+                info.workspace.requests.add_entity(
+                    copy,
+                    Some(&id),
+                    Some(IndexedEntityPosition::After),
+                )?;
+                if is_group {
+                    Self::clone_group_descendants(&mut info.workspace.requests, &id, &new_id);
+                }
+                new_ids.push(new_id);
+            }
+            Ok(())
+        };
+
+        // A failed duplication leaves the workspace as it was
+        if let Err(err) = duplicate() {
+            if let Some(workspace) = info.history.discard_last() {
+                info.workspace = workspace;
+            }
+            return Err(err);
+        }
+        info.dirty = true;
+        Ok(new_ids)
+    }
+
+    /// Assign a scenario, authorization, certificate or proxy to multiple requests
+    /// and groups as a single undoable change
+    pub fn assign_request_entries_parameter(
+        &mut self,
+        workspace_id: &str,
+        request_or_group_ids: &[String],
+        entity_type: EntityType,
+        selection: Option<Selection>,
+    ) -> Result<(), ApicizeAppError> {
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        for id in request_or_group_ids {
+            if !info.workspace.requests.entities.contains_key(id) {
+                return Err(ApicizeAppError::InvalidRequest(id.clone()));
+            }
+        }
+
+        if let Some(selected_id) = selection
+            .as_ref()
+            .map(|s| s.id.as_str())
+            .filter(|id| *id != NO_SELECTION_ID)
+        {
+            let workspace = &info.workspace;
+            match entity_type {
+                EntityType::Scenario if !workspace.scenarios.entities.contains_key(selected_id) => {
+                    return Err(ApicizeAppError::InvalidScenario(selected_id.into()))
+                }
+                EntityType::Authorization
+                    if !workspace.authorizations.entities.contains_key(selected_id) =>
+                {
+                    return Err(ApicizeAppError::InvalidAuthorization(selected_id.into()))
+                }
+                EntityType::Certificate
+                    if !workspace.certificates.entities.contains_key(selected_id) =>
+                {
+                    return Err(ApicizeAppError::InvalidCertificate(selected_id.into()))
+                }
+                EntityType::Proxy if !workspace.proxies.entities.contains_key(selected_id) => {
+                    return Err(ApicizeAppError::InvalidProxy(selected_id.into()))
+                }
+                EntityType::Scenario
+                | EntityType::Authorization
+                | EntityType::Certificate
+                | EntityType::Proxy => {}
+                _ => {
+                    return Err(ApicizeAppError::InvalidOperation(format!(
+                        "Unable to assign {entity_type}",
+                    )))
+                }
+            }
+        }
+
+        info.history
+            .record(&info.workspace, "Assign Parameters", None, None);
+        info.dirty = true;
+
+        for id in request_or_group_ids {
+            let selection = selection.clone();
+            match info.workspace.requests.entities.get_mut(id) {
+                Some(RequestEntry::Request(request)) => match entity_type {
+                    EntityType::Scenario => request.selected_scenario = selection,
+                    EntityType::Authorization => request.selected_authorization = selection,
+                    EntityType::Certificate => request.selected_certificate = selection,
+                    EntityType::Proxy => request.selected_proxy = selection,
+                    _ => {}
+                },
+                Some(RequestEntry::Group(group)) => match entity_type {
+                    EntityType::Scenario => group.selected_scenario = selection,
+                    EntityType::Authorization => group.selected_authorization = selection,
+                    EntityType::Certificate => group.selected_certificate = selection,
+                    EntityType::Proxy => group.selected_proxy = selection,
+                    _ => {}
+                },
+                None => {}
+            }
+        }
+        Ok(())
     }
 
     pub fn update_request(
@@ -806,6 +1222,12 @@ impl Workspaces {
         request: RequestInfo,
     ) -> Result<Option<UpdatedNavigationEntry>, ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history.record(
+            &info.workspace,
+            "Edit Request",
+            Some(&request.id),
+            Some(&request.id),
+        );
         info.dirty = true;
 
         let (name, state) = match info.workspace.requests.entities.get_mut(&request.id) {
@@ -872,6 +1294,12 @@ impl Workspaces {
     ) -> Result<Option<UpdatedNavigationEntry>, ApicizeAppError> {
         match self.workspaces.get_mut(workspace_id) {
             Some(info) => {
+                info.history.record(
+                    &info.workspace,
+                    "Edit Group",
+                    Some(&group.id),
+                    Some(&group.id),
+                );
                 info.dirty = true;
                 let id = &group.id;
                 let result = info.check_request_navigation_update(
@@ -1064,6 +1492,202 @@ impl Workspaces {
         Ok(result)
     }
 
+    pub fn get_mock(
+        &self,
+        workspace_id: &str,
+        request_id: &str,
+    ) -> Result<Option<MockResponse>, ApicizeAppError> {
+        let info = self.get_workspace_info(workspace_id)?;
+        match info.workspace.requests.entities.get(request_id) {
+            Some(RequestEntry::Request(_)) => Ok(info.mocks.get(request_id).cloned()),
+            _ => Err(ApicizeAppError::InvalidRequest(request_id.into())),
+        }
+    }
+
+    /// Set (or clear) the mock server response for a request
+    pub fn update_mock(
+        &mut self,
+        workspace_id: &str,
+        request_id: &str,
+        mock: Option<MockResponse>,
+    ) -> Result<(), ApicizeAppError> {
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        if !matches!(
+            info.workspace.requests.entities.get(request_id),
+            Some(RequestEntry::Request(_))
+        ) {
+            return Err(ApicizeAppError::InvalidRequest(request_id.into()));
+        }
+        if info.mocks.get(request_id) == mock.as_ref() {
+            return Ok(());
+        }
+
+        info.history.record_with_mocks(
+            &info.workspace,
+            &info.mocks,
+            "Edit Mock",
+            Some(request_id),
+            Some(&format!("mock:{request_id}")),
+        );
+        match mock {
+            Some(mock) => {
+                info.mocks.insert(request_id.to_string(), mock);
+            }
+            None => {
+                info.mocks.remove(request_id);
+            }
+        }
+        info.dirty = true;
+        Ok(())
+    }
+
+    /// Search request, group, scenario and authorization text in the workspace
+    pub fn search(
+        &self,
+        workspace_id: &str,
+        options: &SearchOptions,
+        redactor: Option<Redactor>,
+    ) -> Result<Vec<SearchResult>, ApicizeAppError> {
+        let workspace = self.get_workspace(workspace_id)?;
+        Ok(search::search(workspace, options, redactor))
+    }
+
+    /// Return the changes a replace operation would make, without applying them
+    pub fn preview_replace(
+        &self,
+        workspace_id: &str,
+        options: &ReplaceOptions,
+    ) -> Result<Vec<ReplacePreview>, ApicizeAppError> {
+        let workspace = self.get_workspace(workspace_id)?;
+        Ok(replace::plan(workspace, options)?.previews)
+    }
+
+    /// Apply a replace operation as a single undoable change, returning the changes made
+    pub fn apply_replace(
+        &mut self,
+        workspace_id: &str,
+        options: &ReplaceOptions,
+    ) -> Result<Vec<ReplacePreview>, ApicizeAppError> {
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        let plan = replace::plan(&info.workspace, options)?;
+        if plan.requests.is_empty() {
+            return Ok(plan.previews);
+        }
+
+        info.history
+            .record(&info.workspace, "Replace Text", None, None);
+        info.dirty = true;
+
+        for request in plan.requests {
+            info.workspace
+                .requests
+                .entities
+                .insert(request.id.clone(), RequestEntry::Request(request));
+        }
+        Ok(plan.previews)
+    }
+
+    /// Return the request along with the scenario, authorization, certificate and proxy
+    /// in effect for it, whether selected on the request, a parent group or the workspace
+    pub fn get_resolved_request(
+        &self,
+        workspace_id: &str,
+        request_id: &str,
+    ) -> Result<ResolvedRequest, ApicizeAppError> {
+        let workspace = self.get_workspace(workspace_id)?;
+        let request = match workspace.requests.entities.get(request_id) {
+            Some(RequestEntry::Request(request)) => request.clone(),
+            _ => return Err(ApicizeAppError::InvalidRequest(request_id.into())),
+        };
+
+        let scenario = match Self::get_active_selection(
+            workspace,
+            request_id,
+            |e| e.selected_scenario(),
+            workspace.defaults.selected_scenario.as_ref(),
+        ) {
+            Some(selection) => match workspace.scenarios.get(&selection.id) {
+                Some(scenario) => Some(scenario.clone()),
+                None => return Err(ApicizeAppError::InvalidScenario(selection.id.to_owned())),
+            },
+            None => None,
+        };
+
+        let authorization = match Self::get_active_selection(
+            workspace,
+            request_id,
+            |e| e.selected_authorization(),
+            workspace.defaults.selected_authorization.as_ref(),
+        ) {
+            Some(selection) => match workspace.authorizations.get(&selection.id) {
+                Some(authorization) => Some(authorization.clone()),
+                None => {
+                    return Err(ApicizeAppError::InvalidAuthorization(
+                        selection.id.to_owned(),
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        let certificate = match Self::get_active_selection(
+            workspace,
+            request_id,
+            |e| e.selected_certificate(),
+            workspace.defaults.selected_certificate.as_ref(),
+        ) {
+            Some(selection) => match workspace.certificates.get(&selection.id) {
+                Some(certificate) => Some(certificate.clone()),
+                None => return Err(ApicizeAppError::InvalidCertificate(selection.id.to_owned())),
+            },
+            None => None,
+        };
+
+        let proxy = match Self::get_active_selection(
+            workspace,
+            request_id,
+            |e| e.selected_proxy(),
+            workspace.defaults.selected_proxy.as_ref(),
+        ) {
+            Some(selection) => match workspace.proxies.get(&selection.id) {
+                Some(proxy) => Some(proxy.clone()),
+                None => return Err(ApicizeAppError::InvalidProxy(selection.id.to_owned())),
+            },
+            None => None,
+        };
+
+        Ok(ResolvedRequest {
+            request,
+            scenario,
+            authorization,
+            certificate,
+            proxy,
+        })
+    }
+
+    /// Return the selection closest to the request (walking up through parent groups),
+    /// falling back to the workspace default, or None if the selection is turned off
+    fn get_active_selection<'a>(
+        workspace: &'a Workspace,
+        request_id: &str,
+        get_selection: impl Fn(&'a RequestEntry) -> Option<&'a Selection>,
+        default: Option<&'a Selection>,
+    ) -> Option<&'a Selection> {
+        let mut id_to_check = request_id.to_string();
+        let mut result = default;
+        while let Some(entry) = workspace.requests.entities.get(&id_to_check) {
+            if let Some(selection) = get_selection(entry) {
+                result = Some(selection);
+                break;
+            }
+            match Self::get_request_parent_id(entry.get_id(), workspace) {
+                Some(parent_id) => id_to_check = parent_id,
+                None => break,
+            }
+        }
+        result.filter(|s| s.id != NO_SELECTION_ID)
+    }
+
     /// Update request headers and return reference to request info so it can be resent
     pub fn update_request_headers(
         &mut self,
@@ -1072,6 +1696,12 @@ impl Workspaces {
     ) -> Result<RequestInfo, ApicizeAppError> {
         match self.workspaces.get_mut(workspace_id) {
             Some(info) => {
+                info.history.record(
+                    &info.workspace,
+                    "Edit Request Headers",
+                    Some(&header_info.id),
+                    Some(&format!("headers:{}", header_info.id)),
+                );
                 info.dirty = true;
                 let id = &header_info.id;
                 if let Some(RequestEntry::Request(existing_request)) =
@@ -1095,6 +1725,12 @@ impl Workspaces {
     ) -> Result<RequestInfo, ApicizeAppError> {
         match self.workspaces.get_mut(workspace_id) {
             Some(info) => {
+                info.history.record(
+                    &info.workspace,
+                    "Edit Request Body",
+                    Some(&body_info.id),
+                    Some(&format!("body:{}", body_info.id)),
+                );
                 info.dirty = true;
                 let id = &body_info.id;
                 if let Some(RequestEntry::Request(existing_request)) =
@@ -1234,6 +1870,8 @@ impl Workspaces {
         clone_from_id: Option<&str>,
     ) -> Result<String, ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Add Scenario", None, None);
         info.dirty = true;
         let scenario = match clone_from_id {
             Some(other_id) => match info.workspace.scenarios.get(other_id) {
@@ -1255,8 +1893,13 @@ impl Workspaces {
         scenario_id: &str,
     ) -> Result<(), ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Delete Scenario", Some(scenario_id), None);
+        if let Err(err) = info.workspace.scenarios.remove_entity(scenario_id) {
+            info.history.discard_last();
+            return Err(err.into());
+        }
         info.dirty = true;
-        info.workspace.scenarios.remove_entity(scenario_id)?;
         info.workspace.validate_selections();
         Ok(())
     }
@@ -1268,10 +1911,25 @@ impl Workspaces {
         relative_to: &str,
         relative_position: IndexedEntityPosition,
     ) -> Result<bool, ApicizeAppError> {
-        let workspace = self.get_workspace_mut(workspace_id)?;
-        Ok(workspace
-            .scenarios
-            .move_entity(scenario_id, relative_to, relative_position)?)
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Move Scenario", Some(scenario_id), None);
+        let result =
+            info.workspace
+                .scenarios
+                .move_entity(scenario_id, relative_to, relative_position);
+        let moved = match result {
+            Ok(true) => true,
+            Ok(false) => {
+                info.history.discard_last();
+                false
+            }
+            Err(err) => {
+                info.history.discard_last();
+                return Err(err.into());
+            }
+        };
+        Ok(moved)
     }
 
     pub fn update_scenario(
@@ -1281,8 +1939,10 @@ impl Workspaces {
     ) -> Result<Option<UpdatedNavigationEntry>, ApicizeAppError> {
         match self.workspaces.get_mut(workspace_id) {
             Some(info) => {
-                info.dirty = true;
                 let id = scenario.get_id();
+                info.history
+                    .record(&info.workspace, "Edit Scenario", Some(id), Some(id));
+                info.dirty = true;
                 let result =
                     info.check_parameter_navigation_update(&scenario, EntityType::Scenario);
 
@@ -1345,6 +2005,8 @@ impl Workspaces {
         clone_from_id: Option<&str>,
     ) -> Result<String, ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Add Authorization", None, None);
         info.dirty = true;
         let authorization = match clone_from_id {
             Some(other_id) => match info.workspace.authorizations.get(other_id) {
@@ -1366,10 +2028,21 @@ impl Workspaces {
         authorization_id: &str,
     ) -> Result<(), ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
-        info.dirty = true;
-        info.workspace
+        info.history.record(
+            &info.workspace,
+            "Delete Authorization",
+            Some(authorization_id),
+            None,
+        );
+        if let Err(err) = info
+            .workspace
             .authorizations
-            .remove_entity(authorization_id)?;
+            .remove_entity(authorization_id)
+        {
+            info.history.discard_last();
+            return Err(err.into());
+        }
+        info.dirty = true;
 
         Ok(())
     }
@@ -1381,12 +2054,30 @@ impl Workspaces {
         relative_to: &str,
         relative_position: IndexedEntityPosition,
     ) -> Result<bool, ApicizeAppError> {
-        let workspace = self.get_workspace_mut(workspace_id)?;
-        Ok(workspace.authorizations.move_entity(
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history.record(
+            &info.workspace,
+            "Move Authorization",
+            Some(authorization_id),
+            None,
+        );
+        let result = info.workspace.authorizations.move_entity(
             authorization_id,
             relative_to,
             relative_position,
-        )?)
+        );
+        let moved = match result {
+            Ok(true) => true,
+            Ok(false) => {
+                info.history.discard_last();
+                false
+            }
+            Err(err) => {
+                info.history.discard_last();
+                return Err(err.into());
+            }
+        };
+        Ok(moved)
     }
 
     pub fn update_authorization(
@@ -1396,8 +2087,10 @@ impl Workspaces {
     ) -> Result<Option<UpdatedNavigationEntry>, ApicizeAppError> {
         match self.workspaces.get_mut(workspace_id) {
             Some(info) => {
-                info.dirty = true;
                 let id = authorization.get_id();
+                info.history
+                    .record(&info.workspace, "Edit Authorization", Some(id), Some(id));
+                info.dirty = true;
                 let result = info
                     .check_parameter_navigation_update(&authorization, EntityType::Authorization);
 
@@ -1481,6 +2174,8 @@ impl Workspaces {
         clone_from_id: Option<&str>,
     ) -> Result<String, ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Add Certificate", None, None);
         info.dirty = true;
         let certificate = match clone_from_id {
             Some(other_id) => match info.workspace.certificates.get(other_id) {
@@ -1502,8 +2197,17 @@ impl Workspaces {
         certificate_id: &str,
     ) -> Result<(), ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history.record(
+            &info.workspace,
+            "Delete Certificate",
+            Some(certificate_id),
+            None,
+        );
+        if let Err(err) = info.workspace.certificates.remove_entity(certificate_id) {
+            info.history.discard_last();
+            return Err(err.into());
+        }
         info.dirty = true;
-        info.workspace.certificates.remove_entity(certificate_id)?;
         info.workspace.validate_selections();
         Ok(())
     }
@@ -1515,10 +2219,29 @@ impl Workspaces {
         relative_to: &str,
         relative_position: IndexedEntityPosition,
     ) -> Result<bool, ApicizeAppError> {
-        let workspace = self.get_workspace_mut(workspace_id)?;
-        Ok(workspace
-            .certificates
-            .move_entity(certificate_id, relative_to, relative_position)?)
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history.record(
+            &info.workspace,
+            "Move Certificate",
+            Some(certificate_id),
+            None,
+        );
+        let result =
+            info.workspace
+                .certificates
+                .move_entity(certificate_id, relative_to, relative_position);
+        let moved = match result {
+            Ok(true) => true,
+            Ok(false) => {
+                info.history.discard_last();
+                false
+            }
+            Err(err) => {
+                info.history.discard_last();
+                return Err(err.into());
+            }
+        };
+        Ok(moved)
     }
 
     pub fn update_certificate(
@@ -1528,8 +2251,10 @@ impl Workspaces {
     ) -> Result<Option<UpdatedNavigationEntry>, ApicizeAppError> {
         match self.workspaces.get_mut(workspace_id) {
             Some(info) => {
-                info.dirty = true;
                 let id = certificate.get_id();
+                info.history
+                    .record(&info.workspace, "Edit Certificate", Some(id), Some(id));
+                info.dirty = true;
                 let result =
                     info.check_parameter_navigation_update(&certificate, EntityType::Certificate);
 
@@ -1597,6 +2322,8 @@ impl Workspaces {
         clone_from_id: Option<&str>,
     ) -> Result<String, ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Add Proxy", None, None);
         info.dirty = true;
         let proxy = match clone_from_id {
             Some(other_id) => match info.workspace.proxies.get(other_id) {
@@ -1618,8 +2345,13 @@ impl Workspaces {
         proxy_id: &str,
     ) -> Result<(), ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Delete Proxy", Some(proxy_id), None);
+        if let Err(err) = info.workspace.proxies.remove_entity(proxy_id) {
+            info.history.discard_last();
+            return Err(err.into());
+        }
         info.dirty = true;
-        info.workspace.proxies.remove_entity(proxy_id)?;
         info.workspace.validate_selections();
         Ok(())
     }
@@ -1631,11 +2363,25 @@ impl Workspaces {
         relative_to: &str,
         relative_position: IndexedEntityPosition,
     ) -> Result<bool, ApicizeAppError> {
-        let workspace = self.get_workspace_mut(workspace_id)?;
-        let result = workspace
+        let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Move Proxy", Some(proxy_id), None);
+        let result = info
+            .workspace
             .proxies
-            .move_entity(proxy_id, relative_to, relative_position)?;
-        Ok(result)
+            .move_entity(proxy_id, relative_to, relative_position);
+        let moved = match result {
+            Ok(true) => true,
+            Ok(false) => {
+                info.history.discard_last();
+                false
+            }
+            Err(err) => {
+                info.history.discard_last();
+                return Err(err.into());
+            }
+        };
+        Ok(moved)
     }
 
     pub fn update_proxy(
@@ -1645,8 +2391,10 @@ impl Workspaces {
     ) -> Result<Option<UpdatedNavigationEntry>, ApicizeAppError> {
         match self.workspaces.get_mut(workspace_id) {
             Some(info) => {
-                info.dirty = true;
                 let id = proxy.get_id();
+                info.history
+                    .record(&info.workspace, "Edit Proxy", Some(id), Some(id));
+                info.dirty = true;
                 let result = info.check_parameter_navigation_update(&proxy, EntityType::Proxy);
 
                 for request_entry in info.workspace.requests.entities.values_mut() {
@@ -1691,6 +2439,8 @@ impl Workspaces {
         defaults: WorkbookDefaultParameters,
     ) -> Result<(), ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Edit Defaults", None, Some("defaults"));
         info.dirty = true;
         info.workspace.defaults = defaults.clone();
         info.workspace.validate_selections();
@@ -1732,6 +2482,105 @@ impl Workspaces {
             })
     }
 
+    /// Validate and return selected request and group IDs in navigation order, omitting
+    /// entries whose parent group is also selected
+    fn get_selected_request_entry_ids(
+        requests: &IndexedEntities<RequestEntry>,
+        request_or_group_ids: &[String],
+    ) -> Result<Vec<String>, ApicizeAppError> {
+        let selected = request_or_group_ids
+            .iter()
+            .map(|id| id.as_str())
+            .collect::<HashSet<&str>>();
+        for id in &selected {
+            if !requests.entities.contains_key(*id) {
+                return Err(ApicizeAppError::InvalidRequest(id.to_string()));
+            }
+        }
+
+        let mut results = Vec::<String>::with_capacity(selected.len());
+        let mut to_check = requests
+            .top_level_ids
+            .iter()
+            .rev()
+            .collect::<Vec<&String>>();
+        while let Some(id) = to_check.pop() {
+            if selected.contains(id.as_str()) {
+                results.push(id.clone());
+            } else if let Some(child_ids) = requests.child_ids.get(id) {
+                to_check.extend(child_ids.iter().rev());
+            }
+        }
+        Ok(results)
+    }
+
+    /// Clone the descendants of a group into a newly added group
+    fn clone_group_descendants(
+        requests: &mut IndexedEntities<RequestEntry>,
+        source_id: &str,
+        new_id: &str,
+    ) {
+        // Pre-calculate approximate size by counting descendant nodes
+        let estimated_size = Self::count_descendant_nodes(requests, source_id);
+        
+        // Pre-allocate collections with estimated capacity
+        let mut cloned_group_ids = FxHashMap::<String, String>::with_capacity_and_hasher(estimated_size / 2, Default::default());
+        cloned_group_ids.insert(source_id.to_string(), new_id.to_string());
+
+        let mut new_entries = Vec::<RequestEntry>::with_capacity(estimated_size);
+        let mut new_child_mappings = FxHashMap::<String, Vec<String>>::with_capacity_and_hasher(estimated_size / 2, Default::default());
+
+        // Use a single pass to collect all entries that need cloning
+        let mut to_process = VecDeque::with_capacity(estimated_size);
+        to_process.push_back(source_id.to_string());
+
+        let mut processed = HashSet::<String>::with_capacity(estimated_size);
+
+        while let Some(parent_id) = to_process.pop_front() {
+            if !processed.insert(parent_id.clone()) {
+                continue; // Already processed
+            }
+
+            if let Some(child_ids) = requests.child_ids.get(&parent_id) {
+                let new_group_id = cloned_group_ids
+                    .get(&parent_id)
+                    .expect("Parent group ID should exist in cloned_group_ids")
+                    .clone();
+                
+                // Pre-allocate child vector with known size
+                let mut new_group_child_ids = Vec::with_capacity(child_ids.len());
+
+                for child_id in child_ids {
+                    if let Some(child) = requests.get(child_id) {
+                        let cloned_child = child.clone_as_new(child.get_name().to_owned());
+                        let cloned_child_id = cloned_child.get_id().to_string();
+                        let is_group = matches!(&cloned_child, RequestEntry::Group(_));
+
+                        new_group_child_ids.push(cloned_child_id.clone());
+                        new_entries.push(cloned_child);
+
+                        if is_group {
+                            cloned_group_ids.insert(child_id.to_string(), cloned_child_id);
+                            to_process.push_back(child_id.clone());
+                        }
+                    }
+                }
+
+                new_child_mappings.insert(new_group_id, new_group_child_ids);
+            }
+        }
+
+        // Batch insert new entries - more efficient than individual inserts
+        requests.entities.reserve(new_entries.len());
+        for entry in new_entries {
+            requests.entities.insert(entry.get_id().to_string(), entry);
+        }
+
+        // Batch insert child mappings
+        requests.child_ids.reserve(new_child_mappings.len());
+        requests.child_ids.extend(new_child_mappings);
+    }
+
     /// Count descendant nodes for capacity estimation
     fn count_descendant_nodes(requests: &IndexedEntities<RequestEntry>, root_id: &str) -> usize {
         let mut count = 0;
@@ -1770,6 +2619,7 @@ impl Workspaces {
         clone_from_id: Option<&str>,
     ) -> Result<String, ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history.record(&info.workspace, "Add Data", None, None);
         info.dirty = true;
         let data = match clone_from_id {
             Some(other_id) => match info.workspace.data.iter().find(|data| data.id == other_id) {
@@ -1789,6 +2639,8 @@ impl Workspaces {
         data_id: &str,
     ) -> Result<(), ApicizeAppError> {
         let info = self.get_workspace_info_mut(workspace_id)?;
+        info.history
+            .record(&info.workspace, "Delete Data", Some(data_id), None);
         info.dirty = true;
         if let Some(index) = info.workspace.data.iter().position(|d| d.id == data_id) {
             info.workspace.data.remove(index);
@@ -1804,6 +2656,8 @@ impl Workspaces {
     ) -> Result<Option<UpdatedNavigationEntry>, ApicizeAppError> {
         match self.workspaces.get_mut(workspace_id) {
             Some(info) => {
+                info.history
+                    .record(&info.workspace, "Edit Data", Some(&data.id), Some(&data.id));
                 info.dirty = true;
 
                 for request_entry in info.workspace.requests.entities.values_mut() {
@@ -2075,6 +2929,18 @@ impl Workspaces {
 }
 
 impl WorkspaceInfo {
+    /// Replace the workspace (and mocks, if recorded) with a snapshot from history and
+    /// rebuild navigation
+    fn restore_from_history(&mut self, restore: HistoryRestore) -> Option<String> {
+        self.workspace = restore.workspace;
+        if let Some(mocks) = restore.mocks {
+            self.mocks = mocks;
+        }
+        self.navigation = Navigation::new(&self.workspace, &self.executing_request_ids);
+        self.dirty = true;
+        restore.entity_id
+    }
+
     // Check parameter and returns update to navigation if required
     pub fn check_parameter_navigation_update<T: Identifiable + Warnings + ValidationErrors>(
         &mut self,
@@ -83,7 +83,7 @@ const workspaceStore = new WorkspaceStore(
       relativeToId,
       relativePosition,
     }),
-    listLogs: () => core.invoke('list_logs'),
+    listLogs: () => core.invoke('list_logs', { sessionId }),
     clearLogs: () => core.invoke('clear_logs'),
     storeToken: (authorizationId, tokenInfo) => core.invoke('store_token', {
       authorizationId,
@@ -196,9 +196,9 @@ export default function Home() {
             <FileOperationsProvider activeSessionId={sessionId} workspaceStore={workspaceStore}>
               <WorkspaceProvider store={workspaceStore}>
                 <DragDropProvider>
-                  <FileDragDropProvider>
+                  <FileDragDropProvider activeSessionId={sessionId}>
                     <PkceProvider store={workspaceStore}>
-                      <ClipboardProvider>
+                      <ClipboardProvider sessionId={sessionId}>
                         <MainPanel />
                       </ClipboardProvider>
                     </PkceProvider>
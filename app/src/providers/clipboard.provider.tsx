@@ -1,13 +1,16 @@
 import { ReactNode, useEffect, useMemo } from "react";
-import { hasImage, hasText, readText, readImageBase64, writeImageBase64, writeText, onClipboardUpdate, writeImageBinary, readImageBinary } from "tauri-plugin-clipboard-api"
+import * as core from '@tauri-apps/api/core'
+import { hasImage, hasText, readText, readImageBase64, writeImageBase64, onClipboardUpdate, writeImageBinary, readImageBinary } from "tauri-plugin-clipboard-api"
 import { ClipboardContext, ClipboardStore, ToastSeverity, useFeedback } from "@apicize/toolkit";
 
 /**
  * Implementation of clipboard operations via Tauri
  */
 export function ClipboardProvider({
+    sessionId,
     children
 }: {
+    sessionId: string,
     children?: ReactNode
 }) {
     const feedback = useFeedback()
@@ -16,7 +19,8 @@ export function ClipboardProvider({
         () => new ClipboardStore({
             onWriteText: async (text: string) => {
                 try {
-                    await writeText(text)
+                    // Text is written by the backend so that secrets can be masked
+                    await core.invoke('write_clipboard_text', { sessionId, text })
                     feedback.toast('Text copied to clipboard', ToastSeverity.Success)
                 } catch (e) {
                     feedback.toast(`${e}`, ToastSeverity.Error)
@@ -37,7 +41,7 @@ export function ClipboardProvider({
                 return (await readImageBinary("Uint8Array")) as Uint8Array
             },
         }),
-        [feedback]
+        [feedback, sessionId]
     )
 
     useEffect(() => {
@@ -9,8 +9,10 @@ import { DroppedFile } from "@apicize/toolkit/dist/contexts/file-dragdrop.contex
  * Implementation of file drag/drop operations via Tauri
  */
 export function FileDragDropProvider({
+    activeSessionId,
     children
 }: {
+    activeSessionId: string,
     children?: ReactNode
 }) {
     const feedback = useFeedback()
@@ -34,7 +36,19 @@ export function FileDragDropProvider({
                     break
                 case 'drop':
                     core.invoke<DroppedFile>('get_clipboard_file_data', { paths: e.payload.paths }).then(
-                        (f: DroppedFile) => store.onDrop(f)
+                        async (f: DroppedFile) => {
+                            if (f.type === 'har') {
+                                const warnings = await core.invoke<string[]>('add_requests_from_har', {
+                                    sessionId: activeSessionId,
+                                    data: f.data
+                                })
+                                if (warnings.length > 0) {
+                                    feedback.toast(warnings.join('; '), ToastSeverity.Warning)
+                                }
+                            } else {
+                                store.onDrop(f)
+                            }
+                        }
                     ).catch((e) => {
                         feedback.toast(`${e}`, ToastSeverity.Error)
                     })
@@ -45,7 +59,7 @@ export function FileDragDropProvider({
         return () => {
             unlisten.then(() => { })
         }
-    }, [store, feedback])
+    }, [store, feedback, activeSessionId])
 
     return (
         <FileDragDropContext.Provider value={store}>
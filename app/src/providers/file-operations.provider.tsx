@@ -54,6 +54,13 @@ export function FileOperationsProvider({ activeSessionId, workspaceStore, childr
             editorIndentSize: apicizeSettings.editorIndentSize,
             editorDetectExistingIndent: apicizeSettings.editorDetectExistingIndent,
             editorCheckJsSyntax: apicizeSettings.editorCheckJsSyntax,
+            traceRetention: apicizeSettings.traceRetention,
+            traceSpool: apicizeSettings.traceSpool,
+            traceSpoolDays: apicizeSettings.traceSpoolDays,
+            secretStorage: apicizeSettings.secretStorage,
+            encryptPrivateParameters: apicizeSettings.encryptPrivateParameters,
+            canonicalWorkbookFormat: apicizeSettings.canonicalWorkbookFormat,
+            splitMultilineStrings: apicizeSettings.splitMultilineStrings,
         }
         core.invoke<ApicizeSettings>('save_settings', { updatedSettings })
             .catch(e => {
@@ -1,11 +1,12 @@
 import { LogContext, LogStore, ReqwestEvent } from "@apicize/toolkit"
 import React, { useEffect } from "react"
 import { ReactNode } from "react"
-import { listen } from "@tauri-apps/api/event"
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow"
 
 export function LogProvider({ store, children }: { store: LogStore, children?: ReactNode }) {
     useEffect(() => {
-        const unlistenLog = listen<ReqwestEvent>('log', (event) => {
+        // Events are sent to each window separately, with secrets masked unless revealed
+        const unlistenLog = getCurrentWebviewWindow().listen<ReqwestEvent>('log', (event) => {
             store.addEvent(event.payload)
         })
         return () => {